@@ -0,0 +1,15 @@
+/**
+ * Renders `src/generated` from `idl/swap_program.json`.
+ *
+ * The IDL is written by the program itself (`yarn idl`), from the account
+ * layouts its adapters declare, so it is rendered here instead of through
+ * solita's shank generator.
+ */
+import path from 'path'
+import { Solita } from '@metaplex-foundation/solita'
+
+const idl = require('./idl/swap_program.json')
+
+new Solita(idl, { formatCode: true })
+  .renderAndWriteTo(path.join(__dirname, 'src', 'generated'))
+  .then(() => console.log('rendered src/generated'))
@@ -5,6 +5,7 @@ import {
     TransactionMessage,
     sendAndConfirmTransaction,
 } from '@solana/web3.js';
+import { getAssociatedTokenAddress } from '@solana/spl-token';
 import {
     PROGRAM_ID,
     createDLMMSwapInstruction,
@@ -16,10 +17,14 @@ import {BN} from 'bn.js';
 import {
     getOrCreateKeypair,
     ensureSufficientBalance,
+    ensureOperatorLimits,
     setupATAs,
     COMMON_TOKENS,
     COMMON_PROGRAM_IDS,
     createComputeUnitLimitInstruction,
+    getOperatorLimitsAddress,
+    getPnlLedgerAddress,
+    decodePnlLedger,
 } from './utils';
 
 // Connect to local testnet
@@ -33,15 +38,19 @@ async function testDLMMArbitrage() {
 
         // 2. Ensure sufficient SOL balance
         await ensureSufficientBalance(connection, payer, 10);
+        // The local wallet deployed swap_program, so it is the admin of its own limits
+        await ensureOperatorLimits(connection, payer);
 
         // 3. Setup token information
         const baseMint = COMMON_TOKENS.SOL; // SOL
         const testMint = new PublicKey('4eDf52YYzL6i6gbZ6FXqrLUPXbtP61f1gPSFM66M4XHe'); // Test token
 
-        // 4. Batch create ATAs
-        const ataMap = await setupATAs(connection, payer, [baseMint, testMint]);
+        // 4. Create the SOL ATA, swap_program creates the test token ATA if it is missing
+        const ataMap = await setupATAs(connection, payer, [baseMint]);
         const userBaseMintAcc = ataMap.get(baseMint.toBase58())!;
-        const userTestMintAcc = ataMap.get(testMint.toBase58())!;
+        // Token X may be a Token-2022 mint, the ATA and the CPI use the mint's owner
+        const mintTokenProgram = (await connection.getAccountInfo(testMint))!.owner;
+        const userTestMintAcc = await getAssociatedTokenAddress(testMint, payer.publicKey, false, mintTokenProgram);
 
         console.log("User SOL ATA:", userBaseMintAcc.toBase58());
         console.log("User test token ATA:", userTestMintAcc.toBase58());
@@ -81,6 +90,9 @@ async function testDLMMArbitrage() {
             bin1,
             bin2,
             bin3,
+            tokenXProgram: mintTokenProgram,
+            operatorLimits: getOperatorLimitsAddress(payer.publicKey),
+            pnlLedger: getPnlLedgerAddress(payer.publicKey),
         };
 
         // 7. Create instruction data
@@ -90,6 +102,14 @@ async function testDLMMArbitrage() {
                 maxBinToProcess: new BN(20), // Maximum number of bins to process
                 minProfitThreshold: new BN(10), // Minimum profit threshold
                 noFailure: true, // Do not allow failure
+                flags: 0, // Runtime log level, 0 = events only
+                maxPriceDeviationBps: 0, // 0 = no Pyth price account
+                wrapLamports: new BN(0), // 0 = base_mint_acc is already funded
+                slippageBps: 50, // limit derived from the on-chain quote, 0 = none
+                amountOut: new BN(0), // > 0 = swap_exact_out for this amount
+                maxPriceImpactBps: 0, // > 0 = swap_with_price_impact from activeId
+                activeId: 0,
+                priceFeedId: Array(32).fill(0), // Pyth feed id, checked with maxPriceDeviationBps
             },
         };
 
@@ -119,6 +139,13 @@ async function testDLMMArbitrage() {
         console.log(`✅ DLMM Swap Success: ${txSignature}`);
         console.log(`🔍 tx: https://solscan.io/tx/${txSignature}?cluster=custom&customUrl=http://127.0.0.1:8899/`);
 
+        // 10. Read the running PnL from the ledger
+        const ledgerAccount = await connection.getAccountInfo(accounts.pnlLedger);
+        if (ledgerAccount) {
+            const ledger = decodePnlLedger(ledgerAccount.data);
+            console.log(`📒 ${ledger.count} routes, total profit ${ledger.totalProfit}`);
+        }
+
     } catch (error) {
         console.error('❌ Error executing DLMM transaction:', error);
 
@@ -6,6 +6,7 @@ import {
     LAMPORTS_PER_SOL,
     sendAndConfirmTransaction,
 } from '@solana/web3.js';
+import { getAssociatedTokenAddress } from '@solana/spl-token';
 import {
     PROGRAM_ID,
     createPumpAMMSwapInstruction,
@@ -17,9 +18,12 @@ import { BN } from 'bn.js';
 import {
     getOrCreateKeypair,
     ensureSufficientBalance,
+    ensureOperatorLimits,
     setupATAs,
     COMMON_TOKENS,
     COMMON_PROGRAM_IDS, createComputeUnitLimitInstruction,
+    getOperatorLimitsAddress,
+    getPnlLedgerAddress,
 } from './utils';
 
 // Connect to local testnet
@@ -38,15 +42,19 @@ async function main() {
 
         // 2. Ensure sufficient SOL
         await ensureSufficientBalance(connection, payer, 10);
+        // The local wallet deployed swap_program, so it is the admin of its own limits
+        await ensureOperatorLimits(connection, payer);
 
         // 3. Setup token information
         const baseMint = COMMON_TOKENS.SOL; // Native SOL wrapped token
         const testMint = new PublicKey('FtTSDNLD5mMLn3anqEQpy44cRdrtAJRrLX2MKXxfpump'); // Test token
 
-        // 4. Batch create ATAs
-        const ataMap = await setupATAs(connection, payer, [baseMint, testMint]);
+        // 4. Create the SOL ATA, swap_program creates the test token ATA if it is missing
+        const ataMap = await setupATAs(connection, payer, [baseMint]);
         const userBaseMintAcc = ataMap.get(baseMint.toBase58())!;
-        const userTestMintAcc = ataMap.get(testMint.toBase58())!;
+        // Pump tokens may be Token-2022 mints, the ATA and the CPI use the mint's owner
+        const mintTokenProgram = (await connection.getAccountInfo(testMint))!.owner;
+        const userTestMintAcc = await getAssociatedTokenAddress(testMint, payer.publicKey, false, mintTokenProgram);
 
         console.log("User SOL ATA:", userBaseMintAcc.toBase58());
         console.log("User test token ATA:", userTestMintAcc.toBase58());
@@ -86,6 +94,9 @@ async function main() {
             protocolFeeRecipientTokenAcc,
             coinCreatorVaultAta,
             coinCreatorVaultAuthority,
+            mintTokenProgram,
+            operatorLimits: getOperatorLimitsAddress(payer.publicKey),
+            pnlLedger: getPnlLedgerAddress(payer.publicKey),
         };
 
         // 6. Create instruction data
@@ -95,6 +106,14 @@ async function main() {
                 maxBinToProcess: new BN(20),
                 minProfitThreshold: new BN(10),
                 noFailure: true,
+                flags: 0,
+                maxPriceDeviationBps: 0,
+                wrapLamports: new BN(0),
+                slippageBps: 0,
+                amountOut: new BN(0),
+                maxPriceImpactBps: 0,
+                activeId: 0,
+                priceFeedId: Array(32).fill(0),
             },
         };
 
@@ -1,4 +1,5 @@
 import { PublicKey } from '@solana/web3.js'
+export * from './errors'
 export * from './instructions'
 export * from './types'
 
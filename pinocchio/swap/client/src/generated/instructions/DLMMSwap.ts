@@ -54,6 +54,11 @@ export const DLMMSwapStruct = new beet.BeetArgsStruct<
  * @property [_writable_] bin1
  * @property [_writable_] bin2
  * @property [_writable_] bin3
+ * @property [] tokenXProgram
+ * @property [_writable_] operatorLimits
+ * @property [_writable_] pnlLedger
+ * @property [] priceUpdate (optional)
+ * @property [] instructionsSysvar (optional)
  * @category Instructions
  * @category DLMMSwap
  * @category generated
@@ -77,9 +82,14 @@ export type DLMMSwapInstructionAccounts = {
   bin1: web3.PublicKey
   bin2: web3.PublicKey
   bin3: web3.PublicKey
+  tokenXProgram: web3.PublicKey
+  operatorLimits: web3.PublicKey
+  pnlLedger: web3.PublicKey
+  priceUpdate?: web3.PublicKey
+  instructionsSysvar?: web3.PublicKey
 }
 
-export const dLMMSwapInstructionDiscriminator = 1
+export const dLMMSwapInstructionDiscriminator = 0
 
 /**
  * Creates a _DLMMSwap_ instruction.
@@ -191,8 +201,38 @@ export function createDLMMSwapInstruction(
       isWritable: true,
       isSigner: false,
     },
+    {
+      pubkey: accounts.tokenXProgram,
+      isWritable: false,
+      isSigner: false,
+    },
+    {
+      pubkey: accounts.operatorLimits,
+      isWritable: true,
+      isSigner: false,
+    },
+    {
+      pubkey: accounts.pnlLedger,
+      isWritable: true,
+      isSigner: false,
+    },
   ]
 
+  if (accounts.priceUpdate != null) {
+    keys.push({
+      pubkey: accounts.priceUpdate,
+      isWritable: false,
+      isSigner: false,
+    })
+  }
+  if (accounts.instructionsSysvar != null) {
+    keys.push({
+      pubkey: accounts.instructionsSysvar,
+      isWritable: false,
+      isSigner: false,
+    })
+  }
+
   const ix = new web3.TransactionInstruction({
     programId,
     keys,
@@ -55,6 +55,11 @@ export const PumpAMMSwapStruct = new beet.BeetArgsStruct<
  * @property [_writable_] protocolFeeRecipientTokenAcc
  * @property [_writable_] coinCreatorVaultAta
  * @property [] coinCreatorVaultAuthority
+ * @property [] mintTokenProgram
+ * @property [_writable_] operatorLimits
+ * @property [_writable_] pnlLedger
+ * @property [] priceUpdate (optional)
+ * @property [] instructionsSysvar (optional)
  * @category Instructions
  * @category PumpAMMSwap
  * @category generated
@@ -79,6 +84,11 @@ export type PumpAMMSwapInstructionAccounts = {
   protocolFeeRecipientTokenAcc: web3.PublicKey
   coinCreatorVaultAta: web3.PublicKey
   coinCreatorVaultAuthority: web3.PublicKey
+  mintTokenProgram: web3.PublicKey
+  operatorLimits: web3.PublicKey
+  pnlLedger: web3.PublicKey
+  priceUpdate?: web3.PublicKey
+  instructionsSysvar?: web3.PublicKey
 }
 
 export const pumpAMMSwapInstructionDiscriminator = 0
@@ -198,8 +208,38 @@ export function createPumpAMMSwapInstruction(
       isWritable: false,
       isSigner: false,
     },
+    {
+      pubkey: accounts.mintTokenProgram,
+      isWritable: false,
+      isSigner: false,
+    },
+    {
+      pubkey: accounts.operatorLimits,
+      isWritable: true,
+      isSigner: false,
+    },
+    {
+      pubkey: accounts.pnlLedger,
+      isWritable: true,
+      isSigner: false,
+    },
   ]
 
+  if (accounts.priceUpdate != null) {
+    keys.push({
+      pubkey: accounts.priceUpdate,
+      isWritable: false,
+      isSigner: false,
+    })
+  }
+  if (accounts.instructionsSysvar != null) {
+    keys.push({
+      pubkey: accounts.instructionsSysvar,
+      isWritable: false,
+      isSigner: false,
+    })
+  }
+
   const ix = new web3.TransactionInstruction({
     programId,
     keys,
@@ -1,2 +1,7 @@
+export * from './DLMMQuote'
 export * from './DLMMSwap'
+export * from './DLMMToPumpAMMRoundTrip'
+export * from './PumpAMMQuote'
 export * from './PumpAMMSwap'
+export * from './PumpAMMToDLMMRoundTrip'
+export * from './SetOperatorLimits'
@@ -12,6 +12,14 @@ export type ArbitrageIxData = {
   maxBinToProcess: beet.bignum
   minProfitThreshold: beet.bignum
   noFailure: boolean
+  flags: number
+  maxPriceDeviationBps: number
+  wrapLamports: beet.bignum
+  slippageBps: number
+  amountOut: beet.bignum
+  maxPriceImpactBps: number
+  activeId: number
+  priceFeedId: number[] /* size: 32 */
 }
 
 /**
@@ -24,6 +32,14 @@ export const arbitrageIxDataBeet = new beet.BeetArgsStruct<ArbitrageIxData>(
     ['maxBinToProcess', beet.u64],
     ['minProfitThreshold', beet.u64],
     ['noFailure', beet.bool],
+    ['flags', beet.u8],
+    ['maxPriceDeviationBps', beet.u16],
+    ['wrapLamports', beet.u64],
+    ['slippageBps', beet.u16],
+    ['amountOut', beet.u64],
+    ['maxPriceImpactBps', beet.u16],
+    ['activeId', beet.i32],
+    ['priceFeedId', beet.uniformFixedSizeArray(beet.u8, 32)],
   ],
   'ArbitrageIxData'
 )
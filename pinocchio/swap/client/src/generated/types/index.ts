@@ -1,2 +1,5 @@
 export * from './ArbitrageIxData'
+export * from './OperatorLimitsIxData'
+export * from './QuoteIxData'
+export * from './RoundTripIxData'
 export * from './SupportDex'
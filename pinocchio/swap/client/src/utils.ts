@@ -19,6 +19,7 @@ import {
     createCloseAccountInstruction,
 } from '@solana/spl-token';
 import * as fs from 'fs';
+import { PROGRAM_ID, createSetOperatorLimitsInstruction } from './generated';
 
 /**
  * Load or create new keypair from file
@@ -304,6 +305,141 @@ export async function unwrapSOLFromATA(
     }
 }
 
+/**
+ * Operator limits PDA of a swap signer, required by every swap after the leg accounts
+ * @param operator Signer of the swaps
+ * @returns Limits account address, swaps fail until the admin creates it
+ */
+export function getOperatorLimitsAddress(operator: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from('operator_limits'), operator.toBuffer()],
+        PROGRAM_ID
+    )[0];
+}
+
+/**
+ * Programdata account of swap_program, names the upgrade authority that creates operator limits
+ * @returns Programdata account address
+ */
+export function getProgramDataAddress(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+        [PROGRAM_ID.toBuffer()],
+        new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+    )[0];
+}
+
+/**
+ * Create the limits PDA of an operator without limits if it does not exist yet
+ * @param connection Solana connection object
+ * @param admin Upgrade authority of swap_program
+ * @param operator Signer of the swaps (defaults to admin)
+ */
+export async function ensureOperatorLimits(
+    connection: Connection,
+    admin: Keypair,
+    operator: Keypair = admin
+): Promise<void> {
+    const operatorLimits = getOperatorLimitsAddress(operator.publicKey);
+    const account = await connection.getAccountInfo(operatorLimits);
+    if (account && account.data.length > 0) {
+        return;
+    }
+
+    const ix = createSetOperatorLimitsInstruction(
+        {
+            adminAcc: admin.publicKey,
+            operatorAcc: operator.publicKey,
+            operatorLimits,
+            systemProgramId: SystemProgram.programId,
+            programData: getProgramDataAddress(),
+        },
+        {
+            operatorLimitsIxData: {
+                maxNotionalPerTrade: 0,
+                maxWindowNotional: 0,
+                windowSlots: 0,
+                maxTradesPerSlot: 0,
+            },
+        }
+    );
+    ix.data = Buffer.from([2, ...ix.data.slice(1)]);
+    ix.keys[1].isSigner = true;
+
+    const transaction = new Transaction().add(ix);
+    const signers = admin.publicKey.equals(operator.publicKey) ? [admin] : [admin, operator];
+    const signature = await connection.sendTransaction(transaction, signers, {
+        preflightCommitment: 'confirmed'
+    });
+    await connection.confirmTransaction(signature);
+    console.log(`Created operator limits ${operatorLimits.toBase58()}: ${signature}`);
+}
+
+/**
+ * PnL ledger PDA of a swap signer, created by its first swap
+ * @param operator Signer of the swaps
+ * @returns Ledger account address
+ */
+export function getPnlLedgerAddress(operator: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from('pnl_ledger'), operator.toBuffer()],
+        PROGRAM_ID
+    )[0];
+}
+
+export type PnlEntry = {
+    slot: bigint;
+    amountIn: bigint;
+    amountOut: bigint;
+    profit: bigint;
+    dex: number;
+    legs: number;
+};
+
+export type PnlLedger = {
+    operator: PublicKey;
+    count: bigint;
+    totalAmountIn: bigint;
+    totalAmountOut: bigint;
+    totalProfit: bigint;
+    lastSlot: bigint;
+    entries: PnlEntry[];
+};
+
+const PNL_LEDGER_CAPACITY = 64;
+const PNL_LEDGER_HEADER_LEN = 80;
+const PNL_ENTRY_LEN = 40;
+
+/**
+ * Decode a PnL ledger account, entries from oldest to newest
+ * @param data Account data of the ledger
+ */
+export function decodePnlLedger(data: Buffer): PnlLedger {
+    const count = data.readBigUInt64LE(32);
+    const len = Number(count < BigInt(PNL_LEDGER_CAPACITY) ? count : BigInt(PNL_LEDGER_CAPACITY));
+    const start = Number((count - BigInt(len)) % BigInt(PNL_LEDGER_CAPACITY));
+    const entries: PnlEntry[] = [];
+    for (let i = 0; i < len; i++) {
+        const offset = PNL_LEDGER_HEADER_LEN + ((start + i) % PNL_LEDGER_CAPACITY) * PNL_ENTRY_LEN;
+        entries.push({
+            slot: data.readBigUInt64LE(offset),
+            amountIn: data.readBigUInt64LE(offset + 8),
+            amountOut: data.readBigUInt64LE(offset + 16),
+            profit: data.readBigInt64LE(offset + 24),
+            dex: data.readUInt8(offset + 32),
+            legs: data.readUInt8(offset + 33),
+        });
+    }
+    return {
+        operator: new PublicKey(data.subarray(0, 32)),
+        count,
+        totalAmountIn: data.readBigUInt64LE(40),
+        totalAmountOut: data.readBigUInt64LE(48),
+        totalProfit: data.readBigInt64LE(56),
+        lastSlot: data.readBigUInt64LE(64),
+        entries,
+    };
+}
+
 /**
  * Create compute unit limit instruction
  * @param units Compute unit limit amount
@@ -0,0 +1,83 @@
+//! 指令首字节与指令数据的解析
+//!
+//! Every accepted buffer must hold valid enum and bool bytes, and read back
+//! exactly as it was given.
+#![no_main]
+
+use core::mem::offset_of;
+
+use libfuzzer_sys::fuzz_target;
+use swap_program::instruction::{
+    ArbitrageIxData, LossProgramInstruction, OperatorLimitsIxData, QuoteIxData, RoundTripIxData,
+    SupportDex,
+};
+use swap_program::slippage::MAX_SLIPPAGE_BPS;
+use swap_program::state::{load_ix_data, to_bytes, DataLen};
+
+const NO_FAILURE: usize = offset_of!(ArbitrageIxData, no_failure);
+const SLIPPAGE_BPS: usize = offset_of!(ArbitrageIxData, slippage_bps);
+const AMOUNT_OUT: usize = offset_of!(ArbitrageIxData, amount_out);
+const MAX_PRICE_IMPACT_BPS: usize = offset_of!(ArbitrageIxData, max_price_impact_bps);
+const AMOUNT_IN: usize = offset_of!(RoundTripIxData, amount_in);
+const FLASH_LOAN_AMOUNT: usize = offset_of!(RoundTripIxData, flash_loan_amount);
+const ROUND_TRIP_NO_FAILURE: usize = offset_of!(RoundTripIxData, no_failure);
+
+fuzz_target!(|data: &[u8]| {
+    let Some(first) = data.first() else {
+        return;
+    };
+    let instruction = LossProgramInstruction::try_from(first);
+    assert_eq!(instruction.is_ok(), *first <= 3);
+    let dex = SupportDex::try_from(first);
+    assert_eq!(dex.is_ok(), *first <= 6);
+
+    // SAFETY: the ix data structs are `repr(C, packed)`, `validate` rejects their invalid bytes.
+    let arbitrage = unsafe { load_ix_data::<ArbitrageIxData>(data) };
+    let valid = data.len() == ArbitrageIxData::LEN
+        && dex.is_ok()
+        && data[NO_FAILURE] <= 1
+        && u16::from_le_bytes([data[SLIPPAGE_BPS], data[SLIPPAGE_BPS + 1]]) <= MAX_SLIPPAGE_BPS
+        && (data[MAX_PRICE_IMPACT_BPS..MAX_PRICE_IMPACT_BPS + 2] == [0, 0]
+            || (data[0] == SupportDex::DLMM as u8 && data[AMOUNT_OUT..AMOUNT_OUT + 8] == [0; 8]));
+    assert_eq!(arbitrage.is_ok(), valid);
+    if let Ok(ix_data) = arbitrage {
+        assert_eq!(ix_data.dex as u8, data[0]);
+        assert_eq!(ix_data.no_failure as u8, data[NO_FAILURE]);
+        assert_eq!(unsafe { to_bytes(ix_data) }, data);
+    }
+
+    let quote = unsafe { load_ix_data::<QuoteIxData>(data) };
+    assert_eq!(quote.is_ok(), data.len() == QuoteIxData::LEN && dex.is_ok());
+    if let Ok(ix_data) = quote {
+        assert_eq!(ix_data.dex as u8, data[0]);
+        assert_eq!(unsafe { to_bytes(ix_data) }, data);
+    }
+
+    // a round trip needs two different DEXes among Pump AMM and DLMM, and spends
+    // either `amount_in` or the flash loan
+    let round_trip = unsafe { load_ix_data::<RoundTripIxData>(data) };
+    let round_trip_dex =
+        |byte: u8| byte == SupportDex::PumpAmm as u8 || byte == SupportDex::DLMM as u8;
+    assert_eq!(
+        round_trip.is_ok(),
+        data.len() == RoundTripIxData::LEN
+            && round_trip_dex(data[0])
+            && round_trip_dex(data[1])
+            && data[0] != data[1]
+            && (data[AMOUNT_IN..AMOUNT_IN + 8] == [0; 8]
+                || data[FLASH_LOAN_AMOUNT..FLASH_LOAN_AMOUNT + 8] == [0; 8])
+            && data[ROUND_TRIP_NO_FAILURE] <= 1
+    );
+    if let Ok(ix_data) = round_trip {
+        assert_eq!(ix_data.buy_dex as u8, data[0]);
+        assert_eq!(ix_data.sell_dex as u8, data[1]);
+        assert_eq!(ix_data.no_failure as u8, data[ROUND_TRIP_NO_FAILURE]);
+        assert_eq!(unsafe { to_bytes(ix_data) }, data);
+    }
+
+    let limits = unsafe { load_ix_data::<OperatorLimitsIxData>(data) };
+    assert_eq!(limits.is_ok(), data.len() == OperatorLimitsIxData::LEN);
+    if let Ok(ix_data) = limits {
+        assert_eq!(unsafe { to_bytes(ix_data) }, data);
+    }
+});
@@ -0,0 +1,109 @@
+//! 任意账户与指令数据经 runtime 的输入格式进入 `process_instruction`
+//!
+//! The accounts are serialized the way the runtime lays them out and parsed
+//! back with pinocchio's `deserialize`, so the program sees the same
+//! `AccountInfo`s as on chain. Sysvars and CPIs are unavailable on the host:
+//! `Clock::get` fails and an instruction stops there with an error.
+#![no_main]
+
+use core::mem::MaybeUninit;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use pinocchio::{
+    account_info::{AccountInfo, MAX_PERMITTED_DATA_INCREASE},
+    entrypoint::deserialize,
+    pubkey::Pubkey,
+    MAX_TX_ACCOUNTS,
+};
+use swap_program::instruction::process_instruction;
+use swap_program::oracle::PYTH_RECEIVER_PROGRAM_ID;
+use swap_program::wsol::NATIVE_MINT;
+
+/// `borrow_state` of an account serialized in full, a duplicate holds the index of the original.
+const NON_DUP_MARKER: u8 = u8::MAX;
+
+#[derive(Arbitrary, Debug)]
+struct FuzzAccount {
+    /// 指向之前某个账户时按重复账户序列化
+    duplicate_of: Option<u8>,
+    key: u8,
+    owner: u8,
+    is_signer: bool,
+    is_writable: bool,
+    executable: bool,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzInput {
+    accounts: Vec<FuzzAccount>,
+    instruction_data: Vec<u8>,
+}
+
+/// A small key set, so that owners and account keys match each other often.
+fn pubkey(index: u8) -> Pubkey {
+    match index % 16 {
+        0 => swap_program::ID,
+        1 => pinocchio_token::ID,
+        2 => pinocchio_system::ID,
+        3 => NATIVE_MINT,
+        4 => PYTH_RECEIVER_PROGRAM_ID,
+        n => [n; 32],
+    }
+}
+
+/// Serializes the input as the runtime does, into a buffer aligned for its `u64`s.
+fn serialize(input: &FuzzInput) -> Vec<u64> {
+    let accounts = &input.accounts[..input.accounts.len().min(MAX_TX_ACCOUNTS)];
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+    for (i, account) in accounts.iter().enumerate() {
+        match account.duplicate_of.filter(|&index| (index as usize) < i) {
+            Some(index) => {
+                bytes.push(index);
+                bytes.extend_from_slice(&[0; 7]);
+            }
+            None => {
+                bytes.push(NON_DUP_MARKER);
+                bytes.push(account.is_signer as u8);
+                bytes.push(account.is_writable as u8);
+                bytes.push(account.executable as u8);
+                bytes.extend_from_slice(&(account.data.len() as u32).to_le_bytes());
+                bytes.extend_from_slice(&pubkey(account.key));
+                bytes.extend_from_slice(&pubkey(account.owner));
+                bytes.extend_from_slice(&account.lamports.to_le_bytes());
+                bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+                bytes.extend_from_slice(&account.data);
+                bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+                bytes.resize(bytes.len().next_multiple_of(8), 0);
+                // rent_epoch
+                bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+            }
+        }
+    }
+    bytes.extend_from_slice(&(input.instruction_data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&input.instruction_data);
+    bytes.extend_from_slice(&swap_program::ID);
+
+    let mut buffer = vec![0u64; bytes.len().div_ceil(8)];
+    // SAFETY: `buffer` holds at least `bytes.len()` bytes.
+    unsafe {
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.as_mut_ptr() as *mut u8, bytes.len())
+    };
+    buffer
+}
+
+fuzz_target!(|input: FuzzInput| {
+    let mut buffer = serialize(&input);
+    let mut accounts = [const { MaybeUninit::<AccountInfo>::uninit() }; MAX_TX_ACCOUNTS];
+    // SAFETY: `buffer` is laid out as the runtime input, with at most `MAX_TX_ACCOUNTS` accounts.
+    let (program_id, count, instruction_data) =
+        unsafe { deserialize::<MAX_TX_ACCOUNTS>(buffer.as_mut_ptr() as *mut u8, &mut accounts) };
+    // SAFETY: `deserialize` initialized the first `count` accounts.
+    let accounts =
+        unsafe { core::slice::from_raw_parts(accounts.as_ptr() as *const AccountInfo, count) };
+
+    let _ = process_instruction(program_id, accounts, instruction_data);
+});
@@ -0,0 +1,18 @@
+#![allow(unexpected_cfgs)]
+
+use pinocchio::{
+    account_info::AccountInfo, default_panic_handler, no_allocator, program_entrypoint,
+    pubkey::Pubkey, ProgramResult,
+};
+
+program_entrypoint!(process_instruction);
+no_allocator!();
+default_panic_handler!();
+
+fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    crate::forward(accounts, instruction_data)
+}
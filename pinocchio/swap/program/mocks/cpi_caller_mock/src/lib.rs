@@ -0,0 +1,48 @@
+//! Mock of a program that calls swap_program through CPI.
+//!
+//! It forwards its instruction data unchanged to the program in its first
+//! account, with the remaining accounts in order, the way a router or a
+//! hostile DEX calling back would.
+#![no_std]
+
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::slice_invoke,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+/// Most accounts forwarded in one call.
+pub const MAX_FORWARDED_ACCOUNTS: usize = 32;
+
+pub fn forward(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [program, forwarded @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if forwarded.len() > MAX_FORWARDED_ACCOUNTS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let account = |i: usize| forwarded.get(i).unwrap_or(program);
+    let metas: [AccountMeta; MAX_FORWARDED_ACCOUNTS] = core::array::from_fn(|i| {
+        let account = account(i);
+        AccountMeta::new(account.key(), account.is_writable(), account.is_signer())
+    });
+    let infos: [&AccountInfo; MAX_FORWARDED_ACCOUNTS] = core::array::from_fn(account);
+
+    slice_invoke(
+        &Instruction {
+            program_id: program.key(),
+            accounts: &metas[..forwarded.len()],
+            data,
+        },
+        &infos[..forwarded.len()],
+    )
+}
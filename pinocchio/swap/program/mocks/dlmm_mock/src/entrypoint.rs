@@ -0,0 +1,70 @@
+#![allow(unexpected_cfgs)]
+
+use pinocchio::{
+    account_info::AccountInfo, default_panic_handler, no_allocator, program_entrypoint,
+    program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    swap, SWAP_DISCRIMINATOR, SWAP_EXACT_OUT_DISCRIMINATOR, SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR,
+};
+
+program_entrypoint!(process_instruction);
+no_allocator!();
+default_panic_handler!();
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    mock_utils::record(instruction_data, accounts);
+
+    if instruction_data.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (discriminator, args) = instruction_data.split_at(8);
+    if discriminator == SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR {
+        let (amount_in, active_id, max_price_impact_bps) =
+            price_impact_args(args).ok_or(ProgramError::InvalidInstructionData)?;
+        return swap::swap_with_price_impact(
+            program_id,
+            accounts,
+            amount_in,
+            active_id,
+            max_price_impact_bps,
+        );
+    }
+    if args.len() != 16 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount = u64::from_le_bytes(args[0..8].try_into().unwrap());
+    let limit = u64::from_le_bytes(args[8..16].try_into().unwrap());
+
+    match discriminator {
+        d if d == SWAP_DISCRIMINATOR => swap::swap(program_id, accounts, amount, limit),
+        d if d == SWAP_EXACT_OUT_DISCRIMINATOR => {
+            swap::swap_exact_out(program_id, accounts, amount, limit)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Borsh encoded `amount_in: u64, active_id: Option<i32>, max_price_impact_bps: u16`.
+fn price_impact_args(args: &[u8]) -> Option<(u64, Option<i32>, u16)> {
+    let (amount_in, args) = args.split_first_chunk::<8>()?;
+    let (active_id, args) = match args.split_first()? {
+        (0, args) => (None, args),
+        (1, args) => {
+            let (active_id, args) = args.split_first_chunk::<4>()?;
+            (Some(i32::from_le_bytes(*active_id)), args)
+        }
+        _ => return None,
+    };
+    let max_price_impact_bps: [u8; 2] = args.try_into().ok()?;
+    Some((
+        u64::from_le_bytes(*amount_in),
+        active_id,
+        u16::from_le_bytes(max_price_impact_bps),
+    ))
+}
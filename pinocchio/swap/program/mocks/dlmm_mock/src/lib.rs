@@ -0,0 +1,116 @@
+//! Mock of the Meteora DLMM `swap`, `swap_exact_out` and `swap_with_price_impact`
+//! instructions.
+//!
+//! It takes the same discriminator and account order as DLMM, checks every
+//! account against its slot, records what it received and trades on a
+//! constant product curve between the pair reserves instead of walking bins.
+#![no_std]
+
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod swap;
+
+/// Must match `swap_program::dex::SWAP_DISCRIMINATOR`.
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+/// Must match `swap_program::dex::SWAP_EXACT_OUT_DISCRIMINATOR`.
+pub const SWAP_EXACT_OUT_DISCRIMINATOR: [u8; 8] = [250, 73, 101, 33, 38, 207, 75, 184];
+/// Must match `swap_program::dex::SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR`.
+pub const SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR: [u8; 8] = [56, 173, 230, 208, 173, 228, 156, 205];
+
+/// Total fee charged on the input amount, it stays in the input reserve.
+pub const FEE_BPS: u64 = 25;
+
+pub const LB_PAIR_SEED: &[u8] = b"lb_pair";
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Pair account of the mock, a PDA of `[LB_PAIR_SEED, token_x_mint, token_y_mint]`
+/// that owns both reserves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MockLbPair {
+    pub token_x_mint: [u8; 32],
+    pub token_y_mint: [u8; 32],
+    pub reserve_x: [u8; 32],
+    pub reserve_y: [u8; 32],
+    pub oracle: [u8; 32],
+    pub bump: u8,
+}
+
+impl MockLbPair {
+    pub const LEN: usize = 32 * 5 + 1;
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN {
+            return None;
+        }
+        let key = |i: usize| data[i * 32..(i + 1) * 32].try_into().unwrap();
+        Some(Self {
+            token_x_mint: key(0),
+            token_y_mint: key(1),
+            reserve_x: key(2),
+            reserve_y: key(3),
+            oracle: key(4),
+            bump: data[160],
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        for (i, key) in [
+            &self.token_x_mint,
+            &self.token_y_mint,
+            &self.reserve_x,
+            &self.reserve_y,
+            &self.oracle,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            data[i * 32..(i + 1) * 32].copy_from_slice(key);
+        }
+        data[160] = self.bump;
+        data
+    }
+}
+
+/// Output and fee of swapping exactly `amount_in`.
+pub fn quote_swap(reserve_in: u64, reserve_out: u64, amount_in: u64) -> Option<(u64, u64)> {
+    let fee = mock_utils::fee(amount_in, FEE_BPS)?;
+    let amount_out = mock_utils::amount_out(reserve_in, reserve_out, amount_in.checked_sub(fee)?)?;
+    Some((amount_out, fee))
+}
+
+/// Input and fee of swapping for exactly `amount_out`.
+pub fn quote_swap_exact_out(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_out: u64,
+) -> Option<(u64, u64)> {
+    let amount_in_after_fee = mock_utils::amount_in(reserve_in, reserve_out, amount_out)?;
+    // fee charged on the input, so that `amount_in_after_fee` remains
+    let amount_in = u64::try_from(
+        (amount_in_after_fee as u128 * mock_utils::BPS_DENOMINATOR as u128)
+            .div_ceil((mock_utils::BPS_DENOMINATOR - FEE_BPS) as u128),
+    )
+    .ok()?;
+    Some((amount_in, amount_in - amount_in_after_fee))
+}
+
+/// Shortfall of `amount_out` against the pre-swap price of `amount_in_after_fee`, in bps.
+pub fn price_impact_bps(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in_after_fee: u64,
+    amount_out: u64,
+) -> Option<u64> {
+    let spot_amount_out =
+        (amount_in_after_fee as u128 * reserve_out as u128).checked_div(reserve_in as u128)?;
+    if spot_amount_out == 0 {
+        return Some(0);
+    }
+    let shortfall = spot_amount_out.saturating_sub(amount_out as u128);
+    u64::try_from(shortfall * mock_utils::BPS_DENOMINATOR as u128 / spot_amount_out).ok()
+}
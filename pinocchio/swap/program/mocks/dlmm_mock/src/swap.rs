@@ -0,0 +1,190 @@
+use mock_utils::{
+    check_key, check_token_account, check_token_program, check_writable, transfer, wrong_account,
+    INSUFFICIENT_LIQUIDITY, SLIPPAGE_EXCEEDED,
+};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{find_program_address, Pubkey},
+    ProgramResult,
+};
+
+use crate::{
+    price_impact_bps, quote_swap, quote_swap_exact_out, MockLbPair, EVENT_AUTHORITY_SEED,
+    LB_PAIR_SEED,
+};
+
+/// Index of the first bin array, the remaining accounts of `swap`.
+const BIN_ARRAYS_SLOT: usize = 15;
+
+/// Swaps exactly `amount_in` of the mint held by `user_token_in`, receiving at
+/// least `min_amount_out`.
+pub fn swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_amount_out: u64,
+) -> ProgramResult {
+    trade(program_id, accounts, |reserve_in, reserve_out| {
+        let (amount_out, _fee) = quote_swap(reserve_in, reserve_out, amount_in)
+            .filter(|(amount_out, _)| *amount_out < reserve_out)
+            .ok_or(INSUFFICIENT_LIQUIDITY)?;
+        if amount_out < min_amount_out {
+            return Err(SLIPPAGE_EXCEEDED);
+        }
+        Ok((amount_in, amount_out))
+    })
+}
+
+/// Swaps at most `max_in_amount` of the mint held by `user_token_in` for
+/// exactly `out_amount`.
+pub fn swap_exact_out(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_in_amount: u64,
+    out_amount: u64,
+) -> ProgramResult {
+    trade(program_id, accounts, |reserve_in, reserve_out| {
+        let (amount_in, _fee) = quote_swap_exact_out(reserve_in, reserve_out, out_amount)
+            .ok_or(INSUFFICIENT_LIQUIDITY)?;
+        if amount_in > max_in_amount {
+            return Err(SLIPPAGE_EXCEEDED);
+        }
+        Ok((amount_in, out_amount))
+    })
+}
+
+/// Swaps exactly `amount_in` as long as the price moves by at most
+/// `max_price_impact_bps`. The mock has no bins, so `active_id` is only
+/// recorded and the impact is measured against the reserves before the swap.
+pub fn swap_with_price_impact(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    _active_id: Option<i32>,
+    max_price_impact_bps: u16,
+) -> ProgramResult {
+    trade(program_id, accounts, |reserve_in, reserve_out| {
+        let (amount_out, fee) = quote_swap(reserve_in, reserve_out, amount_in)
+            .filter(|(amount_out, _)| *amount_out < reserve_out)
+            .ok_or(INSUFFICIENT_LIQUIDITY)?;
+        let impact = price_impact_bps(reserve_in, reserve_out, amount_in - fee, amount_out)
+            .ok_or(INSUFFICIENT_LIQUIDITY)?;
+        if impact > max_price_impact_bps as u64 {
+            return Err(SLIPPAGE_EXCEEDED);
+        }
+        Ok((amount_in, amount_out))
+    })
+}
+
+/// Checks the accounts of a swap and moves the `(amount_in, amount_out)`
+/// returned by `quote` for the reserves of the input and output mints.
+fn trade(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    quote: impl FnOnce(u64, u64) -> Result<(u64, u64), ProgramError>,
+) -> ProgramResult {
+    let [lb_pair_acc, bin_array_bitmap_extension, reserve_x, reserve_y, user_token_in, user_token_out, token_x_mint, token_y_mint, oracle, host_fee_in, user, token_x_program, token_y_program, event_authority, program, bin_arrays @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !lb_pair_acc.is_owned_by(program_id) || !lb_pair_acc.is_writable() {
+        return Err(wrong_account(0));
+    }
+    let lb_pair =
+        MockLbPair::from_bytes(&lb_pair_acc.try_borrow_data()?).ok_or(wrong_account(0))?;
+
+    // Optional accounts are passed as the program id when absent.
+    check_key(bin_array_bitmap_extension, program_id, 1)?;
+    check_key(reserve_x, &lb_pair.reserve_x, 2)?;
+    let reserve_x_amount =
+        check_token_account(reserve_x, &lb_pair.token_x_mint, lb_pair_acc.key(), 2)?;
+    check_key(reserve_y, &lb_pair.reserve_y, 3)?;
+    let reserve_y_amount =
+        check_token_account(reserve_y, &lb_pair.token_y_mint, lb_pair_acc.key(), 3)?;
+    check_key(token_x_mint, &lb_pair.token_x_mint, 6)?;
+    check_key(token_y_mint, &lb_pair.token_y_mint, 7)?;
+    check_key(oracle, &lb_pair.oracle, 8)?;
+    check_writable(oracle, 8)?;
+    check_key(host_fee_in, program_id, 9)?;
+    if !user.is_signer() {
+        return Err(wrong_account(10));
+    }
+    check_token_program(token_x_program, token_x_mint, 11)?;
+    check_token_program(token_y_program, token_y_mint, 12)?;
+    check_key(
+        event_authority,
+        &find_program_address(&[EVENT_AUTHORITY_SEED], program_id).0,
+        13,
+    )?;
+    check_key(program, program_id, 14)?;
+
+    if bin_arrays.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    for (i, bin_array) in bin_arrays.iter().enumerate() {
+        if !bin_array.is_owned_by(program_id) || !bin_array.is_writable() {
+            return Err(wrong_account(BIN_ARRAYS_SLOT + i));
+        }
+    }
+
+    // The direction follows the mint of the input account.
+    let swap_for_y = mock_utils::token_account(user_token_in, 4)?.mint == lb_pair.token_x_mint;
+    let (reserve_in, reserve_out, reserve_in_amount, reserve_out_amount, mint_in, mint_out) =
+        if swap_for_y {
+            (
+                reserve_x,
+                reserve_y,
+                reserve_x_amount,
+                reserve_y_amount,
+                &lb_pair.token_x_mint,
+                &lb_pair.token_y_mint,
+            )
+        } else {
+            (
+                reserve_y,
+                reserve_x,
+                reserve_y_amount,
+                reserve_x_amount,
+                &lb_pair.token_y_mint,
+                &lb_pair.token_x_mint,
+            )
+        };
+    let (token_program_in, token_program_out) = if swap_for_y {
+        (token_x_program, token_y_program)
+    } else {
+        (token_y_program, token_x_program)
+    };
+    check_token_account(user_token_in, mint_in, user.key(), 4)?;
+    check_token_account(user_token_out, mint_out, user.key(), 5)?;
+
+    let (amount_in, amount_out) = quote(reserve_in_amount, reserve_out_amount)?;
+
+    transfer(
+        token_program_in,
+        user_token_in,
+        reserve_in,
+        user,
+        amount_in,
+        &[],
+    )?;
+
+    let bump = [lb_pair.bump];
+    let seeds = [
+        Seed::from(LB_PAIR_SEED),
+        Seed::from(&lb_pair.token_x_mint),
+        Seed::from(&lb_pair.token_y_mint),
+        Seed::from(&bump),
+    ];
+    transfer(
+        token_program_out,
+        reserve_out,
+        user_token_out,
+        lb_pair_acc,
+        amount_out,
+        &[Signer::from(&seeds)],
+    )
+}
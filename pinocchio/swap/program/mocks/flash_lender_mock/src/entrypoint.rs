@@ -0,0 +1,37 @@
+#![allow(unexpected_cfgs)]
+
+use pinocchio::{
+    account_info::AccountInfo, default_panic_handler, no_allocator, program_entrypoint,
+    program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{loan, BORROW_DISCRIMINATOR, REPAY_DISCRIMINATOR};
+
+program_entrypoint!(process_instruction);
+no_allocator!();
+default_panic_handler!();
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    mock_utils::record(instruction_data, accounts);
+
+    if instruction_data.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (discriminator, args) = instruction_data.split_at(8);
+
+    match discriminator {
+        d if d == BORROW_DISCRIMINATOR => {
+            let amount = args
+                .try_into()
+                .map(u64::from_le_bytes)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            loan::borrow(program_id, accounts, amount)
+        }
+        d if d == REPAY_DISCRIMINATOR && args.is_empty() => loan::repay(program_id, accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
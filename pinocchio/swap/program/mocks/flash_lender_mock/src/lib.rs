@@ -0,0 +1,71 @@
+//! Mock flash-loan lender.
+//!
+//! `borrow(amount)` lends `amount` out of the pool vault and records the
+//! principal plus fee as outstanding, `repay()` pulls the outstanding amount
+//! back. A pool only has one loan open at a time.
+#![no_std]
+
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod loan;
+
+/// Must match `swap_program::flash_loan::MOCK_LENDER.borrow_discriminator`.
+pub const BORROW_DISCRIMINATOR: [u8; 8] = [228, 253, 131, 202, 207, 116, 89, 18];
+/// Must match `swap_program::flash_loan::MOCK_LENDER.repay_discriminator`.
+pub const REPAY_DISCRIMINATOR: [u8; 8] = [234, 103, 67, 82, 208, 234, 219, 166];
+
+pub const LENDING_POOL_SEED: &[u8] = b"lending_pool";
+
+/// Error returned when a loan is taken while another one is open.
+pub const LOAN_OUTSTANDING: pinocchio::program_error::ProgramError =
+    pinocchio::program_error::ProgramError::Custom(3);
+/// Error returned when `repay` finds no open loan.
+pub const NO_LOAN: pinocchio::program_error::ProgramError =
+    pinocchio::program_error::ProgramError::Custom(4);
+
+/// Lending pool of the mock, a PDA of `[LENDING_POOL_SEED, mint]` that owns the vault.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MockLendingPool {
+    pub mint: [u8; 32],
+    pub vault: [u8; 32],
+    pub fee_bps: u16,
+    /// Principal plus fee of the open loan, 0 when none is open.
+    pub outstanding: u64,
+    pub bump: u8,
+}
+
+impl MockLendingPool {
+    pub const LEN: usize = 32 * 2 + 2 + 8 + 1;
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN {
+            return None;
+        }
+        Some(Self {
+            mint: data[0..32].try_into().unwrap(),
+            vault: data[32..64].try_into().unwrap(),
+            fee_bps: u16::from_le_bytes(data[64..66].try_into().unwrap()),
+            outstanding: u64::from_le_bytes(data[66..74].try_into().unwrap()),
+            bump: data[74],
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        data[0..32].copy_from_slice(&self.mint);
+        data[32..64].copy_from_slice(&self.vault);
+        data[64..66].copy_from_slice(&self.fee_bps.to_le_bytes());
+        data[66..74].copy_from_slice(&self.outstanding.to_le_bytes());
+        data[74] = self.bump;
+        data
+    }
+
+    /// Principal plus fee owed for borrowing `amount`.
+    pub fn owed(&self, amount: u64) -> Option<u64> {
+        amount.checked_add(mock_utils::fee(amount, self.fee_bps as u64)?)
+    }
+}
@@ -0,0 +1,115 @@
+use mock_utils::{check_key, check_token_account, transfer, wrong_account, INSUFFICIENT_LIQUIDITY};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{MockLendingPool, LENDING_POOL_SEED, LOAN_OUTSTANDING, NO_LOAN};
+
+/// Accounts of `borrow`/`repay`.
+struct LoanAccounts<'a> {
+    pool: MockLendingPool,
+    pool_acc: &'a AccountInfo,
+    vault: &'a AccountInfo,
+    borrower_token_account: &'a AccountInfo,
+    borrower: &'a AccountInfo,
+    token_program: &'a AccountInfo,
+    vault_amount: u64,
+}
+
+impl<'a> LoanAccounts<'a> {
+    fn load(program_id: &Pubkey, accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [pool_acc, vault, borrower_token_account, borrower, token_program, ..] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !pool_acc.is_owned_by(program_id) || !pool_acc.is_writable() {
+            return Err(wrong_account(0));
+        }
+        let pool =
+            MockLendingPool::from_bytes(&pool_acc.try_borrow_data()?).ok_or(wrong_account(0))?;
+        check_key(vault, &pool.vault, 1)?;
+        let vault_amount = check_token_account(vault, &pool.mint, pool_acc.key(), 1)?;
+        check_token_account(borrower_token_account, &pool.mint, borrower.key(), 2)?;
+        if !borrower.is_signer() {
+            return Err(wrong_account(3));
+        }
+        if !mock_utils::is_token_program(token_program.key()) {
+            return Err(wrong_account(4));
+        }
+
+        Ok(Self {
+            pool,
+            pool_acc,
+            vault,
+            borrower_token_account,
+            borrower,
+            token_program,
+            vault_amount,
+        })
+    }
+
+    fn save(&self) -> ProgramResult {
+        self.pool_acc
+            .try_borrow_mut_data()?
+            .copy_from_slice(&self.pool.to_bytes());
+        Ok(())
+    }
+}
+
+/// Lends `amount` to the borrower and opens a loan of `amount` plus fee.
+pub fn borrow(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let mut ctx = LoanAccounts::load(program_id, accounts)?;
+    if ctx.pool.outstanding != 0 {
+        return Err(LOAN_OUTSTANDING);
+    }
+    if amount > ctx.vault_amount {
+        return Err(INSUFFICIENT_LIQUIDITY);
+    }
+
+    ctx.pool.outstanding = ctx
+        .pool
+        .owed(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    ctx.save()?;
+
+    let bump = [ctx.pool.bump];
+    let seeds = [
+        Seed::from(LENDING_POOL_SEED),
+        Seed::from(&ctx.pool.mint),
+        Seed::from(&bump),
+    ];
+    transfer(
+        ctx.token_program,
+        ctx.vault,
+        ctx.borrower_token_account,
+        ctx.pool_acc,
+        amount,
+        &[Signer::from(&seeds)],
+    )
+}
+
+/// Pulls the outstanding amount back from the borrower and closes the loan.
+pub fn repay(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let mut ctx = LoanAccounts::load(program_id, accounts)?;
+    let outstanding = ctx.pool.outstanding;
+    if outstanding == 0 {
+        return Err(NO_LOAN);
+    }
+
+    ctx.pool.outstanding = 0;
+    ctx.save()?;
+
+    transfer(
+        ctx.token_program,
+        ctx.borrower_token_account,
+        ctx.vault,
+        ctx.borrower,
+        outstanding,
+        &[],
+    )
+}
@@ -0,0 +1,185 @@
+//! Helpers shared by the mock DEX programs used in swap_program's LiteSVM tests.
+#![no_std]
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction, Signer},
+    log::sol_log_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+use pinocchio_pubkey::pubkey;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// First field of the `Program data:` record a mock emits for every call.
+pub const RECORD_TAG: &[u8] = b"mock_recv";
+
+/// Account flag bits of the record.
+pub const RECORD_SIGNER: u8 = 1;
+pub const RECORD_WRITABLE: u8 = 2;
+
+const MAX_RECORDED_ACCOUNTS: usize = 29;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Error returned when the account at `slot` is not the one the instruction expects.
+///
+/// The slot is part of the code so that a misordered CPI points at the culprit.
+#[inline(always)]
+pub fn wrong_account(slot: usize) -> ProgramError {
+    ProgramError::Custom(100 + slot as u32)
+}
+
+/// Error returned when the computed amount does not satisfy the caller's limit.
+pub const SLIPPAGE_EXCEEDED: ProgramError = ProgramError::Custom(1);
+/// Error returned when the pool cannot cover the requested amount.
+pub const INSUFFICIENT_LIQUIDITY: ProgramError = ProgramError::Custom(2);
+
+/// Logs what the mock received through `sol_log_data`:
+/// `[RECORD_TAG, data, flags, key_0, key_1, ...]` where `flags[i]` holds the
+/// signer/writable bits of `key_i`.
+pub fn record(data: &[u8], accounts: &[AccountInfo]) {
+    let len = accounts.len().min(MAX_RECORDED_ACCOUNTS);
+    let mut flags = [0u8; MAX_RECORDED_ACCOUNTS];
+    let mut fields: [&[u8]; MAX_RECORDED_ACCOUNTS + 3] = [&[]; MAX_RECORDED_ACCOUNTS + 3];
+
+    for (flag, account) in flags.iter_mut().zip(&accounts[..len]) {
+        if account.is_signer() {
+            *flag |= RECORD_SIGNER;
+        }
+        if account.is_writable() {
+            *flag |= RECORD_WRITABLE;
+        }
+    }
+
+    fields[0] = RECORD_TAG;
+    fields[1] = data;
+    fields[2] = &flags[..len];
+    for (field, account) in fields[3..].iter_mut().zip(&accounts[..len]) {
+        *field = account.key();
+    }
+    sol_log_data(&fields[..len + 3]);
+}
+
+/// Mint, owner and amount of an SPL token account.
+pub struct TokenAccount {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+pub fn is_token_program(key: &Pubkey) -> bool {
+    *key == TOKEN_PROGRAM_ID || *key == TOKEN_2022_PROGRAM_ID
+}
+
+pub fn token_account(account: &AccountInfo, slot: usize) -> Result<TokenAccount, ProgramError> {
+    if !is_token_program(unsafe { account.owner() }) {
+        return Err(wrong_account(slot));
+    }
+    let data = account.try_borrow_data()?;
+    if data.len() < 165 {
+        return Err(wrong_account(slot));
+    }
+    Ok(TokenAccount {
+        mint: data[0..32].try_into().unwrap(),
+        owner: data[32..64].try_into().unwrap(),
+        amount: u64::from_le_bytes(data[64..72].try_into().unwrap()),
+    })
+}
+
+pub fn check_key(account: &AccountInfo, expected: &Pubkey, slot: usize) -> ProgramResult {
+    if account.key() != expected {
+        return Err(wrong_account(slot));
+    }
+    Ok(())
+}
+
+pub fn check_writable(account: &AccountInfo, slot: usize) -> ProgramResult {
+    if !account.is_writable() {
+        return Err(wrong_account(slot));
+    }
+    Ok(())
+}
+
+/// Checks a writable token account of `mint` owned by `owner`, returns its amount.
+pub fn check_token_account(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    slot: usize,
+) -> Result<u64, ProgramError> {
+    check_writable(account, slot)?;
+    let token = token_account(account, slot)?;
+    if token.mint != *mint || token.owner != *owner {
+        return Err(wrong_account(slot));
+    }
+    Ok(token.amount)
+}
+
+/// Checks that `token_program` is the program owning `mint`.
+pub fn check_token_program(
+    token_program: &AccountInfo,
+    mint: &AccountInfo,
+    slot: usize,
+) -> ProgramResult {
+    if is_token_program(token_program.key()) && mint.is_owned_by(token_program.key()) {
+        Ok(())
+    } else {
+        Err(wrong_account(slot))
+    }
+}
+
+/// SPL token `Transfer`, signed by `signers` when `authority` is a PDA.
+pub fn transfer(
+    token_program: &AccountInfo,
+    from: &AccountInfo,
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+    let mut data = [0u8; 9];
+    data[0] = 3;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+
+    let account_metas = [
+        AccountMeta::writable(from.key()),
+        AccountMeta::writable(to.key()),
+        AccountMeta::readonly_signer(authority.key()),
+    ];
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &account_metas,
+        data: &data,
+    };
+    pinocchio::cpi::invoke_signed(&instruction, &[from, to, authority], signers)
+}
+
+/// `ceil(amount * bps / 10_000)`.
+pub fn fee(amount: u64, bps: u64) -> Option<u64> {
+    let fee = (amount as u128 * bps as u128).div_ceil(BPS_DENOMINATOR as u128);
+    u64::try_from(fee).ok()
+}
+
+/// Output of a constant product swap of `amount_in`, rounded down.
+pub fn amount_out(reserve_in: u64, reserve_out: u64, amount_in: u64) -> Option<u64> {
+    let numerator = reserve_out as u128 * amount_in as u128;
+    let denominator = reserve_in as u128 + amount_in as u128;
+    u64::try_from(numerator.checked_div(denominator)?).ok()
+}
+
+/// Input of a constant product swap returning `amount_out`, rounded up.
+pub fn amount_in(reserve_in: u64, reserve_out: u64, amount_out: u64) -> Option<u64> {
+    let remaining = reserve_out.checked_sub(amount_out).filter(|r| *r > 0)?;
+    let numerator = reserve_in as u128 * amount_out as u128;
+    u64::try_from(numerator.div_ceil(remaining as u128)).ok()
+}
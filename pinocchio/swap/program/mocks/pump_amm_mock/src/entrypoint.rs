@@ -0,0 +1,33 @@
+#![allow(unexpected_cfgs)]
+
+use pinocchio::{
+    account_info::AccountInfo, default_panic_handler, no_allocator, program_entrypoint,
+    program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{swap, BUY_DISCRIMINATOR, SELL_DISCRIMINATOR};
+
+program_entrypoint!(process_instruction);
+no_allocator!();
+default_panic_handler!();
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    mock_utils::record(instruction_data, accounts);
+
+    if instruction_data.len() != 24 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (discriminator, args) = instruction_data.split_at(8);
+    let amount = u64::from_le_bytes(args[0..8].try_into().unwrap());
+    let limit = u64::from_le_bytes(args[8..16].try_into().unwrap());
+
+    match discriminator {
+        d if d == BUY_DISCRIMINATOR => swap::buy(program_id, accounts, amount, limit),
+        d if d == SELL_DISCRIMINATOR => swap::sell(program_id, accounts, amount, limit),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
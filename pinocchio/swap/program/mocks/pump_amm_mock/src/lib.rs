@@ -0,0 +1,176 @@
+//! Mock of the Pump AMM `buy`/`sell` instructions.
+//!
+//! It takes the same discriminators and account order as Pump AMM, checks
+//! every account against its slot, records what it received and trades on a
+//! constant product curve between the pool vaults.
+#![no_std]
+
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod swap;
+
+/// Must match `swap_program::dex::BUY_DISCRIMINATOR`.
+pub const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+/// Must match `swap_program::dex::SELL_DISCRIMINATOR`.
+pub const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+pub const LP_FEE_BPS: u64 = 20;
+pub const PROTOCOL_FEE_BPS: u64 = 5;
+pub const COIN_CREATOR_FEE_BPS: u64 = 5;
+
+pub const POOL_SEED: &[u8] = b"pool";
+pub const GLOBAL_CONFIG_SEED: &[u8] = b"global_config";
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+pub const CREATOR_VAULT_SEED: &[u8] = b"creator_vault";
+
+/// Anchor discriminator `account:Pool` of Pump AMM.
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+/// Anchor discriminator `account:GlobalConfig` of Pump AMM.
+pub const GLOBAL_CONFIG_DISCRIMINATOR: [u8; 8] = [149, 8, 156, 202, 160, 252, 176, 217];
+
+/// Pool account of the mock, a PDA of `[POOL_SEED, base_mint, quote_mint]`
+/// that owns both vaults, stored in the layout of Pump AMM's `Pool` so that
+/// swap_program quotes it like a real pool.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MockPool {
+    pub base_mint: [u8; 32],
+    pub quote_mint: [u8; 32],
+    pub pool_base_token_account: [u8; 32],
+    pub pool_quote_token_account: [u8; 32],
+    pub coin_creator: [u8; 32],
+    pub bump: u8,
+}
+
+impl MockPool {
+    /// discriminator, bump, index, creator, 4 keys, lp_mint, lp_supply, coin_creator
+    pub const LEN: usize = 8 + 1 + 2 + 32 * 6 + 8 + 32;
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN || data[..8] != POOL_DISCRIMINATOR {
+            return None;
+        }
+        let key = |offset: usize| data[offset..offset + 32].try_into().unwrap();
+        Some(Self {
+            base_mint: key(43),
+            quote_mint: key(75),
+            pool_base_token_account: key(139),
+            pool_quote_token_account: key(171),
+            coin_creator: key(211),
+            bump: data[8],
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        data[..8].copy_from_slice(&POOL_DISCRIMINATOR);
+        data[8] = self.bump;
+        for (offset, key) in [
+            (43, &self.base_mint),
+            (75, &self.quote_mint),
+            (139, &self.pool_base_token_account),
+            (171, &self.pool_quote_token_account),
+            (211, &self.coin_creator),
+        ] {
+            data[offset..offset + 32].copy_from_slice(key);
+        }
+        data
+    }
+}
+
+/// Global config of the mock, the PDA of `[GLOBAL_CONFIG_SEED]`, stored in the
+/// layout of Pump AMM's `GlobalConfig` with the fees the mock charges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MockGlobalConfig {
+    pub protocol_fee_recipients: [[u8; 32]; 8],
+}
+
+impl MockGlobalConfig {
+    /// discriminator, admin, 2 fees, disable_flags, 8 fee recipients, coin creator fee
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1 + 32 * 8 + 8;
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN || data[..8] != GLOBAL_CONFIG_DISCRIMINATOR {
+            return None;
+        }
+        let mut protocol_fee_recipients = [[0u8; 32]; 8];
+        for (i, recipient) in protocol_fee_recipients.iter_mut().enumerate() {
+            recipient.copy_from_slice(&data[57 + i * 32..57 + (i + 1) * 32]);
+        }
+        Some(Self {
+            protocol_fee_recipients,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        data[..8].copy_from_slice(&GLOBAL_CONFIG_DISCRIMINATOR);
+        data[40..48].copy_from_slice(&LP_FEE_BPS.to_le_bytes());
+        data[48..56].copy_from_slice(&PROTOCOL_FEE_BPS.to_le_bytes());
+        for (i, recipient) in self.protocol_fee_recipients.iter().enumerate() {
+            data[57 + i * 32..57 + (i + 1) * 32].copy_from_slice(recipient);
+        }
+        data[313..321].copy_from_slice(&COIN_CREATOR_FEE_BPS.to_le_bytes());
+        data
+    }
+}
+
+/// Quote side of a trade, split the way Pump AMM splits it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quote {
+    /// Quote amount moved by the curve, before fees.
+    pub quote_amount: u64,
+    pub lp_fee: u64,
+    pub protocol_fee: u64,
+    pub coin_creator_fee: u64,
+}
+
+impl Quote {
+    fn new(quote_amount: u64) -> Option<Self> {
+        Some(Self {
+            quote_amount,
+            lp_fee: mock_utils::fee(quote_amount, LP_FEE_BPS)?,
+            protocol_fee: mock_utils::fee(quote_amount, PROTOCOL_FEE_BPS)?,
+            coin_creator_fee: mock_utils::fee(quote_amount, COIN_CREATOR_FEE_BPS)?,
+        })
+    }
+
+    fn fees(&self) -> Option<u64> {
+        self.lp_fee
+            .checked_add(self.protocol_fee)?
+            .checked_add(self.coin_creator_fee)
+    }
+}
+
+/// Quote paid by the user to buy exactly `base_amount_out`.
+pub fn quote_buy(
+    base_reserve: u64,
+    quote_reserve: u64,
+    base_amount_out: u64,
+) -> Option<(Quote, u64)> {
+    let quote = Quote::new(mock_utils::amount_in(
+        quote_reserve,
+        base_reserve,
+        base_amount_out,
+    )?)?;
+    let total = quote.quote_amount.checked_add(quote.fees()?)?;
+    Some((quote, total))
+}
+
+/// Quote received by the user for selling exactly `base_amount_in`.
+pub fn quote_sell(
+    base_reserve: u64,
+    quote_reserve: u64,
+    base_amount_in: u64,
+) -> Option<(Quote, u64)> {
+    let quote = Quote::new(mock_utils::amount_out(
+        base_reserve,
+        quote_reserve,
+        base_amount_in,
+    )?)?;
+    let received = quote.quote_amount.checked_sub(quote.fees()?)?;
+    Some((quote, received))
+}
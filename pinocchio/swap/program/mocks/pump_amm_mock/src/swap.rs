@@ -0,0 +1,238 @@
+use mock_utils::{
+    check_key, check_token_account, check_token_program, transfer, wrong_account,
+    ASSOCIATED_TOKEN_PROGRAM_ID, INSUFFICIENT_LIQUIDITY, SLIPPAGE_EXCEEDED, SYSTEM_PROGRAM_ID,
+};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{find_program_address, Pubkey},
+    ProgramResult,
+};
+
+use crate::{
+    quote_buy, quote_sell, MockGlobalConfig, MockPool, CREATOR_VAULT_SEED, EVENT_AUTHORITY_SEED,
+    GLOBAL_CONFIG_SEED, POOL_SEED,
+};
+
+/// Accounts of `buy`/`sell`, in Pump AMM order.
+struct SwapAccounts<'a> {
+    pool: MockPool,
+    pool_acc: &'a AccountInfo,
+    user: &'a AccountInfo,
+    user_base_token_account: &'a AccountInfo,
+    user_quote_token_account: &'a AccountInfo,
+    pool_base_token_account: &'a AccountInfo,
+    pool_quote_token_account: &'a AccountInfo,
+    protocol_fee_recipient_token_account: &'a AccountInfo,
+    base_token_program: &'a AccountInfo,
+    quote_token_program: &'a AccountInfo,
+    coin_creator_vault_ata: &'a AccountInfo,
+    base_reserve: u64,
+    quote_reserve: u64,
+}
+
+impl<'a> SwapAccounts<'a> {
+    fn load(program_id: &Pubkey, accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [pool_acc, user, global_config, base_mint, quote_mint, user_base_token_account, user_quote_token_account, pool_base_token_account, pool_quote_token_account, protocol_fee_recipient, protocol_fee_recipient_token_account, base_token_program, quote_token_program, system_program, associated_token_program, event_authority, program, coin_creator_vault_ata, coin_creator_vault_authority, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !pool_acc.is_owned_by(program_id) {
+            return Err(wrong_account(0));
+        }
+        let pool = MockPool::from_bytes(&pool_acc.try_borrow_data()?).ok_or(wrong_account(0))?;
+
+        if !user.is_signer() || !user.is_writable() {
+            return Err(wrong_account(1));
+        }
+        check_key(
+            global_config,
+            &find_program_address(&[GLOBAL_CONFIG_SEED], program_id).0,
+            2,
+        )?;
+        if !global_config.is_owned_by(program_id) {
+            return Err(wrong_account(2));
+        }
+        let config = MockGlobalConfig::from_bytes(&global_config.try_borrow_data()?)
+            .ok_or(wrong_account(2))?;
+        check_key(base_mint, &pool.base_mint, 3)?;
+        check_key(quote_mint, &pool.quote_mint, 4)?;
+        check_token_account(user_base_token_account, &pool.base_mint, user.key(), 5)?;
+        check_token_account(user_quote_token_account, &pool.quote_mint, user.key(), 6)?;
+        check_key(pool_base_token_account, &pool.pool_base_token_account, 7)?;
+        let base_reserve =
+            check_token_account(pool_base_token_account, &pool.base_mint, pool_acc.key(), 7)?;
+        check_key(pool_quote_token_account, &pool.pool_quote_token_account, 8)?;
+        let quote_reserve = check_token_account(
+            pool_quote_token_account,
+            &pool.quote_mint,
+            pool_acc.key(),
+            8,
+        )?;
+        if !config
+            .protocol_fee_recipients
+            .contains(protocol_fee_recipient.key())
+        {
+            return Err(wrong_account(9));
+        }
+        check_token_account(
+            protocol_fee_recipient_token_account,
+            &pool.quote_mint,
+            protocol_fee_recipient.key(),
+            10,
+        )?;
+        check_token_program(base_token_program, base_mint, 11)?;
+        check_token_program(quote_token_program, quote_mint, 12)?;
+        check_key(system_program, &SYSTEM_PROGRAM_ID, 13)?;
+        check_key(associated_token_program, &ASSOCIATED_TOKEN_PROGRAM_ID, 14)?;
+        check_key(
+            event_authority,
+            &find_program_address(&[EVENT_AUTHORITY_SEED], program_id).0,
+            15,
+        )?;
+        check_key(program, program_id, 16)?;
+        check_token_account(
+            coin_creator_vault_ata,
+            &pool.quote_mint,
+            coin_creator_vault_authority.key(),
+            17,
+        )?;
+        check_key(
+            coin_creator_vault_authority,
+            &find_program_address(&[CREATOR_VAULT_SEED, &pool.coin_creator], program_id).0,
+            18,
+        )?;
+
+        Ok(Self {
+            pool,
+            pool_acc,
+            user,
+            user_base_token_account,
+            user_quote_token_account,
+            pool_base_token_account,
+            pool_quote_token_account,
+            protocol_fee_recipient_token_account,
+            base_token_program,
+            quote_token_program,
+            coin_creator_vault_ata,
+            base_reserve,
+            quote_reserve,
+        })
+    }
+
+    /// Transfers out of a pool vault, signed by the pool PDA.
+    fn transfer_from_pool(
+        &self,
+        token_program: &AccountInfo,
+        from: &AccountInfo,
+        to: &AccountInfo,
+        amount: u64,
+    ) -> ProgramResult {
+        let bump = [self.pool.bump];
+        let seeds = [
+            Seed::from(POOL_SEED),
+            Seed::from(&self.pool.base_mint),
+            Seed::from(&self.pool.quote_mint),
+            Seed::from(&bump),
+        ];
+        transfer(
+            token_program,
+            from,
+            to,
+            self.pool_acc,
+            amount,
+            &[Signer::from(&seeds)],
+        )
+    }
+}
+
+/// Buys exactly `base_amount_out`, spending at most `max_quote_amount_in`.
+pub fn buy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    base_amount_out: u64,
+    max_quote_amount_in: u64,
+) -> ProgramResult {
+    let ctx = SwapAccounts::load(program_id, accounts)?;
+    let (quote, total) = quote_buy(ctx.base_reserve, ctx.quote_reserve, base_amount_out)
+        .ok_or(INSUFFICIENT_LIQUIDITY)?;
+    if total > max_quote_amount_in {
+        return Err(SLIPPAGE_EXCEEDED);
+    }
+
+    transfer(
+        ctx.quote_token_program,
+        ctx.user_quote_token_account,
+        ctx.pool_quote_token_account,
+        ctx.user,
+        quote.quote_amount + quote.lp_fee,
+        &[],
+    )?;
+    transfer(
+        ctx.quote_token_program,
+        ctx.user_quote_token_account,
+        ctx.protocol_fee_recipient_token_account,
+        ctx.user,
+        quote.protocol_fee,
+        &[],
+    )?;
+    transfer(
+        ctx.quote_token_program,
+        ctx.user_quote_token_account,
+        ctx.coin_creator_vault_ata,
+        ctx.user,
+        quote.coin_creator_fee,
+        &[],
+    )?;
+    ctx.transfer_from_pool(
+        ctx.base_token_program,
+        ctx.pool_base_token_account,
+        ctx.user_base_token_account,
+        base_amount_out,
+    )
+}
+
+/// Sells exactly `base_amount_in`, receiving at least `min_quote_amount_out`.
+pub fn sell(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    base_amount_in: u64,
+    min_quote_amount_out: u64,
+) -> ProgramResult {
+    let ctx = SwapAccounts::load(program_id, accounts)?;
+    let (quote, received) = quote_sell(ctx.base_reserve, ctx.quote_reserve, base_amount_in)
+        .ok_or(INSUFFICIENT_LIQUIDITY)?;
+    if received < min_quote_amount_out {
+        return Err(SLIPPAGE_EXCEEDED);
+    }
+
+    transfer(
+        ctx.base_token_program,
+        ctx.user_base_token_account,
+        ctx.pool_base_token_account,
+        ctx.user,
+        base_amount_in,
+        &[],
+    )?;
+    ctx.transfer_from_pool(
+        ctx.quote_token_program,
+        ctx.pool_quote_token_account,
+        ctx.user_quote_token_account,
+        received,
+    )?;
+    ctx.transfer_from_pool(
+        ctx.quote_token_program,
+        ctx.pool_quote_token_account,
+        ctx.protocol_fee_recipient_token_account,
+        quote.protocol_fee,
+    )?;
+    ctx.transfer_from_pool(
+        ctx.quote_token_program,
+        ctx.pool_quote_token_account,
+        ctx.coin_creator_vault_ata,
+        quote.coin_creator_fee,
+    )
+}
@@ -0,0 +1,28 @@
+//! 关联代币账户
+use pinocchio::{account_info::AccountInfo, ProgramResult};
+use pinocchio_associated_token_account::instructions::CreateIdempotent;
+
+/// `account` 尚不存在时创建 `wallet` 的 ATA, 租金由 `wallet` 支付
+///
+/// The associated token program must be among the instruction accounts and
+/// checks that `account` is the ATA of `wallet` for `mint` and `token_program`.
+pub fn create_if_missing(
+    account: &AccountInfo,
+    wallet: &AccountInfo,
+    mint: &AccountInfo,
+    system_program: &AccountInfo,
+    token_program: &AccountInfo,
+) -> ProgramResult {
+    if !account.data_is_empty() {
+        return Ok(());
+    }
+    CreateIdempotent {
+        funding_account: wallet,
+        account,
+        wallet,
+        mint,
+        system_program,
+        token_program,
+    }
+    .invoke()
+}
@@ -0,0 +1,10 @@
+//! 打印 swap_program 的 IDL
+//!
+//! `cargo run --features idl --bin idl > ../client/idl/swap_program.json`
+fn main() {
+    let idl = swap_program::idl::idl();
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&idl).expect("IDL serializes")
+    );
+}
@@ -0,0 +1,287 @@
+//! 离线搜索器
+//!
+//! Loads Pump AMM pools and DLMM lb pairs from surfpool `AccountsSnapshot`
+//! files or a local RPC, quotes every buy-then-sell cycle with the program's
+//! own quoters and prints the best route. With `--submit` it sends the whole
+//! cycle as one `RoundTrip` to a local RPC, as a v0 transaction when a lookup
+//! table is given or created with `--create-lookup-table`.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use solana_instruction::Instruction;
+use solana_keypair::{read_keypair_file, Keypair, Signer};
+use solana_message::AddressLookupTableAccount;
+use solana_pubkey::Pubkey;
+use solana_transaction::Transaction;
+use swap_program::searcher::lookup_table;
+use swap_program::searcher::route::{search, Route};
+use swap_program::searcher::rpc::{is_local_url, RpcClient};
+use swap_program::searcher::store::AccountStore;
+use swap_program::searcher::venue::Venue;
+
+const USAGE: &str =
+    "usage: searcher [--snapshot <file>]... [--rpc <http url>] [--pool <address>]...
+                [--base-mint <mint>] [--amount <base units>]... [--top <n>]
+                [--lookup-table <address>] [--create-lookup-table]
+                [--submit] [--min-profit <base units>] [--keypair <file>]
+                [--allow-remote-rpc]";
+
+const WSOL_MINT: Pubkey = Pubkey::from_str_const("So11111111111111111111111111111111111111112");
+const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ComputeBudget111111111111111111111111111111");
+/// 未指定 `--amount` 时报价的输入数量 (0.01, 0.1 与 1 SOL)
+const DEFAULT_AMOUNTS: [u64; 3] = [10_000_000, 100_000_000, 1_000_000_000];
+/// 未指定 `--min-profit` 时, 提交的循环至少赚 1 个 base 单位
+const DEFAULT_MIN_PROFIT: u64 = 1;
+/// 单笔交易的上限, 足够 DLMM leg 走完报价的全部 bin
+const COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+#[derive(Default)]
+struct Args {
+    snapshots: Vec<String>,
+    rpc: Option<String>,
+    pools: Vec<Pubkey>,
+    base_mint: Option<Pubkey>,
+    amounts: Vec<u64>,
+    top: Option<usize>,
+    submit: bool,
+    min_profit: Option<u64>,
+    keypair: Option<String>,
+    allow_remote_rpc: bool,
+    lookup_table: Option<Pubkey>,
+    create_lookup_table: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{flag} takes a value"));
+            match flag.as_str() {
+                "--snapshot" => parsed.snapshots.push(value()?),
+                "--rpc" => parsed.rpc = Some(value()?),
+                "--pool" => parsed.pools.push(parse_pubkey(&value()?)?),
+                "--base-mint" => parsed.base_mint = Some(parse_pubkey(&value()?)?),
+                "--amount" => parsed
+                    .amounts
+                    .push(value()?.parse().map_err(|e| format!("--amount: {e}"))?),
+                "--top" => parsed.top = Some(value()?.parse().map_err(|e| format!("--top: {e}"))?),
+                "--submit" => parsed.submit = true,
+                "--min-profit" => {
+                    parsed.min_profit =
+                        Some(value()?.parse().map_err(|e| format!("--min-profit: {e}"))?)
+                }
+                "--allow-remote-rpc" => parsed.allow_remote_rpc = true,
+                "--keypair" => parsed.keypair = Some(value()?),
+                "--lookup-table" => parsed.lookup_table = Some(parse_pubkey(&value()?)?),
+                "--create-lookup-table" => parsed.create_lookup_table = true,
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                _ => return Err(format!("unknown argument {flag}\n{USAGE}")),
+            }
+        }
+        if parsed.snapshots.is_empty() && parsed.rpc.is_none() {
+            return Err(format!("pass --snapshot or --rpc\n{USAGE}"));
+        }
+        if parsed.snapshots.is_empty() && parsed.pools.is_empty() {
+            return Err("--rpc without snapshots needs the --pool addresses".to_string());
+        }
+        if parsed.submit && (parsed.rpc.is_none() || parsed.keypair.is_none()) {
+            return Err("--submit needs --rpc and --keypair".to_string());
+        }
+        if parsed.create_lookup_table && (parsed.rpc.is_none() || parsed.keypair.is_none()) {
+            return Err("--create-lookup-table needs --rpc and --keypair".to_string());
+        }
+        if parsed.lookup_table.is_some() && parsed.rpc.is_none() {
+            return Err("--lookup-table needs --rpc".to_string());
+        }
+        let sends = parsed.submit || parsed.create_lookup_table;
+        if sends && !parsed.allow_remote_rpc && !parsed.rpc.as_deref().is_some_and(is_local_url) {
+            return Err(
+                "--submit and --create-lookup-table only send to a local RPC, pass --allow-remote-rpc to override"
+                    .to_string(),
+            );
+        }
+        Ok(parsed)
+    }
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey, String> {
+    value.parse().map_err(|e| format!("{value}: {e:?}"))
+}
+
+fn main() {
+    if let Err(error) = run(Args::parse(std::env::args().skip(1))) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Result<Args, String>) -> Result<(), String> {
+    let args = args?;
+    let keypair = args
+        .keypair
+        .as_deref()
+        .map(|path| read_keypair_file(path).map_err(|e| format!("keypair: {e}")))
+        .transpose()?;
+    let rpc = args.rpc.as_deref().map(RpcClient::new).transpose()?;
+    let mut store = AccountStore::new(rpc);
+    for path in &args.snapshots {
+        let len = store.load_snapshot(path)?;
+        println!("loaded {len} accounts from {path}");
+    }
+
+    let base_mint = args.base_mint.unwrap_or(WSOL_MINT);
+    let addresses = if args.pools.is_empty() {
+        Venue::discover(&store)
+    } else {
+        args.pools.clone()
+    };
+    let mut venues = Vec::new();
+    for address in addresses {
+        match Venue::load(&mut store, address) {
+            Ok(venue) => {
+                println!("{:?} {address}", venue.dex());
+                venues.push(venue);
+            }
+            Err(error) => eprintln!("skipping {address}: {error}"),
+        }
+    }
+
+    let lookup_table = lookup_table(&args, &store, keypair.as_ref(), &venues, &base_mint)?;
+
+    let amounts = if args.amounts.is_empty() {
+        DEFAULT_AMOUNTS.to_vec()
+    } else {
+        args.amounts.clone()
+    };
+    let unix_timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+    let routes = search(&venues, &base_mint, &amounts, unix_timestamp);
+    if routes.is_empty() {
+        println!("no route: every cycle needs two venues of the same mint against {base_mint}");
+        return Ok(());
+    }
+
+    println!();
+    for route in routes.iter().take(args.top.unwrap_or(10)) {
+        print_route(route);
+    }
+    let best = &routes[0];
+    let amount_in = best.buy_quote.amount_in;
+    println!(
+        "\nbest: buy {} on {:?} {}, sell on {:?} {}, profit {}",
+        amount_in,
+        best.buy.dex(),
+        best.buy.address(),
+        best.sell.dex(),
+        best.sell.address(),
+        best.profit()
+    );
+
+    if args.submit {
+        let min_profit = args.min_profit.unwrap_or(DEFAULT_MIN_PROFIT);
+        if !best.meets_min_profit(min_profit) {
+            println!("not submitting: the best route makes less than --min-profit {min_profit}");
+            return Ok(());
+        }
+        let keypair = keypair.expect("--submit is checked with --keypair");
+        let rpc = store.rpc.as_ref().expect("--submit is checked with --rpc");
+        let signature = submit(
+            rpc,
+            &keypair,
+            best,
+            &base_mint,
+            min_profit,
+            lookup_table.as_ref(),
+        )?;
+        println!("submitted {signature}");
+    }
+    Ok(())
+}
+
+/// `--lookup-table` 指定的表, `--create-lookup-table` 时先创建或扩展它以包含所有池子的静态账户
+fn lookup_table(
+    args: &Args,
+    store: &AccountStore,
+    keypair: Option<&Keypair>,
+    venues: &[Venue],
+    base_mint: &Pubkey,
+) -> Result<Option<AddressLookupTableAccount>, String> {
+    let Some(rpc) = &store.rpc else {
+        return Ok(None);
+    };
+    if args.create_lookup_table {
+        let keypair = keypair.expect("--create-lookup-table is checked with --keypair");
+        let keys = lookup_table::static_accounts(venues, base_mint);
+        let table = lookup_table::create_or_extend(rpc, keypair, args.lookup_table, &keys)?;
+        println!(
+            "lookup table {} holds {} addresses",
+            table.key,
+            table.addresses.len()
+        );
+        return Ok(Some(table));
+    }
+    args.lookup_table
+        .map(|address| lookup_table::fetch(rpc, address))
+        .transpose()
+}
+
+fn print_route(route: &Route) {
+    let (buy_in, buy_out) = (route.buy_quote.amount_in, route.buy_quote.amount_out);
+    let sell_out = route.sell_quote.amount_out;
+    let (buy_impact, sell_impact) = (
+        route.buy_quote.price_impact_bps,
+        route.sell_quote.price_impact_bps,
+    );
+    println!(
+        "{:>14} -> {:?} -> {:>16} {} -> {:?} -> {:>14}  profit {:>12}  impact {}/{} bps",
+        buy_in,
+        route.buy.dex(),
+        buy_out,
+        route.mint,
+        route.sell.dex(),
+        sell_out,
+        route.profit(),
+        buy_impact,
+        sell_impact
+    );
+}
+
+/// 发送整个循环的 `RoundTrip`, 买入报价的数量, 收益不足 `min_profit` 时交易失败
+fn submit(
+    rpc: &RpcClient,
+    keypair: &Keypair,
+    route: &Route,
+    base_mint: &Pubkey,
+    min_profit: u64,
+    lookup_table: Option<&AddressLookupTableAccount>,
+) -> Result<String, String> {
+    let mut compute_unit_limit = vec![2u8];
+    compute_unit_limit.extend_from_slice(&COMPUTE_UNIT_LIMIT.to_le_bytes());
+    let instructions = [
+        Instruction {
+            program_id: COMPUTE_BUDGET_PROGRAM_ID,
+            accounts: vec![],
+            data: compute_unit_limit,
+        },
+        route.round_trip_instruction(&keypair.pubkey(), base_mint, min_profit),
+    ];
+    let recent_blockhash = rpc.get_latest_blockhash()?;
+    let wire_transaction = match lookup_table {
+        Some(table) => bincode::serialize(&lookup_table::compile_v0(
+            keypair,
+            &instructions,
+            std::slice::from_ref(table),
+            recent_blockhash,
+        )?),
+        None => bincode::serialize(&Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&keypair.pubkey()),
+            &[keypair],
+            recent_blockhash,
+        )),
+    }
+    .map_err(|e| e.to_string())?;
+    println!("transaction {} bytes", wire_transaction.len());
+    rpc.send_transaction(&wire_transaction)
+}
@@ -0,0 +1,100 @@
+//! 计算单元预算
+//!
+//! A transaction that runs out of compute units inside a DEX CPI fails with no
+//! hint of which step was short. Before changing any state, a route compares
+//! `sol_remaining_compute_units` with rough upper bounds of what its steps cost.
+#![allow(unexpected_cfgs)]
+
+use core::cell::Cell;
+
+use crate::instruction::SupportDex;
+
+/// Pump AMM `buy` CPI
+pub const PUMP_AMM_LEG_UNITS: u64 = 50_000;
+/// DLMM `swap` CPI without crossing bins
+pub const DLMM_LEG_UNITS: u64 = 40_000;
+/// 每个被 DLMM `swap` 处理的 bin
+pub const DLMM_BIN_UNITS: u64 = 3_000;
+/// lender 的 borrow 与 repay CPI
+pub const FLASH_LOAN_UNITS: u64 = 15_000;
+/// 包装与关闭 WSOL ATA
+pub const WRAP_SOL_UNITS: u64 = 30_000;
+/// 创建输出 ATA
+pub const CREATE_ATA_UNITS: u64 = 25_000;
+/// 创建收益账本
+pub const CREATE_LEDGER_UNITS: u64 = 15_000;
+/// 余额读取, 事件与检查
+pub const SAFETY_MARGIN_UNITS: u64 = 5_000;
+/// 报价每个 bin 前需要保留的计算单元
+pub const QUOTE_BIN_UNITS: u64 = 5_000;
+/// 读取池子账户并报价, 不含 DLMM 的 bin
+pub const QUOTE_UNITS: u64 = 10_000;
+
+/// 当前指令剩余的计算单元, host 上不受限制
+#[inline(always)]
+pub fn remaining_compute_units() -> u64 {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        pinocchio::syscalls::sol_remaining_compute_units()
+    }
+    #[cfg(not(target_os = "solana"))]
+    u64::MAX
+}
+
+/// 报价时的剩余计算单元, 报价过 `max_bins` 个 bin 之后视为耗尽
+///
+/// A leg budgets units for `max_bin_to_process` bins, a quote walking more
+/// bins would leave the CPI short of what the route was checked against.
+pub fn bin_budget(max_bins: u64) -> impl Fn() -> u64 {
+    let bins = Cell::new(0u64);
+    move || {
+        bins.set(bins.get().saturating_add(1));
+        if bins.get() > max_bins {
+            0
+        } else {
+            remaining_compute_units()
+        }
+    }
+}
+
+/// 一个 leg 的 DEX CPI 预计消耗, DLMM 按最多处理 `max_bin_to_process` 个 bin 计
+pub fn leg_units(dex: SupportDex, max_bin_to_process: u64) -> u64 {
+    match dex {
+        SupportDex::PumpAmm => PUMP_AMM_LEG_UNITS,
+        SupportDex::DLMM => {
+            DLMM_LEG_UNITS.saturating_add(DLMM_BIN_UNITS.saturating_mul(max_bin_to_process))
+        }
+        _ => 0,
+    }
+}
+
+/// 按 `slippage_bps` 报价一个 leg 的开销, DLMM 按最多报价 `max_bin_to_process` 个 bin 计
+pub fn quote_units(dex: SupportDex, max_bin_to_process: u64) -> u64 {
+    match dex {
+        SupportDex::PumpAmm => QUOTE_UNITS,
+        SupportDex::DLMM => {
+            QUOTE_UNITS.saturating_add(QUOTE_BIN_UNITS.saturating_mul(max_bin_to_process))
+        }
+        _ => 0,
+    }
+}
+
+/// 一次 `DexSwap` 在 leg 之外的开销
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RouteSetup {
+    pub flash_loan: bool,
+    pub wrap_sol: bool,
+    pub create_ata: bool,
+    pub create_ledger: bool,
+}
+
+impl RouteSetup {
+    pub fn units(&self) -> u64 {
+        let step = |enabled: bool, units: u64| if enabled { units } else { 0 };
+        SAFETY_MARGIN_UNITS
+            + step(self.flash_loan, FLASH_LOAN_UNITS)
+            + step(self.wrap_sol, WRAP_SOL_UNITS)
+            + step(self.create_ata, CREATE_ATA_UNITS)
+            + step(self.create_ledger, CREATE_LEDGER_UNITS)
+    }
+}
@@ -13,10 +13,12 @@ pub enum DlmmError {
     NoBinActive,
     /// 超出最大 Bin 限制
     BinLimitExceeded,
+    /// token program 不是对应 mint 的 owner
+    InvalidTokenProgram,
 }
 
 impl From<DlmmError> for ProgramError {
     fn from(e: DlmmError) -> Self {
         ProgramError::Custom(500 + e as u32)
     }
-} 
\ No newline at end of file
+}
@@ -1,9 +1,12 @@
 mod errors;
+mod quote;
 mod swap;
 
 pub use errors::*;
+pub use quote::*;
 pub use swap::*;
 
 pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+pub const SWAP_EXACT_OUT_DISCRIMINATOR: [u8; 8] = [250, 73, 101, 33, 38, 207, 75, 184];
+pub const SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR: [u8; 8] = [56, 173, 230, 208, 173, 228, 156, 205];
 pub const SWAP2_DISCRIMINATOR: [u8; 8] = [65, 75, 63, 76, 235, 91, 91, 136];
-
@@ -0,0 +1,524 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::compute::{bin_budget, QUOTE_BIN_UNITS};
+use crate::dex::quote::{price_impact_bps, SwapQuote};
+use crate::error::LossProgramError;
+
+use super::{DLMMSwap, DlmmError};
+
+/// anchor 账户 discriminator `account:LbPair`
+pub const LB_PAIR_DISCRIMINATOR: [u8; 8] = [33, 11, 49, 98, 181, 101, 177, 13];
+/// anchor 账户 discriminator `account:BinArray`
+pub const BIN_ARRAY_DISCRIMINATOR: [u8; 8] = [92, 142, 92, 220, 5, 148, 70, 181];
+/// 每个 BinArray 中的 bin 数量
+pub const MAX_BIN_PER_ARRAY: i32 = 70;
+
+/// 价格为 Q64.64 定点数
+const SCALE_OFFSET: u32 = 64;
+const ONE: u128 = 1 << SCALE_OFFSET;
+const BASIS_POINT_MAX: u128 = 10_000;
+const FEE_PRECISION: u128 = 1_000_000_000;
+const MAX_FEE_RATE: u128 = 100_000_000;
+/// `pow` 支持的最大指数
+const MAX_EXPONENTIAL: u32 = 0x80000;
+
+/// discriminator, index, version, padding, lb_pair
+const BINS_OFFSET: usize = 8 + 8 + 1 + 7 + 32;
+/// amount_x, amount_y, price, liquidity_supply, rewards, fees, amounts in
+const BIN_LEN: usize = 8 + 8 + 16 + 16 + 16 * 2 + 16 * 2 + 16 * 2;
+
+/// `LbPair` 中报价用到的静态与动态参数
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LbPair {
+    pub base_factor: u16,
+    pub filter_period: u16,
+    pub decay_period: u16,
+    pub reduction_factor: u16,
+    pub variable_fee_control: u32,
+    pub max_volatility_accumulator: u32,
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+    pub base_fee_power_factor: u8,
+    pub volatility_accumulator: u32,
+    pub volatility_reference: u32,
+    pub index_reference: i32,
+    pub last_update_timestamp: i64,
+    pub active_id: i32,
+    pub bin_step: u16,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+}
+
+impl LbPair {
+    /// 至 `reserve_y` 为止的长度
+    pub const LEN: usize = 216;
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < Self::LEN || data[..8] != LB_PAIR_DISCRIMINATOR {
+            return None;
+        }
+        let u16_at =
+            |offset: usize| u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap());
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        let i32_at =
+            |offset: usize| i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        Some(Self {
+            base_factor: u16_at(8),
+            filter_period: u16_at(10),
+            decay_period: u16_at(12),
+            reduction_factor: u16_at(14),
+            variable_fee_control: u32_at(16),
+            max_volatility_accumulator: u32_at(20),
+            min_bin_id: i32_at(24),
+            max_bin_id: i32_at(28),
+            base_fee_power_factor: data[34],
+            volatility_accumulator: u32_at(40),
+            volatility_reference: u32_at(44),
+            index_reference: i32_at(48),
+            last_update_timestamp: i64::from_le_bytes(data[56..64].try_into().unwrap()),
+            active_id: i32_at(76),
+            bin_step: u16_at(80),
+            token_x_mint: data[88..120].try_into().unwrap(),
+            token_y_mint: data[120..152].try_into().unwrap(),
+        })
+    }
+
+    /// 按距上次交易的时间衰减波动率参考值
+    fn update_references(&mut self, unix_timestamp: i64) {
+        let elapsed = unix_timestamp.saturating_sub(self.last_update_timestamp);
+        if elapsed >= self.filter_period as i64 {
+            self.index_reference = self.active_id;
+            self.volatility_reference = if elapsed < self.decay_period as i64 {
+                (self.volatility_accumulator as u128 * self.reduction_factor as u128
+                    / BASIS_POINT_MAX) as u32
+            } else {
+                0
+            };
+        }
+    }
+
+    fn update_volatility_accumulator(&mut self) {
+        let delta_id = self.index_reference.abs_diff(self.active_id) as u128;
+        let volatility_accumulator = self.volatility_reference as u128 + delta_id * BASIS_POINT_MAX;
+        self.volatility_accumulator =
+            volatility_accumulator.min(self.max_volatility_accumulator as u128) as u32;
+    }
+
+    /// 基础费率加可变费率, 精度为 `FEE_PRECISION`
+    fn total_fee_rate(&self) -> u128 {
+        let base_fee = self.base_factor as u128
+            * self.bin_step as u128
+            * 10
+            * 10u128.pow(self.base_fee_power_factor as u32);
+        let variable_fee = if self.variable_fee_control > 0 {
+            let square_vfa_bin =
+                (self.volatility_accumulator as u128 * self.bin_step as u128).pow(2);
+            (self.variable_fee_control as u128 * square_vfa_bin).div_ceil(100_000_000_000)
+        } else {
+            0
+        };
+        (base_fee + variable_fee).min(MAX_FEE_RATE)
+    }
+
+    /// 使扣费后恰好为 `amount` 的手续费
+    fn compute_fee(&self, amount: u64) -> Option<u64> {
+        let rate = self.total_fee_rate();
+        let denominator = FEE_PRECISION - rate;
+        u64::try_from((amount as u128 * rate).div_ceil(denominator)).ok()
+    }
+
+    /// 从含费的 `amount` 中扣除的手续费
+    fn compute_fee_from_amount(&self, amount: u64) -> Option<u64> {
+        u64::try_from((amount as u128 * self.total_fee_rate()).div_ceil(FEE_PRECISION)).ok()
+    }
+}
+
+/// `BinArray` 中的 bin 流动性与价格
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BinArray<'a> {
+    pub index: i64,
+    pub lb_pair: Pubkey,
+    bins: &'a [u8],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Bin {
+    amount_x: u64,
+    amount_y: u64,
+    price: u128,
+}
+
+impl<'a> BinArray<'a> {
+    pub const LEN: usize = BINS_OFFSET + BIN_LEN * MAX_BIN_PER_ARRAY as usize;
+
+    pub fn from_bytes(data: &'a [u8]) -> Option<Self> {
+        if data.len() < Self::LEN || data[..8] != BIN_ARRAY_DISCRIMINATOR {
+            return None;
+        }
+        Some(Self {
+            index: i64::from_le_bytes(data[8..16].try_into().unwrap()),
+            lb_pair: data[24..56].try_into().unwrap(),
+            bins: &data[BINS_OFFSET..Self::LEN],
+        })
+    }
+
+    /// `bin_id` 所在 BinArray 的 index
+    pub fn index_of(bin_id: i32) -> i64 {
+        bin_id.div_euclid(MAX_BIN_PER_ARRAY) as i64
+    }
+
+    fn bin(&self, bin_id: i32) -> Option<Bin> {
+        let offset = (bin_id as i64).checked_sub(self.index * MAX_BIN_PER_ARRAY as i64)?;
+        let bin = self
+            .bins
+            .get(usize::try_from(offset).ok()? * BIN_LEN..)?
+            .get(..BIN_LEN)?;
+        Some(Bin {
+            amount_x: u64::from_le_bytes(bin[0..8].try_into().unwrap()),
+            amount_y: u64::from_le_bytes(bin[8..16].try_into().unwrap()),
+            price: u128::from_le_bytes(bin[16..32].try_into().unwrap()),
+        })
+    }
+}
+
+/// `(x * y) >> 64`, 其中 y 为 Q64.64
+fn mul_shr(x: u64, y: u128, round_up: bool) -> Option<u64> {
+    let x = x as u128;
+    let low = x * (y as u64 as u128);
+    let result = (x * (y >> SCALE_OFFSET)).checked_add(low >> SCALE_OFFSET)?;
+    let result = if round_up && low as u64 != 0 {
+        result.checked_add(1)?
+    } else {
+        result
+    };
+    u64::try_from(result).ok()
+}
+
+/// `(x << 64) / y`, 其中 y 为 Q64.64
+fn shl_div(x: u64, y: u128, round_up: bool) -> Option<u64> {
+    let x = (x as u128) << SCALE_OFFSET;
+    let result = if round_up {
+        x.div_ceil(y)
+    } else {
+        x.checked_div(y)?
+    };
+    u64::try_from(result).ok()
+}
+
+/// Q64.64 的 `base ^ exp`, 舍入方式与 DLMM 程序一致
+fn pow(base: u128, exp: i32) -> Option<u128> {
+    if exp == 0 {
+        return Some(ONE);
+    }
+    let mut invert = exp < 0;
+    let exp = exp.unsigned_abs();
+    if exp >= MAX_EXPONENTIAL {
+        return None;
+    }
+
+    let mut squared_base = base;
+    let mut result = ONE;
+    if squared_base >= result {
+        squared_base = u128::MAX.checked_div(squared_base)?;
+        invert = !invert;
+    }
+    for bit in 0..MAX_EXPONENTIAL.trailing_zeros() {
+        if exp & (1 << bit) > 0 {
+            result = result.checked_mul(squared_base)? >> SCALE_OFFSET;
+        }
+        squared_base = squared_base.checked_mul(squared_base)? >> SCALE_OFFSET;
+    }
+
+    if result == 0 {
+        return None;
+    }
+    if invert {
+        result = u128::MAX.checked_div(result)?;
+    }
+    Some(result)
+}
+
+/// bin 的价格 `(1 + bin_step / 10000) ^ bin_id`
+fn price_from_id(bin_id: i32, bin_step: u16) -> Option<u128> {
+    let bps = ((bin_step as u128) << SCALE_OFFSET) / BASIS_POINT_MAX;
+    pow(ONE.checked_add(bps)?, bin_id)
+}
+
+/// 从 active bin 开始逐个 bin 模拟 `swap` (exact in)
+///
+/// The pair is taken by value: volatility and the active bin move as the
+/// program would move them, without touching the account. The walk stops
+/// before a bin once `remaining_units` falls below `QUOTE_BIN_UNITS`, the
+/// quote then covers the part of `amount_in` the bins already filled.
+pub fn quote_exact_in(
+    mut lb_pair: LbPair,
+    bin_arrays: &[BinArray],
+    amount_in: u64,
+    swap_for_y: bool,
+    unix_timestamp: i64,
+    remaining_units: impl Fn() -> u64,
+) -> Option<SwapQuote> {
+    let mut amount_left = amount_in;
+    let mut amount_out = 0u64;
+    let mut fee = 0u64;
+    let mut spot_price = None;
+
+    lb_pair.update_references(unix_timestamp);
+    while amount_left > 0 {
+        if remaining_units() < QUOTE_BIN_UNITS {
+            break;
+        }
+        let active_id = lb_pair.active_id;
+        if active_id < lb_pair.min_bin_id || active_id > lb_pair.max_bin_id {
+            return None;
+        }
+        let bin = bin_arrays
+            .iter()
+            .find(|bin_array| bin_array.index == BinArray::index_of(active_id))?
+            .bin(active_id)?;
+        lb_pair.update_volatility_accumulator();
+
+        let max_amount_out = if swap_for_y {
+            bin.amount_y
+        } else {
+            bin.amount_x
+        };
+        if max_amount_out > 0 {
+            let price = match bin.price {
+                0 => price_from_id(active_id, lb_pair.bin_step)?,
+                price => price,
+            };
+            spot_price.get_or_insert(price);
+
+            let max_amount_in = if swap_for_y {
+                shl_div(bin.amount_y, price, true)?
+            } else {
+                mul_shr(bin.amount_x, price, true)?
+            };
+            let max_fee = lb_pair.compute_fee(max_amount_in)?;
+            let max_amount_in = max_amount_in.checked_add(max_fee)?;
+
+            let (bin_amount_in, bin_amount_out, bin_fee) = if amount_left >= max_amount_in {
+                (max_amount_in, max_amount_out, max_fee)
+            } else {
+                let bin_fee = lb_pair.compute_fee_from_amount(amount_left)?;
+                let amount_in_after_fee = amount_left - bin_fee;
+                let bin_amount_out = if swap_for_y {
+                    mul_shr(amount_in_after_fee, price, false)?
+                } else {
+                    shl_div(amount_in_after_fee, price, false)?
+                };
+                (amount_left, bin_amount_out.min(max_amount_out), bin_fee)
+            };
+            amount_left -= bin_amount_in;
+            amount_out = amount_out.checked_add(bin_amount_out)?;
+            fee = fee.checked_add(bin_fee)?;
+        }
+
+        if amount_left > 0 {
+            lb_pair.active_id = if swap_for_y {
+                active_id.checked_sub(1)?
+            } else {
+                active_id.checked_add(1)?
+            };
+        }
+    }
+
+    let spot_price = spot_price?;
+    let amount_in = amount_in - amount_left;
+    let spot_amount_out = if swap_for_y {
+        mul_shr(amount_in - fee, spot_price, false)?
+    } else {
+        shl_div(amount_in - fee, spot_price, false)?
+    };
+    Some(SwapQuote {
+        amount_in,
+        amount_out,
+        fee,
+        price_impact_bps: price_impact_bps(spot_amount_out, amount_out),
+    })
+}
+
+/// 从 active bin 开始逐个 bin 模拟 `swap_exact_out`
+///
+/// Same walk as `quote_exact_in`, but a bin is filled until `amount_out` is
+/// reached and the input it needs is rounded up, fee included. Once the
+/// compute units run short, the quote covers the part of `amount_out` the bins
+/// already filled.
+pub fn quote_exact_out(
+    mut lb_pair: LbPair,
+    bin_arrays: &[BinArray],
+    amount_out: u64,
+    swap_for_y: bool,
+    unix_timestamp: i64,
+    remaining_units: impl Fn() -> u64,
+) -> Option<SwapQuote> {
+    let mut amount_out_left = amount_out;
+    let mut amount_in = 0u64;
+    let mut fee = 0u64;
+    let mut spot_price = None;
+
+    lb_pair.update_references(unix_timestamp);
+    while amount_out_left > 0 {
+        if remaining_units() < QUOTE_BIN_UNITS {
+            break;
+        }
+        let active_id = lb_pair.active_id;
+        if active_id < lb_pair.min_bin_id || active_id > lb_pair.max_bin_id {
+            return None;
+        }
+        let bin = bin_arrays
+            .iter()
+            .find(|bin_array| bin_array.index == BinArray::index_of(active_id))?
+            .bin(active_id)?;
+        lb_pair.update_volatility_accumulator();
+
+        let max_amount_out = if swap_for_y {
+            bin.amount_y
+        } else {
+            bin.amount_x
+        };
+        if max_amount_out > 0 {
+            let price = match bin.price {
+                0 => price_from_id(active_id, lb_pair.bin_step)?,
+                price => price,
+            };
+            spot_price.get_or_insert(price);
+
+            let bin_amount_out = amount_out_left.min(max_amount_out);
+            let bin_amount_in = if swap_for_y {
+                shl_div(bin_amount_out, price, true)?
+            } else {
+                mul_shr(bin_amount_out, price, true)?
+            };
+            let bin_fee = lb_pair.compute_fee(bin_amount_in)?;
+            amount_out_left -= bin_amount_out;
+            amount_in = amount_in.checked_add(bin_amount_in)?.checked_add(bin_fee)?;
+            fee = fee.checked_add(bin_fee)?;
+        }
+
+        if amount_out_left > 0 {
+            lb_pair.active_id = if swap_for_y {
+                active_id.checked_sub(1)?
+            } else {
+                active_id.checked_add(1)?
+            };
+        }
+    }
+
+    let spot_price = spot_price?;
+    let amount_out = amount_out - amount_out_left;
+    let spot_amount_out = if swap_for_y {
+        mul_shr(amount_in - fee, spot_price, false)?
+    } else {
+        shl_div(amount_in - fee, spot_price, false)?
+    };
+    Some(SwapQuote {
+        amount_in,
+        amount_out,
+        fee,
+        price_impact_bps: price_impact_bps(spot_amount_out, amount_out),
+    })
+}
+
+impl DLMMSwap {
+    /// 在传入的 3 个 BinArray 内报价一次 swap, 不发起 CPI
+    ///
+    /// 计算单元不足或报价过 `max_bins` 个 bin 时返回部分成交的报价,
+    /// `amount_in` 小于请求的数量
+    pub fn quote(
+        accounts: &[AccountInfo],
+        amount_in: u64,
+        max_bins: u64,
+        unix_timestamp: i64,
+    ) -> Result<SwapQuote, ProgramError> {
+        Self::with_pair(accounts, |lb_pair, bin_arrays, swap_for_y| {
+            quote_exact_in(
+                lb_pair,
+                bin_arrays,
+                amount_in,
+                swap_for_y,
+                unix_timestamp,
+                bin_budget(max_bins),
+            )
+        })
+    }
+
+    /// 报价一次 `swap_exact_out`, 不发起 CPI
+    ///
+    /// 计算单元不足或报价过 `max_bins` 个 bin 时返回部分成交的报价,
+    /// `amount_out` 小于请求的数量
+    pub fn quote_exact_out(
+        accounts: &[AccountInfo],
+        amount_out: u64,
+        max_bins: u64,
+        unix_timestamp: i64,
+    ) -> Result<SwapQuote, ProgramError> {
+        Self::with_pair(accounts, |lb_pair, bin_arrays, swap_for_y| {
+            quote_exact_out(
+                lb_pair,
+                bin_arrays,
+                amount_out,
+                swap_for_y,
+                unix_timestamp,
+                bin_budget(max_bins),
+            )
+        })
+    }
+
+    /// 校验并读取 lb pair 与 3 个 BinArray, 交给 `quote` 报价
+    fn with_pair(
+        accounts: &[AccountInfo],
+        quote: impl FnOnce(LbPair, &[BinArray], bool) -> Option<SwapQuote>,
+    ) -> Result<SwapQuote, ProgramError> {
+        let [_, base_mint, _, _, _, _, _, mint, _, dlmm_program_id, _, lb_pair_acc, _, _, _, bin_1, bin_2, bin_3, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if !lb_pair_acc.is_owned_by(dlmm_program_id.key()) {
+            return Err(LossProgramError::InvalidOwner.into());
+        }
+        let lb_pair = LbPair::from_bytes(&lb_pair_acc.try_borrow_data()?)
+            .ok_or(LossProgramError::InvalidPoolAccount)?;
+        // 与 swap 一致, base_mint 为输入, mint 为输出
+        let swap_for_y = if &lb_pair.token_x_mint == base_mint.key()
+            && &lb_pair.token_y_mint == mint.key()
+        {
+            true
+        } else if &lb_pair.token_y_mint == base_mint.key() && &lb_pair.token_x_mint == mint.key() {
+            false
+        } else {
+            return Err(DlmmError::InvalidTokenPair.into());
+        };
+
+        let (data_1, data_2, data_3) = (
+            bin_1.try_borrow_data()?,
+            bin_2.try_borrow_data()?,
+            bin_3.try_borrow_data()?,
+        );
+        let bin_arrays = [
+            load_bin_array(bin_1, &data_1, lb_pair_acc, dlmm_program_id)?,
+            load_bin_array(bin_2, &data_2, lb_pair_acc, dlmm_program_id)?,
+            load_bin_array(bin_3, &data_3, lb_pair_acc, dlmm_program_id)?,
+        ];
+
+        quote(lb_pair, &bin_arrays, swap_for_y).ok_or(DlmmError::InsufficientLiquidity.into())
+    }
+}
+
+/// 校验 BinArray 属于 DLMM 程序且属于该 lb pair
+fn load_bin_array<'a>(
+    bin_array: &AccountInfo,
+    data: &'a [u8],
+    lb_pair_acc: &AccountInfo,
+    dlmm_program_id: &AccountInfo,
+) -> Result<BinArray<'a>, ProgramError> {
+    if !bin_array.is_owned_by(dlmm_program_id.key()) {
+        return Err(LossProgramError::InvalidOwner.into());
+    }
+    BinArray::from_bytes(data)
+        .filter(|parsed| &parsed.lb_pair == lb_pair_acc.key())
+        .ok_or(LossProgramError::InvalidPoolAccount.into())
+}
@@ -1,12 +1,23 @@
 use pinocchio::instruction::{AccountMeta, Instruction};
-use pinocchio::{
-    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
-};
-use pinocchio_log::log;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
 
-/// DLMM 交换实现
+use crate::instruction::layout::{AccountSpec, COMMON_ACCOUNTS};
+use crate::logging::{log_debug, log_info, LogLevel};
+
+use super::DlmmError;
+
+// swap:
 // amount_in     : u64
 // min_amount_out : u64
+//
+// swap_exact_out, same accounts:
+// max_in_amount : u64
+// out_amount    : u64
+//
+// swap_with_price_impact, same accounts:
+// amount_in            : u64
+// active_id            : Option<i32>
+// max_price_impact_bps : u16
 
 // [0] = [WRITE] lbPair
 //
@@ -37,22 +48,82 @@ use pinocchio_log::log;
 // [13] = [] eventAuthority
 //
 // [14] = [] program
-pub struct DLMMSwap ;
+/// DLMM 交换实现
+pub struct DLMMSwap;
 
 impl DLMMSwap {
-    /// only buy
-    pub fn execute_swap( accounts: &[AccountInfo], inst_data: &[u8]) -> ProgramResult {
+    /// Index of `lb_pair` in the instruction accounts
+    pub const POOL_INDEX: usize = 11;
+    /// `lb_pair`, reserves, oracle and bin arrays, the accounts a swap moves
+    pub const POOL_ACCOUNTS: core::ops::Range<usize> = 11..18;
+    /// Index of `token_x_program` in the instruction accounts
+    pub const MINT_TOKEN_PROGRAM_INDEX: usize = 18;
+    /// DLMM accounts of a leg, after `COMMON_ACCOUNTS`
+    pub const ACCOUNTS: [AccountSpec; 10] = [
+        AccountSpec::readonly("dlmm_program_id", "dlmm program account"),
+        AccountSpec::readonly("dlmm_event_authority", "dlmm event authority account"),
+        AccountSpec::writable("lb_pair", "dlmm pool account"),
+        AccountSpec::writable("reserve_x", "x token account"),
+        AccountSpec::writable("reserve_y", "y token account"),
+        AccountSpec::writable("oracle", "oracle token account"),
+        AccountSpec::writable("bin_1", "bin 1 token account"),
+        AccountSpec::writable("bin_2", "bin 2 token account"),
+        AccountSpec::writable("bin_3", "bin 3 token account"),
+        AccountSpec::readonly(
+            "token_x_program",
+            "Token program owning mint (token X), Token or Token-2022",
+        ),
+    ];
+    /// Number of instruction accounts of a DLMM leg
+    pub const ACCOUNTS_LEN: usize = COMMON_ACCOUNTS.len() + Self::ACCOUNTS.len();
+
+    /// 用 base mint 买入 mint, `inst_data` 为 `swap`, `swap_exact_out` 或 `swap_with_price_impact`
+    pub fn execute_swap(
+        accounts: &[AccountInfo],
+        inst_data: &[u8],
+        log_level: LogLevel,
+    ) -> ProgramResult {
+        Self::invoke_swap(accounts, inst_data, false, log_level)
+    }
+
+    /// 将 mint 卖回 base mint, 账户与 `execute_swap` 相同
+    pub fn execute_sell(
+        accounts: &[AccountInfo],
+        inst_data: &[u8],
+        log_level: LogLevel,
+    ) -> ProgramResult {
+        Self::invoke_swap(accounts, inst_data, true, log_level)
+    }
+
+    fn invoke_swap(
+        accounts: &[AccountInfo],
+        inst_data: &[u8],
+        sell: bool,
+        log_level: LogLevel,
+    ) -> ProgramResult {
         // 基本验证
-        let [signer_acc, base_mint, fee_collector_acc, base_mint_acc, token_program_id, system_program_id, associated_token_program_id, mint, user_mint_acc, dlmm_program_id, dlmm_event_authority, lb_pair, reserve_x, reserve_y, oracle, bin_1, bin_2, bin_3, ..] =
+        let [signer_acc, base_mint, _fee_collector_acc, base_mint_acc, token_program_id, _system_program_id, _associated_token_program_id, mint, user_mint_acc, dlmm_program_id, dlmm_event_authority, lb_pair, reserve_x, reserve_y, oracle, bin_1, bin_2, bin_3, token_x_program, ..] =
             accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        msg!("swap");
+        log_debug!(log_level, "swap");
         if !signer_acc.is_signer() {
             return Err(ProgramError::MissingRequiredSignature);
         }
+        // token X 是 mint, token Y 是 base_mint, 两者可属于不同的 token program
+        if !mint.is_owned_by(token_x_program.key())
+            || !base_mint.is_owned_by(token_program_id.key())
+        {
+            return Err(DlmmError::InvalidTokenProgram.into());
+        }
+        // DLMM 按 user_token_in 的 mint 决定方向
+        let (user_token_in, user_token_out) = if sell {
+            (user_mint_acc, base_mint_acc)
+        } else {
+            (base_mint_acc, user_mint_acc)
+        };
         // 创建账户元数据
         let account_infos = [
             // 1
@@ -64,9 +135,9 @@ impl DLMMSwap {
             // 4
             reserve_y,
             // 5
-            base_mint_acc,
+            user_token_in,
             // 6
-            user_mint_acc,
+            user_token_out,
             // 7
             mint,
             // 8
@@ -76,7 +147,7 @@ impl DLMMSwap {
             // 10
             dlmm_program_id,
             signer_acc,
-            token_program_id,
+            token_x_program,
             token_program_id,
             dlmm_event_authority,
             dlmm_program_id,
@@ -89,14 +160,14 @@ impl DLMMSwap {
             AccountMeta::readonly(dlmm_program_id.key()),
             AccountMeta::writable(reserve_x.key()),
             AccountMeta::writable(reserve_y.key()),
-            AccountMeta::writable(base_mint_acc.key()),
-            AccountMeta::writable(user_mint_acc.key()),
+            AccountMeta::writable(user_token_in.key()),
+            AccountMeta::writable(user_token_out.key()),
             AccountMeta::readonly(mint.key()),
             AccountMeta::readonly(base_mint.key()),
             AccountMeta::writable(oracle.key()),
             AccountMeta::readonly(dlmm_program_id.key()),
             AccountMeta::writable_signer(signer_acc.key()),
-            AccountMeta::readonly(token_program_id.key()),
+            AccountMeta::readonly(token_x_program.key()),
             AccountMeta::readonly(token_program_id.key()),
             AccountMeta::readonly(dlmm_event_authority.key()),
             AccountMeta::readonly(dlmm_program_id.key()),
@@ -111,11 +182,11 @@ impl DLMMSwap {
             accounts: &account_metas,
             data: inst_data,
         };
-        msg!("call cpi");
+        log_debug!(log_level, "call cpi");
 
         pinocchio::cpi::invoke(&instruction, &account_infos)?;
 
-        log!("DLMM Swap CPI调用成功完成");
+        log_info!(log_level, "DLMM Swap CPI调用成功完成");
 
         Ok(())
     }
@@ -1,6 +1,8 @@
 
 pub mod pump_amm;
 pub mod dlmm;
+pub mod quote;
 
 pub use pump_amm::*;
-pub use dlmm::*; 
\ No newline at end of file
+pub use dlmm::*;
+pub use quote::*; 
\ No newline at end of file
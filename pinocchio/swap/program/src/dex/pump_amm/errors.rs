@@ -11,6 +11,8 @@ pub enum PumpAmmError {
     InvalidTokenPair,
     /// 无效的池账户
     InvalidPoolAccount,
+    /// token program 不是对应 mint 的 owner
+    InvalidTokenProgram,
 }
 
 impl From<PumpAmmError> for ProgramError {
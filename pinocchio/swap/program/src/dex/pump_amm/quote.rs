@@ -0,0 +1,280 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::dex::quote::{price_impact_bps, SwapQuote};
+use crate::error::LossProgramError;
+use crate::state::token_amount;
+
+use super::{PumpAmmError, PumpAmmSwap};
+
+/// anchor 账户 discriminator `account:Pool`
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+/// anchor 账户 discriminator `account:GlobalConfig`
+pub const GLOBAL_CONFIG_DISCRIMINATOR: [u8; 8] = [149, 8, 156, 202, 160, 252, 176, 217];
+
+const BPS_DENOMINATOR: u128 = 10_000;
+/// `GlobalConfig::disable_flags` 中禁止 buy 的位
+const DISABLE_BUY: u8 = 1 << 3;
+
+/// `Pool` 中报价用到的字段
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PumpAmmPool {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub pool_base_token_account: Pubkey,
+    pub pool_quote_token_account: Pubkey,
+    pub coin_creator: Pubkey,
+}
+
+impl PumpAmmPool {
+    /// discriminator, bump, index, creator, 4 keys, lp_mint, lp_supply, coin_creator
+    pub const LEN: usize = 8 + 1 + 2 + 32 * 6 + 8 + 32;
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < Self::LEN || data[..8] != POOL_DISCRIMINATOR {
+            return None;
+        }
+        let key = |offset: usize| data[offset..offset + 32].try_into().unwrap();
+        Some(Self {
+            base_mint: key(43),
+            quote_mint: key(75),
+            pool_base_token_account: key(139),
+            pool_quote_token_account: key(171),
+            coin_creator: key(211),
+        })
+    }
+}
+
+/// `GlobalConfig` 中的手续费设置
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PumpAmmFees {
+    pub lp_fee_bps: u64,
+    pub protocol_fee_bps: u64,
+    pub coin_creator_fee_bps: u64,
+    pub disable_flags: u8,
+}
+
+impl PumpAmmFees {
+    /// discriminator, admin, 2 fees, disable_flags, 8 fee recipients, coin creator fee
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1 + 32 * 8 + 8;
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < Self::LEN || data[..8] != GLOBAL_CONFIG_DISCRIMINATOR {
+            return None;
+        }
+        let fee = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        Some(Self {
+            lp_fee_bps: fee(40),
+            protocol_fee_bps: fee(48),
+            coin_creator_fee_bps: fee(313),
+            disable_flags: data[56],
+        })
+    }
+
+    /// lp, protocol 与 coin creator 费率, 没有 coin creator 的池子不收取 creator fee
+    pub fn fee_bps(&self, pool: &PumpAmmPool) -> [u64; 3] {
+        let coin_creator_fee_bps = if pool.coin_creator == Pubkey::default() {
+            0
+        } else {
+            self.coin_creator_fee_bps
+        };
+        [self.lp_fee_bps, self.protocol_fee_bps, coin_creator_fee_bps]
+    }
+
+    /// 费率来自账户数据, 相加时饱和而非溢出
+    pub fn total_bps(&self, pool: &PumpAmmPool) -> u64 {
+        self.fee_bps(pool)
+            .iter()
+            .fold(0u64, |total, &bps| total.saturating_add(bps))
+    }
+}
+
+/// 用 `quote_amount_in` (含手续费) 买入 base, 与 Pump SDK 的 `buyQuoteInput` 一致
+pub fn quote_buy(
+    base_reserve: u64,
+    quote_reserve: u64,
+    quote_amount_in: u64,
+    total_fee_bps: u64,
+) -> Option<SwapQuote> {
+    if quote_reserve == 0 {
+        return None;
+    }
+    let effective_quote =
+        (quote_amount_in as u128) * BPS_DENOMINATOR / (BPS_DENOMINATOR + total_fee_bps as u128);
+    let base_amount_out = (base_reserve as u128) * effective_quote
+        / (quote_reserve as u128).checked_add(effective_quote)?;
+    if base_amount_out == 0 || base_amount_out >= base_reserve as u128 {
+        return None;
+    }
+    let spot_amount_out = (base_reserve as u128) * effective_quote / (quote_reserve as u128);
+    Some(SwapQuote {
+        amount_in: quote_amount_in,
+        amount_out: base_amount_out as u64,
+        fee: quote_amount_in - effective_quote as u64,
+        price_impact_bps: price_impact_bps(
+            u64::try_from(spot_amount_out).ok()?,
+            base_amount_out as u64,
+        ),
+    })
+}
+
+/// 买入 `base_amount_out`, 与 Pump SDK 的 `buyBaseInput` 一致
+///
+/// Every fee of `fee_bps` is added to the input and rounded up on its own.
+pub fn quote_buy_exact_out(
+    base_reserve: u64,
+    quote_reserve: u64,
+    base_amount_out: u64,
+    fee_bps: &[u64],
+) -> Option<SwapQuote> {
+    if base_amount_out == 0 || base_amount_out >= base_reserve {
+        return None;
+    }
+    let quote_amount_in = ((quote_reserve as u128) * (base_amount_out as u128))
+        .div_ceil((base_reserve - base_amount_out) as u128);
+    let fee = fee_bps.iter().try_fold(0u128, |total, &bps| {
+        total.checked_add(
+            quote_amount_in
+                .checked_mul(bps as u128)?
+                .div_ceil(BPS_DENOMINATOR),
+        )
+    })?;
+    let spot_amount_out = (base_reserve as u128) * quote_amount_in / (quote_reserve as u128).max(1);
+    Some(SwapQuote {
+        amount_in: u64::try_from(quote_amount_in.checked_add(fee)?).ok()?,
+        amount_out: base_amount_out,
+        fee: u64::try_from(fee).ok()?,
+        price_impact_bps: price_impact_bps(u64::try_from(spot_amount_out).ok()?, base_amount_out),
+    })
+}
+
+/// 用不超过 `quote_amount_in` 的 quote 按 exact out 买入尽可能多的 base
+///
+/// Inverts `quote_buy_exact_out`: the curve input is the largest one whose
+/// fees, each rounded up on its own, still fit in `quote_amount_in`, and the
+/// base bought is the most that input covers. A `buy` of the quoted
+/// `amount_out` capped at `quote_amount_in` therefore never exceeds the cap,
+/// which the single rounding of `quote_buy` does not guarantee.
+pub fn quote_buy_within(
+    base_reserve: u64,
+    quote_reserve: u64,
+    quote_amount_in: u64,
+    fee_bps: &[u64],
+) -> Option<SwapQuote> {
+    if quote_reserve == 0 {
+        return None;
+    }
+    let total_bps = fee_bps
+        .iter()
+        .fold(0u128, |total, &bps| total.saturating_add(bps as u128));
+    let cost = |curve_in: u128| {
+        fee_bps.iter().try_fold(curve_in, |total, &bps| {
+            total.checked_add(curve_in.checked_mul(bps as u128)?.div_ceil(BPS_DENOMINATOR))
+        })
+    };
+    // 每项手续费的进位最多多出 1, 向下调整的次数不超过费率项数
+    let mut curve_in =
+        (quote_amount_in as u128) * BPS_DENOMINATOR / BPS_DENOMINATOR.saturating_add(total_bps);
+    while cost(curve_in)? > quote_amount_in as u128 {
+        curve_in -= 1;
+    }
+    let base_amount_out =
+        (base_reserve as u128) * curve_in / (quote_reserve as u128).checked_add(curve_in)?;
+    quote_buy_exact_out(
+        base_reserve,
+        quote_reserve,
+        u64::try_from(base_amount_out).ok()?,
+        fee_bps,
+    )
+}
+
+/// 卖出 `base_amount_in` 换回 quote, 与 Pump SDK 的 `sellBaseInput` 一致
+///
+/// Every fee of `fee_bps` is taken from the output and rounded up on its own.
+pub fn quote_sell(
+    base_reserve: u64,
+    quote_reserve: u64,
+    base_amount_in: u64,
+    fee_bps: &[u64],
+) -> Option<SwapQuote> {
+    if base_reserve == 0 {
+        return None;
+    }
+    let after_fees = |amount: u128| -> Option<(u128, u128)> {
+        let fee = fee_bps.iter().try_fold(0u128, |total, &bps| {
+            total.checked_add(amount.checked_mul(bps as u128)?.div_ceil(BPS_DENOMINATOR))
+        })?;
+        Some((amount.checked_sub(fee)?, fee))
+    };
+    let quote_amount_out = (quote_reserve as u128) * (base_amount_in as u128)
+        / (base_reserve as u128).checked_add(base_amount_in as u128)?;
+    let (amount_out, fee) = after_fees(quote_amount_out)?;
+    if amount_out == 0 {
+        return None;
+    }
+    let (spot_amount_out, _) =
+        after_fees((quote_reserve as u128) * (base_amount_in as u128) / (base_reserve as u128))?;
+    Some(SwapQuote {
+        amount_in: base_amount_in,
+        amount_out: amount_out as u64,
+        fee: fee as u64,
+        price_impact_bps: price_impact_bps(u64::try_from(spot_amount_out).ok()?, amount_out as u64),
+    })
+}
+
+impl PumpAmmSwap {
+    /// 报价用 `amount_in` 按 exact out 能买入的 base, `amount_in` 为实际花费
+    pub fn quote_within(
+        accounts: &[AccountInfo],
+        amount_in: u64,
+    ) -> Result<SwapQuote, ProgramError> {
+        let (base_reserve, quote_reserve, fee_bps) = Self::load_pool(accounts)?;
+        quote_buy_within(base_reserve, quote_reserve, amount_in, &fee_bps)
+            .ok_or(PumpAmmError::InsufficientLiquidity.into())
+    }
+
+    /// 报价买入 `base_amount_out` 所需的 quote, 含手续费
+    pub fn quote_exact_out(
+        accounts: &[AccountInfo],
+        base_amount_out: u64,
+    ) -> Result<SwapQuote, ProgramError> {
+        let (base_reserve, quote_reserve, fee_bps) = Self::load_pool(accounts)?;
+        quote_buy_exact_out(base_reserve, quote_reserve, base_amount_out, &fee_bps)
+            .ok_or(PumpAmmError::InsufficientLiquidity.into())
+    }
+
+    /// 校验池子与配置账户, 返回 base 与 quote 储备和各项费率
+    fn load_pool(accounts: &[AccountInfo]) -> Result<(u64, u64, [u64; 3]), ProgramError> {
+        let [_, base_mint, _, _, _, _, _, mint, _, pump_program_id, pump_global_config_acc, _, _, pool_acc, pool_base_token_acc, pool_quote_token_acc, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if !pool_acc.is_owned_by(pump_program_id.key())
+            || !pump_global_config_acc.is_owned_by(pump_program_id.key())
+        {
+            return Err(LossProgramError::InvalidOwner.into());
+        }
+        let pool = PumpAmmPool::from_bytes(&pool_acc.try_borrow_data()?)
+            .ok_or(PumpAmmError::InvalidPoolAccount)?;
+        let fees = PumpAmmFees::from_bytes(&pump_global_config_acc.try_borrow_data()?)
+            .ok_or(PumpAmmError::InvalidPoolAccount)?;
+        // Pump 的 base 是 mint, quote 是 base_mint
+        if &pool.base_mint != mint.key() || &pool.quote_mint != base_mint.key() {
+            return Err(PumpAmmError::InvalidTokenPair.into());
+        }
+        if &pool.pool_base_token_account != pool_base_token_acc.key()
+            || &pool.pool_quote_token_account != pool_quote_token_acc.key()
+        {
+            return Err(PumpAmmError::InvalidPoolAccount.into());
+        }
+        if fees.disable_flags & DISABLE_BUY != 0 {
+            return Err(LossProgramError::PoolDisabled.into());
+        }
+
+        Ok((
+            token_amount(pool_base_token_acc)?,
+            token_amount(pool_quote_token_acc)?,
+            fees.fee_bps(&pool),
+        ))
+    }
+}
@@ -1,8 +1,10 @@
 use pinocchio::instruction::{AccountMeta, Instruction};
-use pinocchio::{
-    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
-};
-use pinocchio_log::log;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::instruction::layout::{AccountSpec, COMMON_ACCOUNTS};
+use crate::logging::{log_debug, log_info, LogLevel};
+
+use super::PumpAmmError;
 
 // buy:
 // base_amount_out    : u64
@@ -51,10 +53,49 @@ use pinocchio_log::log;
 pub struct PumpAmmSwap;
 
 impl PumpAmmSwap {
-    /// only buy
-    pub fn execute_swap(accounts: &[AccountInfo], inst_data: &[u8]) -> ProgramResult {
+    /// Index of `pool_acc` in the instruction accounts
+    pub const POOL_INDEX: usize = 13;
+    /// Pool, base vault and quote vault, the accounts a swap moves
+    pub const POOL_ACCOUNTS: core::ops::Range<usize> = 13..16;
+    /// Index of `mint_token_program` in the instruction accounts
+    pub const MINT_TOKEN_PROGRAM_INDEX: usize = 19;
+    /// Pump AMM accounts of a leg, after `COMMON_ACCOUNTS`
+    pub const ACCOUNTS: [AccountSpec; 11] = [
+        AccountSpec::readonly("pump_program_id", "Pump program account"),
+        AccountSpec::readonly("pump_global_config_acc", "Pump global config account"),
+        AccountSpec::readonly("pump_event_authority_acc", "Pump event authority account"),
+        AccountSpec::readonly("protocol_fee_recipient", "Pump fee wallet account"),
+        AccountSpec::readonly("pool_acc", "Pool pool account"),
+        AccountSpec::writable("pool_base_token_acc", "Pool token vault account"),
+        AccountSpec::writable("pool_quote_token_acc", "Pool sol vault account"),
+        AccountSpec::writable(
+            "protocol_fee_recipient_token_acc",
+            "fee token wallet account",
+        ),
+        AccountSpec::writable(
+            "coin_creator_vault_ata",
+            "Pool coin creator vault ata account",
+        ),
+        AccountSpec::readonly(
+            "coin_creator_vault_authority",
+            "Pool coin creator vault authority account",
+        ),
+        AccountSpec::readonly(
+            "mint_token_program",
+            "Token program owning mint, Token or Token-2022",
+        ),
+    ];
+    /// Number of instruction accounts of a Pump AMM leg
+    pub const ACCOUNTS_LEN: usize = COMMON_ACCOUNTS.len() + Self::ACCOUNTS.len();
+
+    /// `inst_data` 为 buy 或 sell, 方向由 discriminator 决定, 账户相同
+    pub fn execute_swap(
+        accounts: &[AccountInfo],
+        inst_data: &[u8],
+        log_level: LogLevel,
+    ) -> ProgramResult {
         // 基本验证
-        let [signer_acc, base_mint, _fee_collector_acc, base_mint_acc, token_program_id, system_program_id, associated_token_program_id, mint, user_mint_acc, pump_program_id, pump_global_config_acc, pump_event_authority_acc, protocol_fee_recipient, pool_acc, pool_base_token_acc, pool_quote_token_acc, protocol_fee_recipient_token_acc, coin_creator_vault_ata, coin_creator_vault_authority, ..] =
+        let [signer_acc, base_mint, _fee_collector_acc, base_mint_acc, token_program_id, system_program_id, associated_token_program_id, mint, user_mint_acc, pump_program_id, pump_global_config_acc, pump_event_authority_acc, protocol_fee_recipient, pool_acc, pool_base_token_acc, pool_quote_token_acc, protocol_fee_recipient_token_acc, coin_creator_vault_ata, coin_creator_vault_authority, mint_token_program, ..] =
             accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -62,6 +103,12 @@ impl PumpAmmSwap {
         if !signer_acc.is_signer() {
             return Err(ProgramError::MissingRequiredSignature);
         }
+        // Pump 的 base 是 mint, quote 是 base_mint, 两者可属于不同的 token program
+        if !mint.is_owned_by(mint_token_program.key())
+            || !base_mint.is_owned_by(token_program_id.key())
+        {
+            return Err(PumpAmmError::InvalidTokenProgram.into());
+        }
 
         // 创建账户元数据
         let account_infos = [
@@ -76,7 +123,7 @@ impl PumpAmmSwap {
             pool_quote_token_acc,
             protocol_fee_recipient,
             protocol_fee_recipient_token_acc,
-            token_program_id,
+            mint_token_program,
             token_program_id,
             system_program_id,
             associated_token_program_id,
@@ -97,7 +144,7 @@ impl PumpAmmSwap {
             AccountMeta::writable(pool_quote_token_acc.key()),
             AccountMeta::readonly(protocol_fee_recipient.key()),
             AccountMeta::writable(protocol_fee_recipient_token_acc.key()),
-            AccountMeta::readonly(token_program_id.key()),
+            AccountMeta::readonly(mint_token_program.key()),
             AccountMeta::readonly(token_program_id.key()),
             AccountMeta::readonly(system_program_id.key()),
             AccountMeta::readonly(associated_token_program_id.key()),
@@ -106,18 +153,18 @@ impl PumpAmmSwap {
             AccountMeta::writable(coin_creator_vault_ata.key()),
             AccountMeta::readonly(coin_creator_vault_authority.key()),
         ];
-        log!("inst data pump {}", inst_data);
+        log_debug!(log_level, "inst data pump {}", inst_data);
         // 创建指令
         let instruction = Instruction {
             program_id: pump_program_id.key(),
             accounts: &account_metas,
             data: inst_data,
         };
-        msg!("call cpi");
+        log_debug!(log_level, "call cpi");
 
         pinocchio::cpi::invoke(&instruction, &account_infos)?;
 
-        log!("Pump AMM交换CPI调用成功完成");
+        log_info!(log_level, "Pump AMM交换CPI调用成功完成");
         Ok(())
     }
 }
@@ -0,0 +1,46 @@
+//! 只读报价
+//!
+//! Each quoter reads the accounts of its swap leg and replays the DEX math
+//! without any CPI, so a simulated `Quote` instruction prices a route exactly
+//! as the program sees it.
+use crate::state::DataLen;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// `Quote` 指令通过 `set_return_data` 返回的报价
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SwapQuote {
+    /// Base spent, fees included
+    pub amount_in: u64,
+    /// Mint expected in the user output account
+    pub amount_out: u64,
+    /// Part of `amount_in` paid as DEX fees
+    pub fee: u64,
+    /// Shortfall of `amount_out` against the pre-trade price, in bps
+    pub price_impact_bps: u16,
+}
+
+impl DataLen for SwapQuote {
+    const LEN: usize = core::mem::size_of::<SwapQuote>();
+}
+
+impl SwapQuote {
+    /// Reads a quote from the return data of a `Quote` instruction.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN {
+            return None;
+        }
+        // SAFETY: the length matches and every bit pattern of a quote is valid.
+        Some(unsafe { core::ptr::read_unaligned(data.as_ptr() as *const Self) })
+    }
+}
+
+/// `spot_amount_out` 为扣费后的输入按交易前价格可得的数量
+pub fn price_impact_bps(spot_amount_out: u64, amount_out: u64) -> u16 {
+    if spot_amount_out == 0 {
+        return 0;
+    }
+    let shortfall = spot_amount_out.saturating_sub(amount_out) as u128;
+    (shortfall * BPS_DENOMINATOR / spot_amount_out as u128) as u16
+}
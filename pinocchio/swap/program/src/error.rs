@@ -27,6 +27,28 @@ pub enum LossProgramError {
     AuthorizationError = 10,
 
     InvalidOwner = 11,
+    /// 闪电贷未能归还
+    FlashLoanNotRepaid = 12,
+    /// 收益低于 min_profit_threshold
+    ProfitBelowThreshold = 13,
+    /// 无效的 Pyth 价格账户
+    InvalidOracleAccount = 14,
+    /// Pyth 价格过期
+    StaleOraclePrice = 15,
+    /// 成交价偏离预言机价格过大
+    PriceDeviationExceeded = 16,
+    /// 包装 SOL 时 base_mint 不是 native mint
+    BaseMintNotNative = 17,
+    /// 剩余计算单元不足以完成交易
+    ComputeBudgetExhausted = 18,
+    /// 经 CPI 嵌套调用, 包括 DEX 程序回调 swap_program
+    NestedInvocation = 19,
+    /// 交易中没有 ComputeBudget 指令
+    MissingComputeBudget = 20,
+    /// swap 之前有 ComputeBudget 与 swap_program 以外的指令
+    InstructionPositionNotAllowed = 21,
+    /// 交易中的其他指令引用了 leg 的池子账户
+    PoolAccountShared = 22,
 }
 
 impl From<LossProgramError> for ProgramError {
@@ -0,0 +1,173 @@
+//! 结构化交易事件
+//!
+//! Every event is logged with `sol_log_data` as two fields, the 8 byte
+//! discriminator (`sha256("event:<Name>")[..8]`) and the packed little endian
+//! event, which shows up as a `Program data:` line in the transaction logs.
+use pinocchio::{log::sol_log_data, pubkey::Pubkey};
+
+use crate::state::{to_bytes, DataLen};
+
+pub trait Event: DataLen + Copy {
+    const DISCRIMINATOR: [u8; 8];
+
+    fn emit(&self) {
+        // SAFETY: events are `repr(C, packed)` with `LEN == size_of`.
+        sol_log_data(&[&Self::DISCRIMINATOR, unsafe { to_bytes(self) }]);
+    }
+
+    /// Reads an event from its packed bytes, without the discriminator.
+    fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN {
+            return None;
+        }
+        // SAFETY: the length matches and every bit pattern of an event is valid.
+        Some(unsafe { core::ptr::read_unaligned(data.as_ptr() as *const Self) })
+    }
+}
+
+/// 单笔交易 (leg) 事件
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwapEvent {
+    /// `SupportDex` of the leg
+    pub dex: u8,
+    /// Pump AMM pool or DLMM lb pair
+    pub pool: Pubkey,
+    /// Amount that left the user input account
+    pub amount_in: u64,
+    /// Amount that reached the user output account
+    pub amount_out: u64,
+    pub slot: u64,
+}
+
+impl DataLen for SwapEvent {
+    const LEN: usize = core::mem::size_of::<SwapEvent>();
+}
+
+impl Event for SwapEvent {
+    const DISCRIMINATOR: [u8; 8] = [64, 198, 205, 232, 38, 8, 113, 226];
+}
+
+/// 套利汇总事件, 以 base mint 计
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ArbitrageEvent {
+    /// `SupportDex` of the first leg
+    pub dex: u8,
+    pub legs: u8,
+    /// Base spent by the arbitrage
+    pub amount_in: u64,
+    /// Base received back by the arbitrage
+    pub amount_out: u64,
+    /// `amount_out - amount_in`
+    pub profit: i64,
+    pub slot: u64,
+}
+
+impl DataLen for ArbitrageEvent {
+    const LEN: usize = core::mem::size_of::<ArbitrageEvent>();
+}
+
+impl Event for ArbitrageEvent {
+    const DISCRIMINATOR: [u8; 8] = [145, 200, 172, 52, 248, 31, 199, 22];
+}
+
+/// 剩余计算单元不足, 在 `no_failure` 下跳过交易时的事件
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BudgetExhaustedEvent {
+    /// `SupportDex` of the skipped leg
+    pub dex: u8,
+    /// Units the route was estimated to need
+    pub required_units: u64,
+    /// Units left when the route was skipped
+    pub remaining_units: u64,
+    pub slot: u64,
+}
+
+impl DataLen for BudgetExhaustedEvent {
+    const LEN: usize = core::mem::size_of::<BudgetExhaustedEvent>();
+}
+
+impl Event for BudgetExhaustedEvent {
+    const DISCRIMINATOR: [u8; 8] = [242, 200, 179, 184, 133, 203, 221, 246];
+}
+
+#[cfg(feature = "std")]
+pub use decoder::*;
+
+#[cfg(feature = "std")]
+mod decoder {
+    use std::vec::Vec;
+
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use pinocchio::pubkey::Pubkey;
+
+    use super::{ArbitrageEvent, BudgetExhaustedEvent, Event, SwapEvent};
+
+    /// Event emitted by swap_program, decoded from transaction logs.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum SwapProgramEvent {
+        Swap(SwapEvent),
+        Arbitrage(ArbitrageEvent),
+        BudgetExhausted(BudgetExhaustedEvent),
+    }
+
+    impl SwapProgramEvent {
+        /// Decodes the fields of one `sol_log_data` call.
+        pub fn decode(fields: &[&[u8]]) -> Option<Self> {
+            let [discriminator, data] = fields else {
+                return None;
+            };
+            match <[u8; 8]>::try_from(*discriminator).ok()? {
+                SwapEvent::DISCRIMINATOR => SwapEvent::from_bytes(data).map(Self::Swap),
+                ArbitrageEvent::DISCRIMINATOR => {
+                    ArbitrageEvent::from_bytes(data).map(Self::Arbitrage)
+                }
+                BudgetExhaustedEvent::DISCRIMINATOR => {
+                    BudgetExhaustedEvent::from_bytes(data).map(Self::BudgetExhausted)
+                }
+                _ => None,
+            }
+        }
+
+        /// Decodes the events of `program_id` from the log messages of a
+        /// transaction, in emission order.
+        ///
+        /// Only `Program data:` lines logged while `program_id` is the running
+        /// program are considered, data logged by the DEX programs it invokes is
+        /// skipped.
+        pub fn from_logs<S: AsRef<str>>(program_id: &Pubkey, logs: &[S]) -> Vec<Self> {
+            let invoke_prefix = std::format!(
+                "Program {} invoke [",
+                bs58::encode(program_id).into_string()
+            );
+            let mut stack: Vec<bool> = Vec::new();
+            let mut events = Vec::new();
+
+            for log in logs.iter().map(AsRef::as_ref) {
+                if let Some(data) = log.strip_prefix("Program data: ") {
+                    if stack.last() != Some(&true) {
+                        continue;
+                    }
+                    let Some(fields) = data
+                        .split(' ')
+                        .map(|field| STANDARD.decode(field).ok())
+                        .collect::<Option<Vec<_>>>()
+                    else {
+                        continue;
+                    };
+                    let fields: Vec<&[u8]> = fields.iter().map(Vec::as_slice).collect();
+                    events.extend(Self::decode(&fields));
+                } else if log.starts_with("Program log: ") {
+                    continue;
+                } else if log.contains(" invoke [") {
+                    stack.push(log.starts_with(&invoke_prefix));
+                } else if log.ends_with(" success") || log.contains(" failed: ") {
+                    stack.pop();
+                }
+            }
+            events
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! 闪电贷 CPI
+//!
+//! A lender takes `borrow(amount: u64)` and `repay()`, both with the accounts
+//! `[lending_pool (w), vault (w), borrower_token (w), borrower (s), token_program]`.
+//! `repay` pulls the principal plus the lender fee out of the borrower token
+//! account. Only the lenders of `FLASH_LENDERS` are called, each with its own
+//! discriminators and lending pool layout, so supporting another lender with
+//! this CPI interface is one more entry. `mocks/flash_lender_mock` implements
+//! the interface at `MOCK_LENDER`.
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::error::LossProgramError;
+use crate::instruction::layout::AccountSpec;
+use crate::state::token_amount;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// 可调用的 lender: 程序地址, 指令 discriminator 与 lending pool 布局
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlashLender {
+    pub program_id: Pubkey,
+    pub borrow_discriminator: [u8; 8],
+    pub repay_discriminator: [u8; 8],
+    /// lending pool 账户的长度
+    pub pool_len: usize,
+    /// lending pool 中 vault 地址的偏移
+    pub vault_offset: usize,
+    /// lending pool 中 `fee_bps: u16` 的偏移
+    pub fee_bps_offset: usize,
+}
+
+impl FlashLender {
+    /// `program_id` 对应的 lender, 不在 `FLASH_LENDERS` 中时为 `None`
+    pub fn find(program_id: &Pubkey) -> Option<&'static FlashLender> {
+        FLASH_LENDERS
+            .iter()
+            .find(|lender| &lender.program_id == program_id)
+    }
+}
+
+/// `mocks/flash_lender_mock`, lending pool: mint, vault, fee_bps: u16, outstanding: u64, bump
+pub const MOCK_LENDER: FlashLender = FlashLender {
+    program_id: pinocchio_pubkey::pubkey!("qCVbHs3syQ77SSf54u49XchGy7WBYhcZGrSaxYqqfZk"),
+    borrow_discriminator: [228, 253, 131, 202, 207, 116, 89, 18],
+    repay_discriminator: [234, 103, 67, 82, 208, 234, 219, 166],
+    pool_len: 32 + 32 + 2 + 8 + 1,
+    vault_offset: 32,
+    fee_bps_offset: 64,
+};
+
+/// 允许调用的 lender
+pub const FLASH_LENDERS: [FlashLender; 1] = [MOCK_LENDER];
+
+const _: () = {
+    let mut i = 0;
+    while i < FLASH_LENDERS.len() {
+        let lender = &FLASH_LENDERS[i];
+        assert!(lender.vault_offset + 32 <= lender.pool_len);
+        assert!(lender.fee_bps_offset + 2 <= lender.pool_len);
+        i += 1;
+    }
+};
+
+/// 闪电贷账户, 跟在 `RoundTrip` 的 signer PDA 之后
+pub struct FlashLoan<'a> {
+    lender: &'static FlashLender,
+    lender_program: &'a AccountInfo,
+    lending_pool: &'a AccountInfo,
+    lender_vault: &'a AccountInfo,
+    borrower_token: &'a AccountInfo,
+    borrower: &'a AccountInfo,
+    token_program: &'a AccountInfo,
+    fee_bps: u16,
+}
+
+impl<'a> FlashLoan<'a> {
+    /// Trailing accounts, passed when `flash_loan_amount > 0`
+    pub const ACCOUNTS: [AccountSpec; 3] = [
+        AccountSpec::readonly("lender_program", "Flash lender program").optional(),
+        AccountSpec::writable("lending_pool", "Flash lender pool").optional(),
+        AccountSpec::writable("lender_vault", "Flash lender vault of the base mint").optional(),
+    ];
+    /// Number of trailing accounts: lender program, lending pool, lender vault
+    pub const ACCOUNTS_LEN: usize = Self::ACCOUNTS.len();
+
+    pub fn load(
+        trailing_accounts: &'a [AccountInfo],
+        borrower: &'a AccountInfo,
+        borrower_token: &'a AccountInfo,
+        token_program: &'a AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        let [lender_program, lending_pool, lender_vault, ..] = trailing_accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let lender =
+            FlashLender::find(lender_program.key()).ok_or(ProgramError::IncorrectProgramId)?;
+        if !lending_pool.is_owned_by(&lender.program_id) {
+            return Err(LossProgramError::InvalidOwner.into());
+        }
+        let fee_bps = {
+            let data = lending_pool.try_borrow_data()?;
+            let (vault, fee) = (lender.vault_offset, lender.fee_bps_offset);
+            if data.len() != lender.pool_len || data[vault..vault + 32] != *lender_vault.key() {
+                return Err(LossProgramError::InvalidPoolAccount.into());
+            }
+            u16::from_le_bytes([data[fee], data[fee + 1]])
+        };
+        Ok(Self {
+            lender,
+            lender_program,
+            lending_pool,
+            lender_vault,
+            borrower_token,
+            borrower,
+            token_program,
+            fee_bps,
+        })
+    }
+
+    fn invoke(&self, data: &[u8]) -> ProgramResult {
+        let account_metas = [
+            AccountMeta::writable(self.lending_pool.key()),
+            AccountMeta::writable(self.lender_vault.key()),
+            AccountMeta::writable(self.borrower_token.key()),
+            AccountMeta::writable_signer(self.borrower.key()),
+            AccountMeta::readonly(self.token_program.key()),
+        ];
+        let instruction = Instruction {
+            program_id: self.lender_program.key(),
+            accounts: &account_metas,
+            data,
+        };
+        pinocchio::cpi::invoke(
+            &instruction,
+            &[
+                self.lending_pool,
+                self.lender_vault,
+                self.borrower_token,
+                self.borrower,
+                self.token_program,
+            ],
+        )
+    }
+
+    pub fn borrow(&self, amount: u64) -> ProgramResult {
+        let mut data = [0u8; 16];
+        data[0..8].copy_from_slice(&self.lender.borrow_discriminator);
+        data[8..16].copy_from_slice(&amount.to_le_bytes());
+        self.invoke(&data)
+    }
+
+    /// 借入 `amount` 应归还的本金加手续费, 手续费向上取整
+    pub fn owed(&self, amount: u64) -> Result<u64, ProgramError> {
+        let fee = (amount as u128 * self.fee_bps as u128).div_ceil(BPS_DENOMINATOR);
+        u64::try_from(fee)
+            .ok()
+            .and_then(|fee| amount.checked_add(fee))
+            .ok_or(LossProgramError::ArithmeticError.into())
+    }
+
+    /// 归还本金和手续费, 并确认 vault 收回了两者之和
+    pub fn repay(&self, amount: u64) -> ProgramResult {
+        let owed = self.owed(amount)?;
+        if token_amount(self.borrower_token)? < owed {
+            return Err(LossProgramError::FlashLoanNotRepaid.into());
+        }
+        let vault_before = token_amount(self.lender_vault)?;
+
+        self.invoke(&self.lender.repay_discriminator)?;
+
+        let repaid = token_amount(self.lender_vault)?.saturating_sub(vault_before);
+        if repaid < owed {
+            return Err(LossProgramError::FlashLoanNotRepaid.into());
+        }
+        Ok(())
+    }
+}
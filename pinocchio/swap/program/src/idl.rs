@@ -0,0 +1,481 @@
+//! IDL 生成
+//!
+//! Builds `client/idl/swap_program.json`, in the shank IDL format solita and
+//! codama read, from the account layouts the adapters declare
+//! (`instruction::layout`), the error enums and the event structs. Run
+//! `cargo run --features idl --bin idl > ../client/idl/swap_program.json`
+//! after changing any of them; `src/tests/idl.rs` fails while the checked-in
+//! file differs. The ix data and event field tables go through `fields!`, which
+//! checks every name and offset against the packed struct at compile time.
+use std::{string::String, vec::Vec};
+
+use pinocchio::program_error::ProgramError;
+use serde_json::{json, Value};
+
+use crate::dex::{DLMMSwap, DlmmError, PumpAmmError, PumpAmmSwap};
+use crate::error::LossProgramError;
+use crate::events::{ArbitrageEvent, BudgetExhaustedEvent, Event, SwapEvent};
+use crate::flash_loan::FlashLoan;
+use crate::instruction::layout::{AccountSpec, COMMON_ACCOUNTS, SIGNER_PDA_ACCOUNTS};
+use crate::instruction::{
+    ArbitrageIxData, LossProgramInstruction, OperatorLimitsIxData, QuoteIxData, RoundTripIxData,
+    SupportDex, SET_OPERATOR_LIMITS_ACCOUNTS,
+};
+use crate::introspection::INSTRUCTIONS_SYSVAR_ACCOUNT;
+use crate::oracle::PRICE_UPDATE_ACCOUNT;
+
+/// 字段名与 IDL 类型, 按内存顺序
+pub type Fields = &'static [(&'static str, IdlType)];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IdlType {
+    Bool,
+    U8,
+    U16,
+    I32,
+    U64,
+    I64,
+    PublicKey,
+    /// 定长字节数组
+    Bytes(usize),
+    Defined(&'static str),
+}
+
+impl IdlType {
+    fn to_json(self) -> Value {
+        match self {
+            IdlType::Bool => json!("bool"),
+            IdlType::U8 => json!("u8"),
+            IdlType::U16 => json!("u16"),
+            IdlType::I32 => json!("i32"),
+            IdlType::U64 => json!("u64"),
+            IdlType::I64 => json!("i64"),
+            IdlType::PublicKey => json!("publicKey"),
+            IdlType::Bytes(len) => json!({ "array": ["u8", len] }),
+            IdlType::Defined(name) => json!({ "defined": name }),
+        }
+    }
+
+    /// 打包后的字节数, 自定义类型只有 `SupportDex`
+    pub const fn size(self) -> usize {
+        match self {
+            IdlType::Bool | IdlType::U8 | IdlType::Defined(_) => 1,
+            IdlType::U16 => 2,
+            IdlType::I32 => 4,
+            IdlType::U64 | IdlType::I64 => 8,
+            IdlType::PublicKey => 32,
+            IdlType::Bytes(len) => len,
+        }
+    }
+}
+
+/// 按结构体字段生成 `Fields`
+///
+/// Every name must be a field of `$struct`, and its `offset_of!` must equal the
+/// sizes listed before it, so a renamed, reordered or resized field of the
+/// packed struct fails to compile instead of drifting from the IDL.
+macro_rules! fields {
+    ($struct:ty { $($field:ident: $ty:expr),* $(,)? }) => {{
+        const _: () = {
+            let mut offset = 0;
+            $(
+                assert!(
+                    core::mem::offset_of!($struct, $field) == offset,
+                    concat!("IDL offset of ", stringify!($struct), "::", stringify!($field))
+                );
+                offset += $ty.size();
+            )*
+            assert!(
+                offset == core::mem::size_of::<$struct>(),
+                concat!("IDL fields of ", stringify!($struct), " miss its tail")
+            );
+        };
+        &[$((stringify!($field), $ty)),*]
+    }};
+}
+
+pub const SUPPORT_DEX_VARIANTS: [SupportDex; 7] = [
+    SupportDex::Pump,
+    SupportDex::PumpAmm,
+    SupportDex::RaydiumAmm,
+    SupportDex::RaydiumCP,
+    SupportDex::RaydiumCLMM,
+    SupportDex::DLMM,
+    SupportDex::WhirlPool,
+];
+
+pub const ARBITRAGE_IX_DATA_FIELDS: Fields = fields!(ArbitrageIxData {
+    dex: IdlType::Defined("SupportDex"),
+    max_bin_to_process: IdlType::U64,
+    min_profit_threshold: IdlType::U64,
+    no_failure: IdlType::Bool,
+    flags: IdlType::U8,
+    max_price_deviation_bps: IdlType::U16,
+    wrap_lamports: IdlType::U64,
+    slippage_bps: IdlType::U16,
+    amount_out: IdlType::U64,
+    max_price_impact_bps: IdlType::U16,
+    active_id: IdlType::I32,
+    price_feed_id: IdlType::Bytes(32),
+});
+
+pub const QUOTE_IX_DATA_FIELDS: Fields = fields!(QuoteIxData {
+    dex: IdlType::Defined("SupportDex"),
+    amount_in: IdlType::U64,
+});
+
+pub const ROUND_TRIP_IX_DATA_FIELDS: Fields = fields!(RoundTripIxData {
+    buy_dex: IdlType::Defined("SupportDex"),
+    sell_dex: IdlType::Defined("SupportDex"),
+    amount_in: IdlType::U64,
+    min_profit_threshold: IdlType::U64,
+    max_bin_to_process: IdlType::U64,
+    flags: IdlType::U8,
+    flash_loan_amount: IdlType::U64,
+    no_failure: IdlType::Bool,
+});
+
+pub const OPERATOR_LIMITS_IX_DATA_FIELDS: Fields = fields!(OperatorLimitsIxData {
+    max_notional_per_trade: IdlType::U64,
+    max_window_notional: IdlType::U64,
+    window_slots: IdlType::U64,
+    max_trades_per_slot: IdlType::U64,
+});
+
+pub const SWAP_EVENT_FIELDS: Fields = fields!(SwapEvent {
+    dex: IdlType::U8,
+    pool: IdlType::PublicKey,
+    amount_in: IdlType::U64,
+    amount_out: IdlType::U64,
+    slot: IdlType::U64,
+});
+
+pub const ARBITRAGE_EVENT_FIELDS: Fields = fields!(ArbitrageEvent {
+    dex: IdlType::U8,
+    legs: IdlType::U8,
+    amount_in: IdlType::U64,
+    amount_out: IdlType::U64,
+    profit: IdlType::I64,
+    slot: IdlType::U64,
+});
+
+pub const BUDGET_EXHAUSTED_EVENT_FIELDS: Fields = fields!(BudgetExhaustedEvent {
+    dex: IdlType::U8,
+    required_units: IdlType::U64,
+    remaining_units: IdlType::U64,
+    slot: IdlType::U64,
+});
+
+pub const LOSS_PROGRAM_ERRORS: [LossProgramError; 23] = [
+    LossProgramError::PdaMismatch,
+    LossProgramError::ArithmeticError,
+    LossProgramError::InvalidPoolAccount,
+    LossProgramError::InvalidInstructionData,
+    LossProgramError::SlippageExceeded,
+    LossProgramError::InsufficientLiquidity,
+    LossProgramError::InvalidTokenPair,
+    LossProgramError::PoolDisabled,
+    LossProgramError::AmountTooSmall,
+    LossProgramError::AmountTooLarge,
+    LossProgramError::AuthorizationError,
+    LossProgramError::InvalidOwner,
+    LossProgramError::FlashLoanNotRepaid,
+    LossProgramError::ProfitBelowThreshold,
+    LossProgramError::InvalidOracleAccount,
+    LossProgramError::StaleOraclePrice,
+    LossProgramError::PriceDeviationExceeded,
+    LossProgramError::BaseMintNotNative,
+    LossProgramError::ComputeBudgetExhausted,
+    LossProgramError::NestedInvocation,
+    LossProgramError::MissingComputeBudget,
+    LossProgramError::InstructionPositionNotAllowed,
+    LossProgramError::PoolAccountShared,
+];
+
+pub const PUMP_AMM_ERRORS: [PumpAmmError; 5] = [
+    PumpAmmError::InsufficientLiquidity,
+    PumpAmmError::SlippageExceeded,
+    PumpAmmError::InvalidTokenPair,
+    PumpAmmError::InvalidPoolAccount,
+    PumpAmmError::InvalidTokenProgram,
+];
+
+pub const DLMM_ERRORS: [DlmmError; 6] = [
+    DlmmError::InsufficientLiquidity,
+    DlmmError::SlippageExceeded,
+    DlmmError::InvalidTokenPair,
+    DlmmError::NoBinActive,
+    DlmmError::BinLimitExceeded,
+    DlmmError::InvalidTokenProgram,
+];
+
+fn loss_program_error_msg(error: LossProgramError) -> &'static str {
+    match error {
+        LossProgramError::PdaMismatch => "PDA mismatch",
+        LossProgramError::ArithmeticError => "Arithmetic error",
+        LossProgramError::InvalidPoolAccount => "Invalid pool account",
+        LossProgramError::InvalidInstructionData => "Invalid instruction data",
+        LossProgramError::SlippageExceeded => "Slippage exceeded",
+        LossProgramError::InsufficientLiquidity => "Insufficient liquidity",
+        LossProgramError::InvalidTokenPair => "Invalid token pair",
+        LossProgramError::PoolDisabled => "Pool disabled",
+        LossProgramError::AmountTooSmall => "Amount too small",
+        LossProgramError::AmountTooLarge => "Amount too large",
+        LossProgramError::AuthorizationError => "Signer is not authorized",
+        LossProgramError::InvalidOwner => "Account has an unexpected owner",
+        LossProgramError::FlashLoanNotRepaid => "Flash loan not repaid",
+        LossProgramError::ProfitBelowThreshold => "Profit below min_profit_threshold",
+        LossProgramError::InvalidOracleAccount => "Invalid Pyth price account",
+        LossProgramError::StaleOraclePrice => "Pyth price is stale",
+        LossProgramError::PriceDeviationExceeded => {
+            "Execution price deviates too much from the oracle price"
+        }
+        LossProgramError::BaseMintNotNative => "base_mint is not the native mint",
+        LossProgramError::ComputeBudgetExhausted => "Not enough compute units left for the route",
+        LossProgramError::NestedInvocation => "Instruction must be a top-level instruction",
+        LossProgramError::MissingComputeBudget => "Transaction has no ComputeBudget instruction",
+        LossProgramError::InstructionPositionNotAllowed => {
+            "Only ComputeBudget and swap_program instructions may precede the swap"
+        }
+        LossProgramError::PoolAccountShared => {
+            "Another instruction references a pool account of the leg"
+        }
+    }
+}
+
+fn pump_amm_error_msg(error: PumpAmmError) -> &'static str {
+    match error {
+        PumpAmmError::InsufficientLiquidity => "Pump AMM pool has insufficient liquidity",
+        PumpAmmError::SlippageExceeded => "Pump AMM slippage exceeded",
+        PumpAmmError::InvalidTokenPair => "Pump AMM pool does not trade this pair",
+        PumpAmmError::InvalidPoolAccount => "Invalid Pump AMM pool account",
+        PumpAmmError::InvalidTokenProgram => "Token program does not own the mint",
+    }
+}
+
+fn dlmm_error_msg(error: DlmmError) -> &'static str {
+    match error {
+        DlmmError::InsufficientLiquidity => "DLMM pair has insufficient liquidity",
+        DlmmError::SlippageExceeded => "DLMM slippage exceeded",
+        DlmmError::InvalidTokenPair => "DLMM pair does not trade this pair",
+        DlmmError::NoBinActive => "No active DLMM bin",
+        DlmmError::BinLimitExceeded => "DLMM bin limit exceeded",
+        DlmmError::InvalidTokenProgram => "Token program does not own the mint",
+    }
+}
+
+/// DexSwap 的完整账户: 公共账户, leg 账户, signer PDA, 可选的尾部账户
+pub fn swap_accounts(leg_accounts: &[AccountSpec]) -> Vec<AccountSpec> {
+    let mut accounts = COMMON_ACCOUNTS.to_vec();
+    accounts.extend_from_slice(leg_accounts);
+    accounts.extend_from_slice(&SIGNER_PDA_ACCOUNTS);
+    accounts.push(PRICE_UPDATE_ACCOUNT);
+    accounts.push(INSTRUCTIONS_SYSVAR_ACCOUNT);
+    accounts
+}
+
+/// Quote 的账户, 与 swap 的 leg 账户相同
+pub fn quote_accounts(leg_accounts: &[AccountSpec]) -> Vec<AccountSpec> {
+    let mut accounts = COMMON_ACCOUNTS.to_vec();
+    accounts.extend_from_slice(leg_accounts);
+    accounts
+}
+
+/// RoundTrip 的账户: 公共账户, 买入与卖出 leg 的账户, signer PDA, 可选的闪电贷账户
+///
+/// Pump AMM 与 DLMM 的 leg 账户名互不相同, 无需加前缀.
+pub fn round_trip_accounts(
+    buy_accounts: &[AccountSpec],
+    sell_accounts: &[AccountSpec],
+) -> Vec<AccountSpec> {
+    let mut accounts = COMMON_ACCOUNTS.to_vec();
+    accounts.extend_from_slice(buy_accounts);
+    accounts.extend_from_slice(sell_accounts);
+    accounts.extend_from_slice(&SIGNER_PDA_ACCOUNTS);
+    accounts.extend_from_slice(&FlashLoan::ACCOUNTS);
+    accounts
+}
+
+fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+fn account_json(account: &AccountSpec) -> Value {
+    let mut value = json!({
+        "name": camel_case(account.name),
+        "isMut": account.writable,
+        "isSigner": account.signer,
+    });
+    if account.optional {
+        value["isOptional"] = json!(true);
+    }
+    value["docs"] = json!([account.desc]);
+    value
+}
+
+fn instruction_json(
+    name: &str,
+    accounts: &[AccountSpec],
+    args_type: &str,
+    discriminant: LossProgramInstruction,
+) -> Value {
+    json!({
+        "name": name,
+        "accounts": accounts.iter().map(account_json).collect::<Vec<_>>(),
+        "args": [{
+            "name": camel_case(&args_name(args_type)),
+            "type": { "defined": args_type },
+        }],
+        "discriminant": { "type": "u8", "value": discriminant as u8 },
+    })
+}
+
+/// `ArbitrageIxData` -> `arbitrage_ix_data`
+fn args_name(type_name: &str) -> String {
+    let mut name = String::new();
+    for (i, c) in type_name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            name.push('_');
+        }
+        name.extend(c.to_lowercase());
+    }
+    name
+}
+
+fn fields_json(fields: Fields) -> Vec<Value> {
+    fields
+        .iter()
+        .map(|(name, ty)| json!({ "name": camel_case(name), "type": ty.to_json() }))
+        .collect()
+}
+
+fn struct_json(name: &str, fields: Fields) -> Value {
+    json!({
+        "name": name,
+        "type": { "kind": "struct", "fields": fields_json(fields) },
+    })
+}
+
+fn event_json<E: Event>(name: &str, fields: Fields) -> Value {
+    json!({
+        "name": name,
+        "discriminator": E::DISCRIMINATOR,
+        "fields": fields
+            .iter()
+            .map(|(name, ty)| json!({ "name": camel_case(name), "type": ty.to_json(), "index": false }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn error_json(error: impl Into<ProgramError>, name: String, msg: &str) -> Value {
+    let ProgramError::Custom(code) = error.into() else {
+        unreachable!("program errors are custom errors");
+    };
+    json!({ "code": code, "name": name, "msg": msg })
+}
+
+/// swap_program 的 IDL
+pub fn idl() -> Value {
+    // DEX 错误加上前缀, 与 `LossProgramError` 的同名错误区分
+    let errors = LOSS_PROGRAM_ERRORS
+        .iter()
+        .map(|&e| error_json(e, std::format!("{e:?}"), loss_program_error_msg(e)))
+        .chain(
+            PUMP_AMM_ERRORS
+                .iter()
+                .map(|&e| error_json(e, std::format!("PumpAmm{e:?}"), pump_amm_error_msg(e))),
+        )
+        .chain(
+            DLMM_ERRORS
+                .iter()
+                .map(|&e| error_json(e, std::format!("Dlmm{e:?}"), dlmm_error_msg(e))),
+        )
+        .collect::<Vec<_>>();
+
+    json!({
+        "version": "0.1.0",
+        "name": "swap_program",
+        "instructions": [
+            instruction_json(
+                "PumpAMMSwap",
+                &swap_accounts(&PumpAmmSwap::ACCOUNTS),
+                "ArbitrageIxData",
+                LossProgramInstruction::DexSwap,
+            ),
+            instruction_json(
+                "DLMMSwap",
+                &swap_accounts(&DLMMSwap::ACCOUNTS),
+                "ArbitrageIxData",
+                LossProgramInstruction::DexSwap,
+            ),
+            instruction_json(
+                "PumpAMMQuote",
+                &quote_accounts(&PumpAmmSwap::ACCOUNTS),
+                "QuoteIxData",
+                LossProgramInstruction::Quote,
+            ),
+            instruction_json(
+                "DLMMQuote",
+                &quote_accounts(&DLMMSwap::ACCOUNTS),
+                "QuoteIxData",
+                LossProgramInstruction::Quote,
+            ),
+            instruction_json(
+                "SetOperatorLimits",
+                &SET_OPERATOR_LIMITS_ACCOUNTS,
+                "OperatorLimitsIxData",
+                LossProgramInstruction::SetOperatorLimits,
+            ),
+            instruction_json(
+                "PumpAMMToDLMMRoundTrip",
+                &round_trip_accounts(&PumpAmmSwap::ACCOUNTS, &DLMMSwap::ACCOUNTS),
+                "RoundTripIxData",
+                LossProgramInstruction::RoundTrip,
+            ),
+            instruction_json(
+                "DLMMToPumpAMMRoundTrip",
+                &round_trip_accounts(&DLMMSwap::ACCOUNTS, &PumpAmmSwap::ACCOUNTS),
+                "RoundTripIxData",
+                LossProgramInstruction::RoundTrip,
+            ),
+        ],
+        "types": [
+            struct_json("ArbitrageIxData", ARBITRAGE_IX_DATA_FIELDS),
+            struct_json("OperatorLimitsIxData", OPERATOR_LIMITS_IX_DATA_FIELDS),
+            struct_json("QuoteIxData", QUOTE_IX_DATA_FIELDS),
+            struct_json("RoundTripIxData", ROUND_TRIP_IX_DATA_FIELDS),
+            {
+                "name": "SupportDex",
+                "type": {
+                    "kind": "enum",
+                    "variants": SUPPORT_DEX_VARIANTS
+                        .iter()
+                        .map(|dex| json!({ "name": std::format!("{dex:?}") }))
+                        .collect::<Vec<_>>(),
+                },
+            },
+        ],
+        "events": [
+            event_json::<SwapEvent>("SwapEvent", SWAP_EVENT_FIELDS),
+            event_json::<ArbitrageEvent>("ArbitrageEvent", ARBITRAGE_EVENT_FIELDS),
+            event_json::<BudgetExhaustedEvent>("BudgetExhaustedEvent", BUDGET_EXHAUSTED_EVENT_FIELDS),
+        ],
+        "errors": errors,
+        "metadata": {
+            "origin": "shank",
+            "address": bs58::encode(crate::ID).into_string(),
+        },
+    })
+}
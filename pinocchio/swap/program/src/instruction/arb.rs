@@ -1,68 +1,396 @@
-use crate::dex::{pump_amm::PumpAmmSwap, DLMMSwap, BUY_DISCRIMINATOR, SWAP_DISCRIMINATOR};
-use crate::state::{load_ix_data, DataLen};
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
-use pinocchio_log::log;
+use crate::ata;
+use crate::compute::{leg_units, quote_units, remaining_compute_units, RouteSetup};
+use crate::dex::{
+    pump_amm::PumpAmmSwap, DLMMSwap, BUY_DISCRIMINATOR, SWAP_DISCRIMINATOR,
+    SWAP_EXACT_OUT_DISCRIMINATOR, SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR,
+};
+use crate::error::LossProgramError;
+use crate::events::{ArbitrageEvent, BudgetExhaustedEvent, Event, SwapEvent};
+use crate::introspection::{check_instruction_policy, INSTRUCTION_POLICY_FLAG};
+use crate::logging::{log_debug, log_info, LogLevel};
+use crate::oracle::OraclePrice;
+use crate::reentrancy::check_top_level;
+use crate::slippage::{SlippageLimit, MAX_SLIPPAGE_BPS};
+use crate::state::{
+    balance_change, load_ix_data, mint_decimals, token_amount, DataLen, IxData, OperatorLimits,
+    PnlEntry, PnlLedger,
+};
+use crate::wsol::WrappedSol;
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
 
 use super::SupportDex;
 
-#[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ArbitrageIxData {
     pub dex: SupportDex,
     pub max_bin_to_process: u64,
     pub min_profit_threshold: u64,
     pub no_failure: bool,
+    /// 低两位为运行时日志级别, 见 `logging::LOG_LEVEL_MASK`;
+    /// 第 2 位启用 instructions sysvar 检查, 见 `introspection`
+    pub flags: u8,
+    /// 大于 0 时按 Pyth 价格检查每个 leg 的成交价偏离 (bps)
+    pub max_price_deviation_bps: u16,
+    /// 大于 0 时先将该数量的 SOL 包装进 `base_mint_acc`, 结束后关闭该账户
+    pub wrap_lamports: u64,
+    /// 大于 0 时按执行时的链上报价推导每个 leg 的滑点限制 (bps), 见 `slippage`
+    pub slippage_bps: u16,
+    /// 大于 0 时 leg 按 exact out 买入该数量的 mint, DLMM 改为调用 `swap_exact_out`
+    pub amount_out: u64,
+    /// 大于 0 时 DLMM leg 调用 `swap_with_price_impact`, 相对 `active_id` 的价格影响上限 (bps)
+    pub max_price_impact_bps: u16,
+    /// `swap_with_price_impact` 计算价格影响的起始 bin, 一般为报价时的 active bin
+    pub active_id: i32,
+    /// `max_price_deviation_bps > 0` 时 price_update 账户必须属于该 Pyth feed
+    pub price_feed_id: [u8; 32],
 }
 
+/// 未设置 `amount_out` 时 Pump AMM buy 买入的数量
+const BASE_AMOUNT_OUT: u64 = 10_000_000;
+/// 未设置 `slippage_bps` 时 exact out leg 最多支付的 base
+const MAX_AMOUNT_IN: u64 = 1_000_000;
+
 impl DataLen for ArbitrageIxData {
-    const LEN: usize = core::mem::size_of::<ArbitrageIxData>() - 7 * 2;
+    const LEN: usize = core::mem::size_of::<ArbitrageIxData>();
+}
+
+impl IxData for ArbitrageIxData {
+    fn validate(bytes: &[u8]) -> Result<(), ProgramError> {
+        SupportDex::try_from(&bytes[0])?;
+        if bytes[core::mem::offset_of!(ArbitrageIxData, no_failure)] > 1 {
+            return Err(LossProgramError::InvalidInstructionData.into());
+        }
+        let offset = core::mem::offset_of!(ArbitrageIxData, slippage_bps);
+        let slippage_bps = u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+        if slippage_bps > MAX_SLIPPAGE_BPS {
+            return Err(LossProgramError::InvalidInstructionData.into());
+        }
+        // 价格影响模式只用于 DLMM 的 exact in leg
+        let offset = core::mem::offset_of!(ArbitrageIxData, max_price_impact_bps);
+        if bytes[offset..offset + 2] != [0, 0] {
+            let offset = core::mem::offset_of!(ArbitrageIxData, amount_out);
+            if bytes[0] != SupportDex::DLMM as u8 || bytes[offset..offset + 8] != [0; 8] {
+                return Err(LossProgramError::InvalidInstructionData.into());
+            }
+        }
+        Ok(())
+    }
 }
 
 pub fn process_execute_arbitrage(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    log!(
-        "ArbitrageIxData len {}",
-        core::mem::size_of::<ArbitrageIxData>()
-    );
+    // DEX 程序回调或其他程序 CPI 进入时, 在读取任何账户前拒绝
+    check_top_level()?;
+
     // 解析指令数据
     let ix_data = unsafe { load_ix_data::<ArbitrageIxData>(data)? };
+    let log_level = LogLevel::from_flags(ix_data.flags);
+    log_debug!(log_level, "ArbitrageIxData len {}", ArbitrageIxData::LEN);
 
-    // 提取最低收益阈值
-    let min_profit_threshold = ix_data.min_profit_threshold;
+    let max_price_deviation_bps = ix_data.max_price_deviation_bps;
+    let wrap_lamports = ix_data.wrap_lamports;
+    let slippage_bps = ix_data.slippage_bps;
 
-    let (ix_disc, instruction_data) = data
+    let (ix_disc, _instruction_data) = data
         .split_first()
         .ok_or(ProgramError::InvalidInstructionData)?;
 
-    match SupportDex::try_from(ix_disc)? {
+    let [signer_acc, base_mint, _, base_mint_acc, _, system_program_id, _, mint, user_mint_acc, ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let dex = SupportDex::try_from(ix_disc)?;
+    let (leg_accounts_len, pool_accounts, mint_token_program) = match dex {
+        SupportDex::PumpAmm => (
+            PumpAmmSwap::ACCOUNTS_LEN,
+            PumpAmmSwap::POOL_ACCOUNTS,
+            accounts
+                .get(PumpAmmSwap::MINT_TOKEN_PROGRAM_INDEX)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?,
+        ),
+        SupportDex::DLMM => (
+            DLMMSwap::ACCOUNTS_LEN,
+            DLMMSwap::POOL_ACCOUNTS,
+            accounts
+                .get(DLMMSwap::MINT_TOKEN_PROGRAM_INDEX)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?,
+        ),
+        _ => return Ok(()),
+    };
+    let clock = Clock::get()?;
+    let slot = clock.slot;
+
+    // leg 账户之后依次是 signer 的限额与账本 PDA, 可选的 Pyth 价格账户与
+    // instructions sysvar
+    let [operator_limits, pnl_ledger, trailing_accounts @ ..] =
+        accounts.get(leg_accounts_len..).unwrap_or_default()
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 在改动任何状态前检查剩余计算单元, 避免在 DEX CPI 中途耗尽
+    let route_setup = RouteSetup {
+        wrap_sol: wrap_lamports > 0,
+        create_ata: user_mint_acc.data_is_empty(),
+        create_ledger: pnl_ledger.data_is_empty(),
+        ..RouteSetup::default()
+    };
+    let mut required_units = route_setup
+        .units()
+        .saturating_add(leg_units(dex, ix_data.max_bin_to_process));
+    if slippage_bps > 0 {
+        required_units =
+            required_units.saturating_add(quote_units(dex, ix_data.max_bin_to_process));
+    }
+    let remaining_units = remaining_compute_units();
+    if remaining_units < required_units {
+        log_info!(log_level, "cu {} < {}", remaining_units, required_units);
+        if !ix_data.no_failure {
+            return Err(LossProgramError::ComputeBudgetExhausted.into());
+        }
+        BudgetExhaustedEvent {
+            dex: dex as u8,
+            required_units,
+            remaining_units,
+            slot,
+        }
+        .emit();
+        return Ok(());
+    }
+
+    let mut trailing_accounts = trailing_accounts;
+    let oracle_price = if max_price_deviation_bps > 0 {
+        let [price_update, rest @ ..] = trailing_accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        trailing_accounts = rest;
+        Some(OraclePrice::load(
+            price_update,
+            &ix_data.price_feed_id,
+            clock.unix_timestamp,
+        )?)
+    } else {
+        None
+    };
+    if ix_data.flags & INSTRUCTION_POLICY_FLAG != 0 {
+        let [instructions_sysvar, ..] = trailing_accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        check_instruction_policy(instructions_sysvar, &accounts[pool_accounts])?;
+    }
+
+    let wrapped_sol = if wrap_lamports > 0 {
+        let wrapped_sol = WrappedSol::load(accounts)?;
+        log_info!(log_level, "wrap {}", wrap_lamports);
+        wrapped_sol.wrap(wrap_lamports)?;
+        Some(wrapped_sol)
+    } else {
+        None
+    };
+
+    // 首次交易该 mint 时在同一笔交易内创建输出 ATA
+    ata::create_if_missing(
+        user_mint_acc,
+        signer_acc,
+        mint,
+        system_program_id,
+        mint_token_program,
+    )?;
+
+    let base_before = token_amount(base_mint_acc)?;
+
+    let leg = execute_leg(dex, accounts, ix_data, clock.unix_timestamp, log_level)?;
+    if let Some(oracle_price) = &oracle_price {
+        oracle_price.check_execution(
+            leg.amount_in,
+            leg.amount_out,
+            mint_decimals(base_mint)?,
+            mint_decimals(mint)?,
+            max_price_deviation_bps,
+        )?;
+    }
+    OperatorLimits::enforce(operator_limits, signer_acc.key(), leg.amount_in, &clock)?;
+    SwapEvent { slot, ..leg }.emit();
+
+    // 记录结构化事件
+    let base_after = token_amount(base_mint_acc)?;
+    let amount_in = base_before.saturating_sub(base_after);
+    let amount_out = base_after.saturating_sub(base_before);
+    let profit = balance_change(base_before, base_after);
+
+    ArbitrageEvent {
+        dex: dex as u8,
+        legs: 1,
+        amount_in,
+        amount_out,
+        profit,
+        slot,
+    }
+    .emit();
+    PnlLedger::append(
+        pnl_ledger,
+        signer_acc,
+        system_program_id,
+        PnlEntry::new(dex as u8, 1, amount_in, amount_out, profit, slot),
+    )?;
+
+    if let Some(wrapped_sol) = &wrapped_sol {
+        wrapped_sol.unwrap()?;
+    }
+
+    Ok(())
+}
+
+/// 执行单个 leg, 返回不含 slot 的 `SwapEvent`
+///
+/// With `slippage_bps` set, the DEX gets no limit of its own and the limit
+/// derived from the on-chain quote is checked on the balance changes instead.
+fn execute_leg(
+    dex: SupportDex,
+    accounts: &[AccountInfo],
+    ix_data: &ArbitrageIxData,
+    unix_timestamp: i64,
+    log_level: LogLevel,
+) -> Result<SwapEvent, ProgramError> {
+    let amount_out = ix_data.amount_out;
+    let max_bin_to_process = ix_data.max_bin_to_process;
+    let slippage_bps = ix_data.slippage_bps;
+    let max_price_impact_bps = ix_data.max_price_impact_bps;
+    let [_, _, _, base_mint_acc, _, _, _, _, user_mint_acc, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let base_before = token_amount(base_mint_acc)?;
+    let mint_before = token_amount(user_mint_acc)?;
+
+    let (pool_index, slippage_limit) = match dex {
         SupportDex::PumpAmm => {
-            log!("PumpAmm");
+            log_info!(log_level, "PumpAmm");
             // buy:
             // base_amount_out    : u64
             // max_quote_amount_in : u64
             let mut inst_data = [0u8; 24];
-            let base_amount_out: u64 = 10_000_000;
-            let max_quote_amount_in: u64 = 10_000_00;
+            let base_amount_out = if amount_out > 0 {
+                amount_out
+            } else {
+                BASE_AMOUNT_OUT
+            };
+            let (max_quote_amount_in, slippage_limit) = if slippage_bps > 0 {
+                let quote = PumpAmmSwap::quote_exact_out(accounts, base_amount_out)?;
+                log_info!(log_level, "quoted in {}", quote.amount_in);
+                (
+                    u64::MAX,
+                    Some(SlippageLimit::max_amount_in(quote.amount_in, slippage_bps)),
+                )
+            } else {
+                (MAX_AMOUNT_IN, None)
+            };
             inst_data[0..8].copy_from_slice(&BUY_DISCRIMINATOR);
             inst_data[8..16].copy_from_slice(&(base_amount_out).to_le_bytes());
             inst_data[16..24].copy_from_slice(&(max_quote_amount_in).to_le_bytes());
-            PumpAmmSwap::execute_swap(accounts, &inst_data)?;
+            PumpAmmSwap::execute_swap(accounts, &inst_data, log_level)?;
+            (PumpAmmSwap::POOL_INDEX, slippage_limit)
+        }
+        SupportDex::DLMM if amount_out > 0 => {
+            log_info!(log_level, "DLMM exact out");
+            // swap_exact_out:
+            // MaxInAmount *uint64
+            // OutAmount   *uint64
+            let mut inst_data = [0u8; 24];
+            let mut amount_out = amount_out;
+            let (max_in_amount, slippage_limit) = if slippage_bps > 0 {
+                let quote = DLMMSwap::quote_exact_out(
+                    accounts,
+                    amount_out,
+                    max_bin_to_process,
+                    unix_timestamp,
+                )?;
+                // 预算内的 bin 只覆盖部分输出时, 按报价的部分成交
+                if quote.amount_out < amount_out {
+                    log_info!(log_level, "partial out {}", quote.amount_out);
+                    amount_out = quote.amount_out;
+                }
+                log_info!(log_level, "quoted in {}", quote.amount_in);
+                (
+                    u64::MAX,
+                    Some(SlippageLimit::max_amount_in(quote.amount_in, slippage_bps)),
+                )
+            } else {
+                (MAX_AMOUNT_IN, None)
+            };
+            inst_data[0..8].copy_from_slice(&SWAP_EXACT_OUT_DISCRIMINATOR);
+            inst_data[8..16].copy_from_slice(&(max_in_amount).to_le_bytes());
+            inst_data[16..24].copy_from_slice(&(amount_out).to_le_bytes());
+
+            DLMMSwap::execute_swap(accounts, &inst_data, log_level)?;
+            (DLMMSwap::POOL_INDEX, slippage_limit)
         }
         SupportDex::DLMM => {
-            log!("DLMM");
-            // swap:
-            // AmountIn     *uint64
-            // MinAmountOut *uint64
+            log_info!(log_level, "DLMM");
             let mut inst_data = [0u8; 24];
-            let amount_in: u64 = 100;
-            let min_amount_out: u64 = 0;
-            inst_data[0..8].copy_from_slice(&SWAP_DISCRIMINATOR);
-            inst_data[8..16].copy_from_slice(&(amount_in).to_le_bytes());
-            inst_data[16..24].copy_from_slice(&(min_amount_out).to_le_bytes());
+            let mut amount_in: u64 = 100;
+            let slippage_limit = if slippage_bps > 0 {
+                let quote =
+                    DLMMSwap::quote(accounts, amount_in, max_bin_to_process, unix_timestamp)?;
+                // 预算内的 bin 只覆盖部分输入时, 按报价的部分成交
+                if quote.amount_in < amount_in {
+                    log_info!(log_level, "partial in {}", quote.amount_in);
+                    amount_in = quote.amount_in;
+                }
+                log_info!(log_level, "quoted out {}", quote.amount_out);
+                Some(SlippageLimit::min_amount_out(
+                    quote.amount_out,
+                    slippage_bps,
+                ))
+            } else {
+                None
+            };
+            let data_len = if max_price_impact_bps > 0 {
+                // swap_with_price_impact:
+                // AmountIn          *uint64
+                // ActiveId          *int32 (Option)
+                // MaxPriceImpactBps *uint16
+                let active_id = ix_data.active_id;
+                inst_data[0..8].copy_from_slice(&SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR);
+                inst_data[8..16].copy_from_slice(&(amount_in).to_le_bytes());
+                inst_data[16] = 1;
+                inst_data[17..21].copy_from_slice(&(active_id).to_le_bytes());
+                inst_data[21..23].copy_from_slice(&(max_price_impact_bps).to_le_bytes());
+                23
+            } else {
+                // swap:
+                // AmountIn     *uint64
+                // MinAmountOut *uint64
+                let min_amount_out: u64 = 0;
+                inst_data[0..8].copy_from_slice(&SWAP_DISCRIMINATOR);
+                inst_data[8..16].copy_from_slice(&(amount_in).to_le_bytes());
+                inst_data[16..24].copy_from_slice(&(min_amount_out).to_le_bytes());
+                24
+            };
 
-            DLMMSwap::execute_swap(accounts, &inst_data)?;
+            DLMMSwap::execute_swap(accounts, &inst_data[..data_len], log_level)?;
+            (DLMMSwap::POOL_INDEX, slippage_limit)
         }
-        _ => {}
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    let amount_in = base_before.saturating_sub(token_amount(base_mint_acc)?);
+    let amount_out = token_amount(user_mint_acc)?.saturating_sub(mint_before);
+    if let Some(slippage_limit) = slippage_limit {
+        slippage_limit.check(amount_in, amount_out)?;
     }
 
-    Ok(())
+    Ok(SwapEvent {
+        dex: dex as u8,
+        pool: *accounts[pool_index].key(),
+        amount_in,
+        amount_out,
+        slot: 0,
+    })
 }
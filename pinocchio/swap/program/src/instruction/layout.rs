@@ -0,0 +1,77 @@
+//! 指令账户布局
+//!
+//! Each adapter describes the accounts of its leg next to the code that reads
+//! them. The account counts used on-chain and the IDL consumed by the clients
+//! (`src/idl.rs`) are both derived from these tables.
+
+/// 指令中的一个账户
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AccountSpec {
+    pub name: &'static str,
+    pub writable: bool,
+    pub signer: bool,
+    /// 按 `ArbitrageIxData` 的字段决定是否传入, 只出现在必需账户之后
+    pub optional: bool,
+    pub desc: &'static str,
+}
+
+impl AccountSpec {
+    pub const fn readonly(name: &'static str, desc: &'static str) -> Self {
+        Self {
+            name,
+            writable: false,
+            signer: false,
+            optional: false,
+            desc,
+        }
+    }
+
+    pub const fn writable(name: &'static str, desc: &'static str) -> Self {
+        Self {
+            writable: true,
+            ..Self::readonly(name, desc)
+        }
+    }
+
+    pub const fn writable_signer(name: &'static str, desc: &'static str) -> Self {
+        Self {
+            signer: true,
+            ..Self::writable(name, desc)
+        }
+    }
+
+    pub const fn optional(self) -> Self {
+        Self {
+            optional: true,
+            ..self
+        }
+    }
+}
+
+/// 所有 leg 共用的前 9 个账户
+pub const COMMON_ACCOUNTS: [AccountSpec; 9] = [
+    AccountSpec::writable_signer("signer_acc", "Fee payer account"),
+    AccountSpec::readonly("base_mint", "SOL/USDC mint address"),
+    AccountSpec::writable("fee_collector_acc", "Fee collector account"),
+    AccountSpec::writable("base_mint_acc", "Base mint ata account"),
+    AccountSpec::readonly("token_program_id", "Token program account"),
+    AccountSpec::readonly("system_program_id", "System program account"),
+    AccountSpec::readonly(
+        "associated_token_program_id",
+        "Associated token program account",
+    ),
+    AccountSpec::readonly("mint", "mint account"),
+    AccountSpec::writable("user_mint_acc", "user mint ata account"),
+];
+
+/// leg 账户之后 signer 的限额与账本 PDA
+pub const SIGNER_PDA_ACCOUNTS: [AccountSpec; 2] = [
+    AccountSpec::writable(
+        "operator_limits",
+        "Operator limits PDA of the signer, created by the admin",
+    ),
+    AccountSpec::writable(
+        "pnl_ledger",
+        "PnL ledger PDA of the signer, created on the first route",
+    ),
+];
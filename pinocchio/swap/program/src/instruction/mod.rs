@@ -1,11 +1,21 @@
-use pinocchio::program_error::ProgramError;
+use crate::logging::{log_debug, log_info, LogLevel};
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
 
 pub mod arb;
+pub mod layout;
+pub mod operator_limits;
+pub mod quote;
+pub mod round_trip;
 
 pub use arb::*;
+pub use operator_limits::*;
+pub use quote::*;
+pub use round_trip::*;
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SupportDex {
     Pump,
     PumpAmm,
@@ -35,6 +45,9 @@ impl TryFrom<&u8> for SupportDex {
 #[repr(u8)]
 pub enum LossProgramInstruction {
     DexSwap,
+    Quote,
+    SetOperatorLimits,
+    RoundTrip,
 }
 
 impl TryFrom<&u8> for LossProgramInstruction {
@@ -43,105 +56,45 @@ impl TryFrom<&u8> for LossProgramInstruction {
     fn try_from(value: &u8) -> Result<Self, Self::Error> {
         match *value {
             0 => Ok(LossProgramInstruction::DexSwap),
+            1 => Ok(LossProgramInstruction::Quote),
+            2 => Ok(LossProgramInstruction::SetOperatorLimits),
+            3 => Ok(LossProgramInstruction::RoundTrip),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
 }
 
-mod idl_gen {
-    use super::{ArbitrageIxData};
+/// 按首字节分发指令, 供 entrypoint 与 host 上的 fuzz target 调用
+#[inline(always)]
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // 指令数据尚未解析, 仅受编译期级别控制
+    log_debug!(LogLevel::MAX, "data {}", instruction_data);
+    log_debug!(LogLevel::MAX, "accounts {}", accounts.len());
 
-    #[derive(shank::ShankInstruction)]
-    enum _MyProgramInstruction {
-        #[account(0, writable, signer, name = "signer_acc", desc = "Fee payer account")]
-        #[account(1, name = "base_mint", desc = "SOL/USDC mint address")]
-        #[account(
-            2,
-            writable,
-            name = "fee_collector_acc",
-            desc = "Fee collector account"
-        )]
-        #[account(3, writable, name = "base_mint_acc", desc = "Base mint ata account")]
-        #[account(4, name = "token_program_id", desc = "Token program account")]
-        #[account(5, name = "system_program_id", desc = "System program account")]
-        #[account(
-            6,
-            name = "associated_token_program_id",
-            desc = "Associated token program account"
-        )]
-        #[account(7, name = "mint", desc = "mint account")]
-        #[account(8, writable, name = "user_mint_acc", desc = "user mint ata account")]
-        #[account(9, name = "pump_program_id", desc = "Pump program account")]
-        #[account(
-            10,
-            name = "pump_global_config_acc",
-            desc = "Pump global config account"
-        )]
-        #[account(
-            11,
-            name = "pump_event_authority_acc",
-            desc = "Pump event authority account"
-        )]
-        #[account(12, name = "protocol_fee_recipient", desc = "Pump fee wallet account")]
-        #[account(13, name = "pool_acc", desc = "Pool pool account")]
-        #[account(
-            14,
-            writable,
-            name = "pool_base_token_acc",
-            desc = "Pool token vault account"
-        )]
-        #[account(
-            15,
-            writable,
-            name = "pool_quote_token_acc",
-            desc = "Pool sol vault account"
-        )]
-        #[account(
-            16,
-            writable,
-            name = "protocol_fee_recipient_token_acc",
-            desc = "fee token wallet account"
-        )]
-        #[account(
-            17,
-            writable,
-            name = "coin_creator_vault_ata",
-            desc = "Pool coin creator vault ata account"
-        )]
-        #[account(
-            18,
-            name = "coin_creator_vault_authority",
-            desc = "Pool coin creator vault authority account"
-        )]
-        PumpAMMSwap(ArbitrageIxData),
+    let (ix_disc, instruction_data) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
 
-        #[account(0, writable, signer, name = "signer_acc", desc = "Fee payer account")]
-        #[account(1, name = "base_mint", desc = "SOL/USDC mint address")]
-        #[account(
-            2,
-            writable,
-            name = "fee_collector_acc",
-            desc = "Fee collector account"
-        )]
-        #[account(3, writable, name = "base_mint_acc", desc = "Base mint ata account")]
-        #[account(4, name = "token_program_id", desc = "Token program account")]
-        #[account(5, name = "system_program_id", desc = "System program account")]
-        #[account(
-            6,
-            name = "associated_token_program_id",
-            desc = "Associated token program account"
-        )]
-        #[account(7, name = "mint", desc = "mint account")]
-        #[account(8, writable, name = "user_mint_acc", desc = "user mint ata account")]
-        #[account(9, name = "dlmm_program_id", desc = "dlmm program account")]
-        #[account(10, name = "dlmm_event_authority", desc = "dlmm program account")]
-        #[account(11, writable, name = "lb_pair", desc = "dlmm pool account")]
-        #[account(12, writable, name = "reserve_x", desc = "x token account")]
-        #[account(13, writable, name = "reserve_y", desc = "y token account")]
-        #[account(14, writable, name = "oracle", desc = "oracle token account")]
-        #[account(15, writable, name = "bin_1", desc = "bin 1 token account")]
-        #[account(16, writable, name = "bin_2", desc = "bin 2 token account")]
-        #[account(17, writable, name = "bin_3", desc = "bin 3 token account")]
-        DLMMSwap(ArbitrageIxData),
+    match LossProgramInstruction::try_from(ix_disc)? {
+        LossProgramInstruction::DexSwap => {
+            log_info!(LogLevel::MAX, "Ix:Swap");
+            process_execute_arbitrage(accounts, instruction_data)
+        }
+        LossProgramInstruction::Quote => {
+            log_info!(LogLevel::MAX, "Ix:Quote");
+            process_quote(accounts, instruction_data)
+        }
+        LossProgramInstruction::SetOperatorLimits => {
+            log_info!(LogLevel::MAX, "Ix:SetOperatorLimits");
+            process_set_operator_limits(accounts, instruction_data)
+        }
+        LossProgramInstruction::RoundTrip => {
+            log_info!(LogLevel::MAX, "Ix:RoundTrip");
+            process_round_trip(accounts, instruction_data)
+        }
     }
 }
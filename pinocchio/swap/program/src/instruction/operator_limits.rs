@@ -0,0 +1,138 @@
+use crate::error::LossProgramError;
+use crate::instruction::layout::AccountSpec;
+use crate::reentrancy::check_top_level;
+use crate::state::{
+    create_pda_account, load_acc_mut, load_ix_data, to_bytes, DataLen, IxData, OperatorLimits,
+    OPERATOR_LIMITS_SEED,
+};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Signer,
+    program_error::ProgramError,
+    pubkey::{try_find_program_address, Pubkey},
+    seeds, ProgramResult,
+};
+
+/// 部署本程序的 upgradeable loader
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+/// `UpgradeableLoaderState::ProgramData`: tag: u32, slot: u64, upgrade_authority: Option<Pubkey>
+const PROGRAM_DATA_TAG: [u8; 4] = [3, 0, 0, 0];
+const UPGRADE_AUTHORITY_OFFSET: usize = 12;
+
+/// `SetOperatorLimits` 的指令数据, 0 表示不限制
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OperatorLimitsIxData {
+    /// 单笔交易的最大名义金额 (base mint)
+    pub max_notional_per_trade: u64,
+    /// 每个窗口内累计的最大名义金额 (base mint)
+    pub max_window_notional: u64,
+    /// 窗口长度 (slot), 0 表示以 epoch 为窗口
+    pub window_slots: u64,
+    /// 每个 slot 内的最大交易次数
+    pub max_trades_per_slot: u64,
+}
+
+impl DataLen for OperatorLimitsIxData {
+    const LEN: usize = core::mem::size_of::<OperatorLimitsIxData>();
+}
+
+impl IxData for OperatorLimitsIxData {}
+
+/// `SetOperatorLimits` 的账户
+pub const SET_OPERATOR_LIMITS_ACCOUNTS: [AccountSpec; 5] = [
+    AccountSpec::writable_signer("admin_acc", "Limits admin and payer"),
+    AccountSpec::readonly(
+        "operator_acc",
+        "Operator signing the swaps, signer on creation",
+    ),
+    AccountSpec::writable("operator_limits", "Operator limits PDA"),
+    AccountSpec::readonly("system_program_id", "System program account"),
+    AccountSpec::readonly(
+        "program_data",
+        "swap_program programdata account, names the upgrade authority",
+    ),
+];
+
+/// `admin` 是本程序的 upgrade authority
+fn check_upgrade_authority(program_data: &AccountInfo, admin: &Pubkey) -> ProgramResult {
+    let (address, _) = try_find_program_address(&[&crate::ID], &BPF_LOADER_UPGRADEABLE_ID)
+        .ok_or(LossProgramError::PdaMismatch)?;
+    if program_data.key() != &address {
+        return Err(LossProgramError::PdaMismatch.into());
+    }
+    if !program_data.is_owned_by(&BPF_LOADER_UPGRADEABLE_ID) {
+        return Err(LossProgramError::InvalidOwner.into());
+    }
+    let data = program_data.try_borrow_data()?;
+    let authority = UPGRADE_AUTHORITY_OFFSET..UPGRADE_AUTHORITY_OFFSET + 33;
+    if data.len() < authority.end || data[..4] != PROGRAM_DATA_TAG {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // 没有 upgrade authority 的程序不能再创建限额
+    if data[authority.start] != 1 || data[authority.start + 1..authority.end] != *admin {
+        return Err(LossProgramError::AuthorizationError.into());
+    }
+    Ok(())
+}
+
+/// 创建或更新 operator 的限额 PDA
+///
+/// Creating it takes the signatures of the program upgrade authority, as the
+/// admin, and of the operator, and records the admin. Afterwards only that
+/// admin can change the limits.
+pub fn process_set_operator_limits(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    check_top_level()?;
+    let ix_data = unsafe { load_ix_data::<OperatorLimitsIxData>(data)? };
+
+    let [admin_acc, operator_acc, limits_acc, system_program_id, program_data, ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !admin_acc.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if system_program_id.key() != &pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if limits_acc.data_is_empty() {
+        if !operator_acc.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        check_upgrade_authority(program_data, admin_acc.key())?;
+        let (address, bump) = OperatorLimits::find_address(operator_acc.key())?;
+        if &address != limits_acc.key() {
+            return Err(LossProgramError::PdaMismatch.into());
+        }
+        let bump_seed = [bump];
+        let seeds = seeds!(OPERATOR_LIMITS_SEED, operator_acc.key(), &bump_seed);
+        create_pda_account(
+            admin_acc,
+            limits_acc,
+            OperatorLimits::LEN,
+            &[Signer::from(&seeds)],
+        )?;
+
+        let mut data = limits_acc.try_borrow_mut_data()?;
+        let mut limits = OperatorLimits::new(*admin_acc.key(), *operator_acc.key(), bump);
+        limits.configure(ix_data);
+        data.copy_from_slice(unsafe { to_bytes(&limits) });
+        return Ok(());
+    }
+
+    if !limits_acc.is_owned_by(&crate::ID) {
+        return Err(LossProgramError::InvalidOwner.into());
+    }
+    let mut data = limits_acc.try_borrow_mut_data()?;
+    let limits = unsafe { load_acc_mut::<OperatorLimits>(&mut data)? };
+    if &limits.admin != admin_acc.key() {
+        return Err(LossProgramError::AuthorizationError.into());
+    }
+    if &limits.operator != operator_acc.key() {
+        return Err(LossProgramError::PdaMismatch.into());
+    }
+    limits.configure(ix_data);
+    Ok(())
+}
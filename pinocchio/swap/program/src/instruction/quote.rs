@@ -0,0 +1,55 @@
+use crate::dex::{pump_amm::PumpAmmSwap, DLMMSwap};
+use crate::error::LossProgramError;
+use crate::state::{load_ix_data, to_bytes, DataLen, IxData};
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::set_return_data,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use super::SupportDex;
+
+/// `Quote` 的指令数据, 账户与对应 DEX 的 swap 相同
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuoteIxData {
+    pub dex: SupportDex,
+    /// 卖出的 base mint 数量, 含手续费
+    pub amount_in: u64,
+}
+
+impl DataLen for QuoteIxData {
+    const LEN: usize = core::mem::size_of::<QuoteIxData>();
+}
+
+impl IxData for QuoteIxData {
+    fn validate(bytes: &[u8]) -> Result<(), ProgramError> {
+        SupportDex::try_from(&bytes[0]).map(|_| ())
+    }
+}
+
+/// 报价一个 leg 并以 `SwapQuote` 写入 return data, 不发起 CPI
+pub fn process_quote(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let ix_data = unsafe { load_ix_data::<QuoteIxData>(data)? };
+    let amount_in = ix_data.amount_in;
+    if amount_in == 0 {
+        return Err(LossProgramError::AmountTooSmall.into());
+    }
+
+    let (ix_disc, _instruction_data) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let quote = match SupportDex::try_from(ix_disc)? {
+        SupportDex::PumpAmm => PumpAmmSwap::quote_within(accounts, amount_in)?,
+        SupportDex::DLMM => {
+            DLMMSwap::quote(accounts, amount_in, u64::MAX, Clock::get()?.unix_timestamp)?
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    // SAFETY: `SwapQuote` is `repr(C, packed)` with `LEN == size_of`.
+    set_return_data(unsafe { to_bytes(&quote) });
+    Ok(())
+}
@@ -0,0 +1,304 @@
+use crate::ata;
+use crate::compute::{leg_units, remaining_compute_units, RouteSetup, QUOTE_UNITS};
+use crate::dex::{
+    pump_amm::PumpAmmSwap, DLMMSwap, BUY_DISCRIMINATOR, SELL_DISCRIMINATOR, SWAP_DISCRIMINATOR,
+};
+use crate::error::LossProgramError;
+use crate::events::{ArbitrageEvent, BudgetExhaustedEvent, Event, SwapEvent};
+use crate::flash_loan::FlashLoan;
+use crate::instruction::layout::COMMON_ACCOUNTS;
+use crate::logging::{log_debug, log_info, LogLevel};
+use crate::reentrancy::check_top_level;
+use crate::state::{
+    balance_change, load_ix_data, meets_min_profit, token_amount, DataLen, IxData, OperatorLimits,
+    PnlEntry, PnlLedger,
+};
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use super::SupportDex;
+
+/// `RoundTrip` 的指令数据: 在 `buy_dex` 用 base mint 买入 mint, 再在 `sell_dex` 全部卖出
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoundTripIxData {
+    pub buy_dex: SupportDex,
+    pub sell_dex: SupportDex,
+    /// 买入 leg 支付的 base mint 数量, 使用闪电贷时须为 0
+    pub amount_in: u64,
+    /// base mint 的净增加至少为该值, 否则整笔交易失败
+    pub min_profit_threshold: u64,
+    pub max_bin_to_process: u64,
+    /// 低两位为运行时日志级别, 见 `logging::LOG_LEVEL_MASK`
+    pub flags: u8,
+    /// 大于 0 时从 lender 借入该数量的 base mint, 全部用于买入 leg, 卖出后连同手续费归还
+    pub flash_loan_amount: u64,
+    /// 剩余计算单元不足以完成往返时, 记录 `BudgetExhaustedEvent` 并跳过, 而不是失败
+    pub no_failure: bool,
+}
+
+/// 两个 leg 中最长的账户数, 用于拼出卖出 leg 的连续账户
+const MAX_LEG_ACCOUNTS_LEN: usize = PumpAmmSwap::ACCOUNTS_LEN;
+const _: () = assert!(DLMMSwap::ACCOUNTS_LEN <= MAX_LEG_ACCOUNTS_LEN);
+
+impl DataLen for RoundTripIxData {
+    const LEN: usize = core::mem::size_of::<RoundTripIxData>();
+}
+
+impl IxData for RoundTripIxData {
+    fn validate(bytes: &[u8]) -> Result<(), ProgramError> {
+        let buy_dex = SupportDex::try_from(&bytes[0])?;
+        let sell_dex = SupportDex::try_from(&bytes[1])?;
+        if buy_dex == sell_dex || !is_round_trip_dex(buy_dex) || !is_round_trip_dex(sell_dex) {
+            return Err(LossProgramError::InvalidInstructionData.into());
+        }
+        // 闪电贷模式下买入数量即借入数量
+        let amount_in = core::mem::offset_of!(RoundTripIxData, amount_in);
+        let flash_loan_amount = core::mem::offset_of!(RoundTripIxData, flash_loan_amount);
+        if bytes[amount_in..amount_in + 8] != [0; 8]
+            && bytes[flash_loan_amount..flash_loan_amount + 8] != [0; 8]
+        {
+            return Err(LossProgramError::InvalidInstructionData.into());
+        }
+        if bytes[core::mem::offset_of!(RoundTripIxData, no_failure)] > 1 {
+            return Err(LossProgramError::InvalidInstructionData.into());
+        }
+        Ok(())
+    }
+}
+
+fn is_round_trip_dex(dex: SupportDex) -> bool {
+    matches!(dex, SupportDex::PumpAmm | SupportDex::DLMM)
+}
+
+fn leg_accounts_len(dex: SupportDex) -> usize {
+    match dex {
+        SupportDex::PumpAmm => PumpAmmSwap::ACCOUNTS.len(),
+        _ => DLMMSwap::ACCOUNTS.len(),
+    }
+}
+
+/// 跨 DEX 的同币种往返套利
+///
+/// 账户依次为 `COMMON_ACCOUNTS`, 买入 leg 的账户, 卖出 leg 的账户, signer 的限额与账本 PDA,
+/// 使用闪电贷时再加上 `FlashLoan::ACCOUNTS`.
+/// 卖出 leg 的数量是买入 leg 前后 `user_mint_acc` 的余额差, 而不是报价.
+pub fn process_round_trip(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    check_top_level()?;
+
+    let ix_data = unsafe { load_ix_data::<RoundTripIxData>(data)? };
+    let log_level = LogLevel::from_flags(ix_data.flags);
+    log_debug!(log_level, "RoundTripIxData len {}", RoundTripIxData::LEN);
+
+    let buy_dex = ix_data.buy_dex;
+    let sell_dex = ix_data.sell_dex;
+    let flash_loan_amount = ix_data.flash_loan_amount;
+    let amount_in = if flash_loan_amount > 0 {
+        flash_loan_amount
+    } else {
+        ix_data.amount_in
+    };
+    let min_profit_threshold = ix_data.min_profit_threshold;
+    if amount_in == 0 {
+        return Err(LossProgramError::AmountTooSmall.into());
+    }
+
+    let [signer_acc, _, _, base_mint_acc, token_program_id, system_program_id, _, mint, user_mint_acc, ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 买入 leg 的账户已经连续, 卖出 leg 需要接在 `COMMON_ACCOUNTS` 之后
+    let buy_len = COMMON_ACCOUNTS.len() + leg_accounts_len(buy_dex);
+    let sell_len = COMMON_ACCOUNTS.len() + leg_accounts_len(sell_dex);
+    let [operator_limits, pnl_ledger, trailing_accounts @ ..] = accounts
+        .get(buy_len + leg_accounts_len(sell_dex)..)
+        .unwrap_or_default()
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let buy_accounts = &accounts[..buy_len];
+    let sell_accounts: [AccountInfo; MAX_LEG_ACCOUNTS_LEN] = core::array::from_fn(|i| {
+        let index = match i {
+            i if i < COMMON_ACCOUNTS.len() => i,
+            i if i < sell_len => buy_len + i - COMMON_ACCOUNTS.len(),
+            _ => 0,
+        };
+        accounts[index].clone()
+    });
+    let sell_accounts = &sell_accounts[..sell_len];
+
+    // mint 的 token program 由买入 leg 给出, 两个 leg 各自检查其 owner
+    let mint_token_program = match buy_dex {
+        SupportDex::PumpAmm => &buy_accounts[PumpAmmSwap::MINT_TOKEN_PROGRAM_INDEX],
+        _ => &buy_accounts[DLMMSwap::MINT_TOKEN_PROGRAM_INDEX],
+    };
+
+    let clock = Clock::get()?;
+    let slot = clock.slot;
+
+    let route_setup = RouteSetup {
+        flash_loan: flash_loan_amount > 0,
+        create_ata: user_mint_acc.data_is_empty(),
+        create_ledger: pnl_ledger.data_is_empty(),
+        ..RouteSetup::default()
+    };
+    let mut required_units = route_setup
+        .units()
+        .saturating_add(leg_units(buy_dex, ix_data.max_bin_to_process))
+        .saturating_add(leg_units(sell_dex, ix_data.max_bin_to_process));
+    if buy_dex == SupportDex::PumpAmm {
+        required_units = required_units.saturating_add(QUOTE_UNITS);
+    }
+    let remaining_units = remaining_compute_units();
+    if remaining_units < required_units {
+        log_info!(log_level, "cu {} < {}", remaining_units, required_units);
+        if !ix_data.no_failure {
+            return Err(LossProgramError::ComputeBudgetExhausted.into());
+        }
+        BudgetExhaustedEvent {
+            dex: buy_dex as u8,
+            required_units,
+            remaining_units,
+            slot,
+        }
+        .emit();
+        return Ok(());
+    }
+
+    let flash_loan = if flash_loan_amount > 0 {
+        Some(FlashLoan::load(
+            trailing_accounts,
+            signer_acc,
+            base_mint_acc,
+            token_program_id,
+        )?)
+    } else {
+        None
+    };
+
+    ata::create_if_missing(
+        user_mint_acc,
+        signer_acc,
+        mint,
+        system_program_id,
+        mint_token_program,
+    )?;
+
+    let base_before = token_amount(base_mint_acc)?;
+    if let Some(flash_loan) = &flash_loan {
+        log_info!(log_level, "flash loan {}", flash_loan_amount);
+        flash_loan.borrow(flash_loan_amount)?;
+    }
+    let base_start = token_amount(base_mint_acc)?;
+    let mint_before = token_amount(user_mint_acc)?;
+
+    // 买入: base mint -> mint
+    let mut inst_data = [0u8; 24];
+    let buy_pool = match buy_dex {
+        SupportDex::PumpAmm => {
+            log_info!(log_level, "buy PumpAmm");
+            // Pump AMM 只能按 exact out 买入, 取花费不超过 `amount_in` 的最大买入数量
+            let quote = PumpAmmSwap::quote_within(buy_accounts, amount_in)?;
+            inst_data[0..8].copy_from_slice(&BUY_DISCRIMINATOR);
+            inst_data[8..16].copy_from_slice(&(quote.amount_out).to_le_bytes());
+            inst_data[16..24].copy_from_slice(&(amount_in).to_le_bytes());
+            PumpAmmSwap::execute_swap(buy_accounts, &inst_data, log_level)?;
+            PumpAmmSwap::POOL_INDEX
+        }
+        _ => {
+            log_info!(log_level, "buy DLMM");
+            inst_data[0..8].copy_from_slice(&SWAP_DISCRIMINATOR);
+            inst_data[8..16].copy_from_slice(&(amount_in).to_le_bytes());
+            inst_data[16..24].copy_from_slice(&0u64.to_le_bytes());
+            DLMMSwap::execute_swap(buy_accounts, &inst_data, log_level)?;
+            DLMMSwap::POOL_INDEX
+        }
+    };
+    let base_mid = token_amount(base_mint_acc)?;
+    let mint_mid = token_amount(user_mint_acc)?;
+    let intermediate = mint_mid.saturating_sub(mint_before);
+    if intermediate == 0 {
+        return Err(LossProgramError::AmountTooSmall.into());
+    }
+    let buy = SwapEvent {
+        dex: buy_dex as u8,
+        pool: *buy_accounts[buy_pool].key(),
+        amount_in: base_start.saturating_sub(base_mid),
+        amount_out: intermediate,
+        slot,
+    };
+    log_info!(log_level, "intermediate {}", intermediate);
+
+    // 卖出: 买入的 mint 全部换回 base mint, 收益由下方的阈值检查保证
+    let sell_pool = match sell_dex {
+        SupportDex::PumpAmm => {
+            log_info!(log_level, "sell PumpAmm");
+            inst_data[0..8].copy_from_slice(&SELL_DISCRIMINATOR);
+            inst_data[8..16].copy_from_slice(&(intermediate).to_le_bytes());
+            inst_data[16..24].copy_from_slice(&0u64.to_le_bytes());
+            PumpAmmSwap::execute_swap(sell_accounts, &inst_data, log_level)?;
+            PumpAmmSwap::POOL_INDEX
+        }
+        _ => {
+            log_info!(log_level, "sell DLMM");
+            inst_data[0..8].copy_from_slice(&SWAP_DISCRIMINATOR);
+            inst_data[8..16].copy_from_slice(&(intermediate).to_le_bytes());
+            inst_data[16..24].copy_from_slice(&0u64.to_le_bytes());
+            DLMMSwap::execute_sell(sell_accounts, &inst_data, log_level)?;
+            DLMMSwap::POOL_INDEX
+        }
+    };
+    let sell = SwapEvent {
+        dex: sell_dex as u8,
+        pool: *sell_accounts[sell_pool].key(),
+        amount_in: mint_mid.saturating_sub(token_amount(user_mint_acc)?),
+        amount_out: token_amount(base_mint_acc)?.saturating_sub(base_mid),
+        slot,
+    };
+
+    // 收益按归还本金和手续费之后的余额计算
+    if let Some(flash_loan) = &flash_loan {
+        flash_loan.repay(flash_loan_amount)?;
+    }
+    let base_after = token_amount(base_mint_acc)?;
+
+    let profit = balance_change(base_before, base_after);
+    log_info!(log_level, "profit {}", profit);
+    if !meets_min_profit(base_before, base_after, min_profit_threshold) {
+        return Err(LossProgramError::ProfitBelowThreshold.into());
+    }
+    OperatorLimits::enforce(operator_limits, signer_acc.key(), buy.amount_in, &clock)?;
+
+    buy.emit();
+    sell.emit();
+    ArbitrageEvent {
+        dex: buy_dex as u8,
+        legs: 2,
+        amount_in: buy.amount_in,
+        amount_out: sell.amount_out,
+        profit,
+        slot,
+    }
+    .emit();
+    PnlLedger::append(
+        pnl_ledger,
+        signer_acc,
+        system_program_id,
+        PnlEntry::new(
+            buy_dex as u8,
+            2,
+            buy.amount_in,
+            sell.amount_out,
+            profit,
+            slot,
+        ),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! 基于 instructions sysvar 的交易结构检查
+//!
+//! Operators bundle `DexSwap` with instructions of other programs, and nothing
+//! stops a bundle from moving the pool right before the swap and back after
+//! it. With `INSTRUCTION_POLICY_FLAG` set in `ArbitrageIxData::flags` the route
+//! reads the instructions sysvar, passed as the last account, and requires:
+//! - a ComputeBudget instruction somewhere in the transaction,
+//! - only ComputeBudget and swap_program instructions before the swap,
+//! - no other instruction listing one of the pool accounts of the leg.
+use pinocchio::{
+    account_info::AccountInfo,
+    pubkey::Pubkey,
+    sysvars::instructions::{Instructions, IntrospectedInstruction},
+    ProgramResult,
+};
+
+use crate::error::LossProgramError;
+use crate::instruction::layout::AccountSpec;
+
+/// `ArbitrageIxData::flags` 中启用指令检查的位
+pub const INSTRUCTION_POLICY_FLAG: u8 = 1 << 2;
+/// 最后一个账户, 设置 `INSTRUCTION_POLICY_FLAG` 时传入
+pub const INSTRUCTIONS_SYSVAR_ACCOUNT: AccountSpec =
+    AccountSpec::readonly("instructions_sysvar", "Instructions sysvar").optional();
+/// ComputeBudget 程序
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// 按上述规则检查当前交易, `pool_accounts` 为 leg 会改动的池子账户
+pub fn check_instruction_policy(
+    instructions_sysvar: &AccountInfo,
+    pool_accounts: &[AccountInfo],
+) -> ProgramResult {
+    let instructions = Instructions::try_from(instructions_sysvar)?;
+    let current_index = instructions.load_current_index() as usize;
+
+    let mut has_compute_budget = false;
+    let mut index = 0;
+    while let Ok(instruction) = instructions.load_instruction_at(index) {
+        let program_id = instruction.get_program_id();
+        let is_compute_budget = program_id == &COMPUTE_BUDGET_PROGRAM_ID;
+        has_compute_budget |= is_compute_budget;
+        if index < current_index && !is_compute_budget && program_id != &crate::ID {
+            return Err(LossProgramError::InstructionPositionNotAllowed.into());
+        }
+        if index != current_index && references_any(&instruction, pool_accounts) {
+            return Err(LossProgramError::PoolAccountShared.into());
+        }
+        index += 1;
+    }
+
+    if !has_compute_budget {
+        return Err(LossProgramError::MissingComputeBudget.into());
+    }
+    Ok(())
+}
+
+fn references_any(instruction: &IntrospectedInstruction, accounts: &[AccountInfo]) -> bool {
+    let mut index = 0;
+    while let Ok(meta) = instruction.get_account_meta_at(index) {
+        if accounts.iter().any(|account| account.key() == &meta.key) {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
@@ -8,9 +8,26 @@ mod entrypoint;
 #[cfg(feature = "std")]
 extern crate std;
 
+pub mod ata;
+pub mod compute;
 pub mod error;
+pub mod events;
+pub mod flash_loan;
+#[cfg(feature = "idl")]
+pub mod idl;
 pub mod instruction;
+pub mod introspection;
+mod logging;
+pub mod oracle;
+pub mod reentrancy;
+#[cfg(feature = "searcher")]
+pub mod searcher;
+pub mod slippage;
 pub mod state;
+pub mod wsol;
 pub mod dex;
 
 pinocchio_pubkey::declare_id!("D7Nv2Yt9i7r1xSGgTZo9zGHgZ8wwiAX13nFodBXdpox4");
+
+#[cfg(all(test, any(feature = "test-default", feature = "bench-default")))]
+mod tests;
@@ -0,0 +1,64 @@
+//! 日志级别
+//!
+//! The compile-time ceiling comes from the `log-info` / `log-debug` features,
+//! release builds have neither and only emit the structured events. Within that
+//! ceiling each instruction picks its level through the low bits of
+//! `ArbitrageIxData::flags`. Logs written before the instruction data is parsed
+//! only follow the compile-time ceiling.
+
+/// `ArbitrageIxData::flags` 中的日志级别位 (0 关闭, 1 info, 2 debug)
+pub const LOG_LEVEL_MASK: u8 = 0b11;
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Off,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    /// 编译期允许的最高级别
+    pub const MAX: LogLevel = if cfg!(feature = "log-debug") {
+        LogLevel::Debug
+    } else if cfg!(feature = "log-info") {
+        LogLevel::Info
+    } else {
+        LogLevel::Off
+    };
+
+    /// 指令 flags 请求的级别, 不超过 `MAX`
+    pub fn from_flags(flags: u8) -> LogLevel {
+        let requested = match flags & LOG_LEVEL_MASK {
+            0 => LogLevel::Off,
+            1 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        };
+        requested.min(Self::MAX)
+    }
+}
+
+/// `log!` when `$level` allows info logs.
+macro_rules! log_info {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::logging::LogLevel::MAX >= $crate::logging::LogLevel::Info
+            && $level >= $crate::logging::LogLevel::Info
+        {
+            pinocchio_log::log!($($arg)+);
+        }
+    };
+}
+
+/// `log!` when `$level` allows debug logs.
+macro_rules! log_debug {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::logging::LogLevel::MAX >= $crate::logging::LogLevel::Debug
+            && $level >= $crate::logging::LogLevel::Debug
+        {
+            pinocchio_log::log!($($arg)+);
+        }
+    };
+}
+
+pub(crate) use log_debug;
+pub(crate) use log_info;
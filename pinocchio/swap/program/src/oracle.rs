@@ -0,0 +1,128 @@
+//! Pyth 价格保护
+//!
+//! Reads the `PriceUpdateV2` accounts posted by the Pyth receiver program, the
+//! same accounts `anchor/price-feed` and `anchor/hello-pyth` consume. The feed
+//! must price the leg output mint in units of the base mint, e.g. a TOKEN/SOL
+//! feed for legs spending WSOL, and is named by its feed id in the instruction
+//! data, since any fully verified update of another feed would parse as well.
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::LossProgramError;
+use crate::instruction::layout::AccountSpec;
+
+/// Pyth receiver program, owner of the `PriceUpdateV2` accounts
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// anchor 账户 discriminator `account:PriceUpdateV2`
+pub const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// 跟在 signer PDA 之后, `max_price_deviation_bps > 0` 时传入
+pub const PRICE_UPDATE_ACCOUNT: AccountSpec = AccountSpec::readonly(
+    "price_update",
+    "Pyth PriceUpdateV2 of the leg output mint in the base mint",
+)
+.optional();
+/// 价格最长有效期 (秒), 与 `anchor/price-feed` 的 `MAXIMUM_AGE` 一致
+pub const MAXIMUM_AGE: i64 = 60;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+/// `VerificationLevel::Full` 的 borsh 标签
+const VERIFICATION_FULL: u8 = 1;
+/// discriminator + write_authority
+const VERIFICATION_LEVEL_OFFSET: usize = 8 + 32;
+/// feed_id + price + conf + exponent + publish_time
+const PRICE_MESSAGE_LEN: usize = 32 + 8 + 8 + 4 + 8;
+
+/// `PriceUpdateV2::price_message` 中用到的字段
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// 解析 `feed_id` 完全验证的 `PriceUpdateV2`, 拒绝超过 `MAXIMUM_AGE` 的价格
+    pub fn load(
+        price_update: &AccountInfo,
+        feed_id: &[u8; 32],
+        unix_timestamp: i64,
+    ) -> Result<Self, ProgramError> {
+        if !price_update.is_owned_by(&PYTH_RECEIVER_PROGRAM_ID) {
+            return Err(LossProgramError::InvalidOwner.into());
+        }
+        let oracle_price = Self::from_bytes(&price_update.try_borrow_data()?, feed_id)
+            .ok_or(LossProgramError::InvalidOracleAccount)?;
+        if oracle_price.publish_time.saturating_add(MAXIMUM_AGE) < unix_timestamp {
+            return Err(LossProgramError::StaleOraclePrice.into());
+        }
+        Ok(oracle_price)
+    }
+
+    /// Borsh layout of `PriceUpdateV2`. Partially verified updates and updates
+    /// of another feed are rejected, as `get_price_no_older_than` does.
+    pub fn from_bytes(data: &[u8], feed_id: &[u8; 32]) -> Option<Self> {
+        if data.get(..8)? != PRICE_UPDATE_V2_DISCRIMINATOR
+            || *data.get(VERIFICATION_LEVEL_OFFSET)? != VERIFICATION_FULL
+        {
+            return None;
+        }
+        let message = data.get(VERIFICATION_LEVEL_OFFSET + 1..)?;
+        let message = message.get(..PRICE_MESSAGE_LEN)?;
+        if message[0..32] != *feed_id {
+            return None;
+        }
+        let oracle_price = Self {
+            price: i64::from_le_bytes(message[32..40].try_into().ok()?),
+            exponent: i32::from_le_bytes(message[48..52].try_into().ok()?),
+            publish_time: i64::from_le_bytes(message[52..60].try_into().ok()?),
+        };
+        (oracle_price.price > 0).then_some(oracle_price)
+    }
+
+    /// 比较一个 leg 的成交价与预言机价格
+    ///
+    /// The execution price is `amount_in / amount_out` in whole tokens, so the
+    /// check is done on the cross products in `u128`:
+    /// `amount_in * 10^out_decimals` against
+    /// `amount_out * 10^in_decimals * price * 10^exponent`.
+    pub fn check_execution(
+        &self,
+        amount_in: u64,
+        amount_out: u64,
+        in_decimals: u8,
+        out_decimals: u8,
+        max_deviation_bps: u16,
+    ) -> Result<(), ProgramError> {
+        let pow10 = |exp: u32| {
+            10u128
+                .checked_pow(exp)
+                .ok_or(LossProgramError::ArithmeticError)
+        };
+
+        let mut execution = (amount_in as u128)
+            .checked_mul(pow10(out_decimals as u32)?)
+            .ok_or(LossProgramError::ArithmeticError)?;
+        let mut oracle = (amount_out as u128)
+            .checked_mul(pow10(in_decimals as u32)?)
+            .and_then(|v| v.checked_mul(self.price as u128))
+            .ok_or(LossProgramError::ArithmeticError)?;
+        if self.exponent < 0 {
+            execution = execution
+                .checked_mul(pow10(self.exponent.unsigned_abs())?)
+                .ok_or(LossProgramError::ArithmeticError)?;
+        } else {
+            oracle = oracle
+                .checked_mul(pow10(self.exponent as u32)?)
+                .ok_or(LossProgramError::ArithmeticError)?;
+        }
+
+        let deviation = execution
+            .abs_diff(oracle)
+            .checked_mul(BPS_DENOMINATOR)
+            .ok_or(LossProgramError::ArithmeticError)?;
+        if deviation > oracle.saturating_mul(max_deviation_bps as u128) {
+            return Err(LossProgramError::PriceDeviationExceeded.into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+//! 重入保护
+//!
+//! `DexSwap` hands control to whatever program the caller passes as the DEX
+//! program id, and a hostile one could call back into swap_program halfway
+//! through a route, with the loan borrowed and the limits not yet recorded.
+//! The runtime rejects indirect reentrancy today, the guard does not rely on
+//! it: instructions that move funds or change state only run at the top level
+//! of a transaction, so any nested invocation fails with `NestedInvocation`.
+//! `Quote` only reads accounts and stays available to other programs via CPI.
+#![allow(unexpected_cfgs)]
+
+use pinocchio::ProgramResult;
+
+use crate::error::LossProgramError;
+
+/// 交易顶层指令的调用栈高度
+pub const TRANSACTION_LEVEL_STACK_HEIGHT: u64 = 1;
+
+/// 当前指令的调用栈高度, host 上视为顶层
+#[inline(always)]
+pub fn stack_height() -> u64 {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        pinocchio::syscalls::sol_get_stack_height()
+    }
+    #[cfg(not(target_os = "solana"))]
+    TRANSACTION_LEVEL_STACK_HEIGHT
+}
+
+/// 非交易顶层调用时返回 `NestedInvocation`
+#[inline(always)]
+pub fn check_top_level() -> ProgramResult {
+    if stack_height() > TRANSACTION_LEVEL_STACK_HEIGHT {
+        return Err(LossProgramError::NestedInvocation.into());
+    }
+    Ok(())
+}
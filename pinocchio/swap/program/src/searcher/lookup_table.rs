@@ -0,0 +1,173 @@
+//! Address lookup table 的创建与 v0 交易
+//!
+//! A Pump AMM or DLMM leg alone carries 18 to 20 accounts, so a route of two
+//! legs nearly fills the 1232 bytes of a legacy transaction and the flash loan
+//! accounts push it over. The static accounts of every pool go into a lookup
+//! table once, then each route is compiled into a v0 message that references
+//! them by a one byte index.
+use std::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use solana_address_lookup_table_interface::{
+    instruction::{create_lookup_table, extend_lookup_table},
+    program::ID as ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+    state::LOOKUP_TABLE_META_SIZE,
+};
+use solana_hash::Hash;
+use solana_instruction::Instruction;
+use solana_keypair::{Keypair, Signer};
+use solana_message::{v0, AddressLookupTableAccount, VersionedMessage};
+use solana_pubkey::Pubkey;
+use solana_transaction::{versioned::VersionedTransaction, Transaction};
+
+use super::rpc::RpcClient;
+use super::venue::{Venue, ASSOCIATED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+/// 每笔 extend 交易写入的地址数, 保持在 legacy 交易大小之内
+pub const EXTEND_CHUNK_LEN: usize = 20;
+/// 一个 lookup table 最多容纳的地址数
+pub const MAX_ADDRESSES: usize = 256;
+
+/// 所有 `DexSwap` 与 `RoundTrip` 共用的程序与 base mint, 以及每个池子的静态账户, 去重后保持顺序
+pub fn static_accounts(venues: &[Venue], base_mint: &Pubkey) -> Vec<Pubkey> {
+    let mut keys = vec![
+        *base_mint,
+        TOKEN_PROGRAM_ID,
+        SYSTEM_PROGRAM_ID,
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+    ];
+    for key in venues
+        .iter()
+        .flat_map(|venue| venue.static_accounts(base_mint))
+    {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// 读取链上已有的 lookup table
+pub fn fetch(rpc: &RpcClient, address: Pubkey) -> Result<AddressLookupTableAccount, String> {
+    let account = rpc
+        .get_multiple_accounts(&[address])?
+        .pop()
+        .flatten()
+        .ok_or_else(|| format!("lookup table {address} not found"))?;
+    if account.owner != ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
+        return Err(format!("{address} is not a lookup table"));
+    }
+    let addresses = account
+        .data
+        .get(LOOKUP_TABLE_META_SIZE..)
+        .ok_or_else(|| format!("lookup table {address} is truncated"))?
+        .chunks_exact(32)
+        .map(|key| Pubkey::new_from_array(key.try_into().unwrap()))
+        .collect();
+    Ok(AddressLookupTableAccount {
+        key: address,
+        addresses,
+    })
+}
+
+/// 创建 lookup table, 或扩展 `existing`, 使其包含全部 `keys`
+///
+/// The payer is the authority of a new table. Returns once the added
+/// addresses are active, a slot after the last extension.
+pub fn create_or_extend(
+    rpc: &RpcClient,
+    payer: &Keypair,
+    existing: Option<Pubkey>,
+    keys: &[Pubkey],
+) -> Result<AddressLookupTableAccount, String> {
+    let authority = payer.pubkey();
+    let (address, mut addresses) = match existing {
+        Some(address) => (address, fetch(rpc, address)?.addresses),
+        None => {
+            let (create_ix, address) = create_lookup_table(authority, authority, rpc.get_slot()?);
+            send_and_confirm(rpc, payer, &[create_ix])?;
+            (address, Vec::new())
+        }
+    };
+
+    let extend_ixs = extend_instructions(address, &authority, &addresses, keys)?;
+    let extended = !extend_ixs.is_empty();
+    for (extend_ix, chunk) in extend_ixs {
+        send_and_confirm(rpc, payer, &[extend_ix])?;
+        addresses.extend(chunk);
+    }
+    if extended {
+        // 扩展的地址在下一个 slot 才能被交易引用
+        rpc.wait_for_slot_after(rpc.get_slot()?)?;
+    }
+
+    Ok(AddressLookupTableAccount {
+        key: address,
+        addresses,
+    })
+}
+
+/// 把 `table_addresses` 中缺少的 `keys` 拆成若干 extend 指令, 每条最多 `EXTEND_CHUNK_LEN` 个地址
+///
+/// Returns each instruction with the addresses it appends, in order.
+pub fn extend_instructions(
+    address: Pubkey,
+    authority: &Pubkey,
+    table_addresses: &[Pubkey],
+    keys: &[Pubkey],
+) -> Result<Vec<(Instruction, Vec<Pubkey>)>, String> {
+    let mut missing: Vec<Pubkey> = Vec::new();
+    for key in keys {
+        if !table_addresses.contains(key) && !missing.contains(key) {
+            missing.push(*key);
+        }
+    }
+    if table_addresses.len() + missing.len() > MAX_ADDRESSES {
+        return Err(format!(
+            "lookup table {address} would hold {} addresses, more than {MAX_ADDRESSES}",
+            table_addresses.len() + missing.len()
+        ));
+    }
+    Ok(missing
+        .chunks(EXTEND_CHUNK_LEN)
+        .map(|chunk| {
+            let extend_ix =
+                extend_lookup_table(address, *authority, Some(*authority), chunk.to_vec());
+            (extend_ix, chunk.to_vec())
+        })
+        .collect())
+}
+
+fn send_and_confirm(
+    rpc: &RpcClient,
+    payer: &Keypair,
+    instructions: &[Instruction],
+) -> Result<String, String> {
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        rpc.get_latest_blockhash()?,
+    );
+    let wire_transaction = bincode::serialize(&transaction).map_err(|e| e.to_string())?;
+    let signature = rpc.send_transaction(&wire_transaction)?;
+    rpc.confirm_transaction(&signature)?;
+    Ok(signature)
+}
+
+/// 以 `tables` 中的地址编译并签名 v0 交易, 不在表中的账户保留为静态账户
+pub fn compile_v0(
+    payer: &Keypair,
+    instructions: &[Instruction],
+    tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedTransaction, String> {
+    let message = v0::Message::try_compile(&payer.pubkey(), instructions, tables, recent_blockhash)
+        .map_err(|e| format!("v0 message: {e}"))?;
+    VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer])
+        .map_err(|e| format!("v0 transaction: {e}"))
+}
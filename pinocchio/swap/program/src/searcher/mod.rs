@@ -0,0 +1,11 @@
+//! 离线搜索器的核心
+//!
+//! Venues loaded from surfpool `AccountsSnapshot` files or a local RPC, the
+//! route search over them, and the address lookup table and v0 message
+//! helpers. The `searcher` binary drives them; clients reuse them with the
+//! `searcher` feature.
+pub mod lookup_table;
+pub mod route;
+pub mod rpc;
+pub mod store;
+pub mod venue;
@@ -0,0 +1,135 @@
+//! 两个 leg 的循环: 在一个池子买入 mint, 在另一个池子卖回 base_mint
+use std::{vec, vec::Vec};
+
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use crate::dex::{quote::SwapQuote, MAX_BIN_PER_ARRAY};
+use crate::instruction::{LossProgramInstruction, RoundTripIxData};
+use crate::state::{to_bytes, OPERATOR_LIMITS_SEED, PNL_LEDGER_SEED};
+
+use super::venue::{
+    associated_token_address, Venue, ASSOCIATED_TOKEN_PROGRAM_ID, BIN_ARRAYS_PER_SWAP,
+    SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+
+/// DLMM leg 最多处理的 bin 数, 即报价走过的全部 BinArray
+pub const MAX_BIN_TO_PROCESS: u64 = BIN_ARRAYS_PER_SWAP as u64 * MAX_BIN_PER_ARRAY as u64;
+
+pub struct Route<'a> {
+    pub mint: Pubkey,
+    pub buy: &'a Venue,
+    pub sell: &'a Venue,
+    pub buy_quote: SwapQuote,
+    pub sell_quote: SwapQuote,
+}
+
+impl Route<'_> {
+    pub fn profit(&self) -> i64 {
+        self.sell_quote.amount_out as i64 - self.buy_quote.amount_in as i64
+    }
+
+    /// 卖出报价至少比买入多 `min_profit_threshold`
+    pub fn meets_min_profit(&self, min_profit_threshold: u64) -> bool {
+        self.buy_quote
+            .amount_in
+            .checked_add(min_profit_threshold)
+            .is_some_and(|required| self.sell_quote.amount_out >= required)
+    }
+
+    /// 以报价的买入数量执行整个循环的 `RoundTrip`
+    ///
+    /// The program sells whatever the buy leg received and fails unless the
+    /// base mint grew by `min_profit_threshold`, so a stale quote costs only
+    /// the fee.
+    pub fn round_trip_instruction(
+        &self,
+        user: &Pubkey,
+        base_mint: &Pubkey,
+        min_profit_threshold: u64,
+    ) -> Instruction {
+        let program_id = Pubkey::new_from_array(crate::ID);
+        let base_mint_acc = associated_token_address(user, base_mint, &TOKEN_PROGRAM_ID);
+        let user_mint_acc =
+            associated_token_address(user, &self.mint, &self.buy.mint_token_program());
+        let pda = |seed: &[u8]| Pubkey::find_program_address(&[seed, user.as_ref()], &program_id).0;
+
+        let mut accounts = vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(*base_mint, false),
+            AccountMeta::new(*user, false),
+            AccountMeta::new(base_mint_acc, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new(user_mint_acc, false),
+        ];
+        accounts.extend(self.buy.leg_accounts(base_mint, true));
+        accounts.extend(self.sell.leg_accounts(base_mint, false));
+        accounts.extend([
+            AccountMeta::new(pda(OPERATOR_LIMITS_SEED), false),
+            AccountMeta::new(pda(PNL_LEDGER_SEED), false),
+        ]);
+
+        let ix_data = RoundTripIxData {
+            buy_dex: self.buy.dex(),
+            sell_dex: self.sell.dex(),
+            amount_in: self.buy_quote.amount_in,
+            min_profit_threshold,
+            max_bin_to_process: MAX_BIN_TO_PROCESS,
+            flags: 0,
+            flash_loan_amount: 0,
+            no_failure: false,
+        };
+        let mut data = vec![LossProgramInstruction::RoundTrip as u8];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+        Instruction {
+            program_id,
+            accounts,
+            data,
+        }
+    }
+}
+
+/// 报价所有候选循环, 按收益从高到低排序
+///
+/// `RoundTrip` rejects a cycle whose two legs use the same DEX, so only pairs of
+/// venues on different DEXes are quoted.
+pub fn search<'a>(
+    venues: &'a [Venue],
+    base_mint: &Pubkey,
+    amounts: &[u64],
+    unix_timestamp: i64,
+) -> Vec<Route<'a>> {
+    let mut routes = Vec::new();
+    for buy in venues {
+        let Some(mint) = buy.mint(base_mint) else {
+            continue;
+        };
+        for sell in venues {
+            if buy.dex() == sell.dex() || sell.mint(base_mint) != Some(mint) {
+                continue;
+            }
+            for &amount_in in amounts {
+                let Some(buy_quote) = buy.quote(base_mint, true, amount_in, unix_timestamp) else {
+                    continue;
+                };
+                let Some(sell_quote) =
+                    sell.quote(base_mint, false, buy_quote.amount_out, unix_timestamp)
+                else {
+                    continue;
+                };
+                routes.push(Route {
+                    mint,
+                    buy,
+                    sell,
+                    buy_quote,
+                    sell_quote,
+                });
+            }
+        }
+    }
+    routes.sort_by_key(|route| std::cmp::Reverse(route.profit()));
+    routes
+}
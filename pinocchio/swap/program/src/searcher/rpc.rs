@@ -0,0 +1,210 @@
+//! 最小的 JSON-RPC 客户端
+//!
+//! Plain HTTP/1.1 over `TcpStream`, enough for a local validator or surfnet.
+//! TLS endpoints are out of scope, point `--rpc` at a local node.
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpStream};
+use std::thread::sleep;
+use std::time::Duration;
+use std::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_account::Account;
+use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+
+/// `getMultipleAccounts` 每次最多查询的账户数
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+/// 等待交易确认或 slot 推进时的轮询间隔与次数
+const POLL_INTERVAL: Duration = Duration::from_millis(400);
+const MAX_POLLS: usize = 75;
+
+/// `url` 是否指向本机, 如 `http://127.0.0.1:8899` 上的 surfnet
+pub fn is_local_url(url: &str) -> bool {
+    let Some(rest) = url.strip_prefix("http://") else {
+        return false;
+    };
+    let authority = rest.split('/').next().unwrap_or_default();
+    if authority.contains('@') {
+        return false;
+    }
+    let host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+pub struct RpcClient {
+    host: String,
+    path: String,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("{url}: only http:// endpoints are supported"))?;
+        let (host, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        Ok(Self {
+            host: host.to_string(),
+            path: path.to_string(),
+        })
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        let body =
+            json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}).to_string();
+        let mut stream =
+            TcpStream::connect(&self.host).map_err(|e| format!("{}: {e}", self.host))?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            self.path,
+            self.host,
+            body.len()
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .map_err(|e| e.to_string())?;
+        let response = String::from_utf8_lossy(&response);
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or("malformed HTTP response")?;
+        let body = if head
+            .to_ascii_lowercase()
+            .contains("transfer-encoding: chunked")
+        {
+            dechunk(body)?
+        } else {
+            body.to_string()
+        };
+
+        let mut value: Value = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+        if let Some(error) = value.get("error") {
+            return Err(format!("{method}: {error}"));
+        }
+        Ok(value["result"].take())
+    }
+
+    /// 缺失的账户为 `None`
+    pub fn get_multiple_accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>, String> {
+        let mut accounts = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let keys: Vec<String> = chunk.iter().map(Pubkey::to_string).collect();
+            let result = self.call(
+                "getMultipleAccounts",
+                json!([keys, {"encoding": "base64", "commitment": "confirmed"}]),
+            )?;
+            let values = result["value"].as_array().ok_or("missing value")?;
+            for value in values {
+                accounts.push(if value.is_null() {
+                    None
+                } else {
+                    Some(parse_account(value)?)
+                });
+            }
+        }
+        Ok(accounts)
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<Hash, String> {
+        let result = self.call("getLatestBlockhash", json!([{"commitment": "confirmed"}]))?;
+        result["value"]["blockhash"]
+            .as_str()
+            .ok_or("missing blockhash")?
+            .parse()
+            .map_err(|e| format!("blockhash: {e:?}"))
+    }
+
+    pub fn get_slot(&self) -> Result<u64, String> {
+        self.call("getSlot", json!([{"commitment": "confirmed"}]))?
+            .as_u64()
+            .ok_or_else(|| "missing slot".to_string())
+    }
+
+    /// 等待 confirmed slot 超过 `slot`
+    pub fn wait_for_slot_after(&self, slot: u64) -> Result<u64, String> {
+        for _ in 0..MAX_POLLS {
+            let current = self.get_slot()?;
+            if current > slot {
+                return Ok(current);
+            }
+            sleep(POLL_INTERVAL);
+        }
+        Err(format!("slot did not advance past {slot}"))
+    }
+
+    /// 等待交易在 confirmed 下落地, 交易失败时返回其错误
+    pub fn confirm_transaction(&self, signature: &str) -> Result<(), String> {
+        for _ in 0..MAX_POLLS {
+            let result = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(format!("{signature}: {}", status["err"]));
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed" | "finalized")
+                ) {
+                    return Ok(());
+                }
+            }
+            sleep(POLL_INTERVAL);
+        }
+        Err(format!("{signature} was not confirmed"))
+    }
+
+    /// 返回交易签名
+    pub fn send_transaction(&self, wire_transaction: &[u8]) -> Result<String, String> {
+        let result = self.call(
+            "sendTransaction",
+            json!([BASE64_STANDARD.encode(wire_transaction), {"encoding": "base64"}]),
+        )?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "missing signature".to_string())
+    }
+}
+
+fn parse_account(value: &Value) -> Result<Account, String> {
+    let data = value["data"][0].as_str().ok_or("missing account data")?;
+    Ok(Account {
+        lamports: value["lamports"].as_u64().ok_or("missing lamports")?,
+        data: BASE64_STANDARD.decode(data).map_err(|e| e.to_string())?,
+        owner: value["owner"]
+            .as_str()
+            .ok_or("missing owner")?
+            .parse()
+            .map_err(|e| format!("owner: {e:?}"))?,
+        executable: value["executable"].as_bool().unwrap_or_default(),
+        rent_epoch: value["rentEpoch"].as_u64().unwrap_or_default(),
+    })
+}
+
+fn dechunk(mut body: &str) -> Result<String, String> {
+    let mut decoded = String::new();
+    loop {
+        let (size, rest) = body.split_once("\r\n").ok_or("malformed chunk")?;
+        let size = usize::from_str_radix(size.trim(), 16).map_err(|e| e.to_string())?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+        decoded.push_str(rest.get(..size).ok_or("truncated chunk")?);
+        body = rest[size..].trim_start_matches("\r\n");
+    }
+}
@@ -0,0 +1,79 @@
+//! 账户来源: surfpool `AccountsSnapshot` 文件与可选的 RPC
+use std::{collections::HashMap, format, string::String, vec::Vec};
+
+use solana_account::Account;
+use solana_pubkey::Pubkey;
+use surfpool_types::AccountsSnapshot;
+
+use super::rpc::RpcClient;
+
+/// Accounts read from snapshots first, the RPC fills in the missing ones.
+pub struct AccountStore {
+    accounts: HashMap<Pubkey, Account>,
+    pub rpc: Option<RpcClient>,
+}
+
+impl AccountStore {
+    pub fn new(rpc: Option<RpcClient>) -> Self {
+        Self {
+            accounts: HashMap::new(),
+            rpc,
+        }
+    }
+
+    pub fn load_snapshot(&mut self, path: &str) -> Result<usize, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        let snapshot: AccountsSnapshot =
+            serde_json::from_str(&json).map_err(|e| format!("{path}: {e}"))?;
+        let len = snapshot.len();
+        for (address, account) in snapshot {
+            let address = address
+                .parse()
+                .map_err(|e| format!("{path}: {address}: {e:?}"))?;
+            self.accounts.insert(address, account.into());
+        }
+        Ok(len)
+    }
+
+    /// 通过 RPC 拉取尚未加载的账户
+    pub fn fetch(&mut self, keys: &[Pubkey]) -> Result<(), String> {
+        let Some(rpc) = &self.rpc else {
+            return Ok(());
+        };
+        let mut missing: Vec<Pubkey> = keys
+            .iter()
+            .filter(|key| !self.accounts.contains_key(key))
+            .copied()
+            .collect();
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        for (key, account) in missing.iter().zip(rpc.get_multiple_accounts(&missing)?) {
+            if let Some(account) = account {
+                self.accounts.insert(*key, account);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &Pubkey) -> Option<&Account> {
+        self.accounts.get(key)
+    }
+
+    pub fn require(&self, key: &Pubkey) -> Result<&Account, String> {
+        self.get(key)
+            .ok_or_else(|| format!("account {key} not found"))
+    }
+
+    /// `program_id` 拥有的账户
+    pub fn owned_by<'a>(
+        &'a self,
+        program_id: &'a Pubkey,
+    ) -> impl Iterator<Item = (&'a Pubkey, &'a Account)> + 'a {
+        self.accounts
+            .iter()
+            .filter(move |(_, account)| &account.owner == program_id)
+    }
+}
@@ -0,0 +1,446 @@
+//! Pump AMM 池子与 DLMM lb pair 的报价与 `DexSwap` 账户
+use std::{format, string::String, vec, vec::Vec};
+
+use solana_account::Account;
+use solana_instruction::AccountMeta;
+use solana_pubkey::Pubkey;
+
+use crate::dex::quote::SwapQuote;
+use crate::dex::{
+    pump_amm::{quote_buy, quote_sell, PumpAmmFees, PumpAmmPool, POOL_DISCRIMINATOR},
+    quote_exact_in, BinArray, LbPair, LB_PAIR_DISCRIMINATOR,
+};
+use crate::instruction::SupportDex;
+
+use super::store::AccountStore;
+
+pub const PUMP_AMM_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA");
+pub const DLMM_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+pub const TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// `GlobalConfig` 中第一个 protocol fee recipient 的偏移
+const PROTOCOL_FEE_RECIPIENTS_OFFSET: usize = 57;
+/// `LbPair` 中 reserve_x, reserve_y 与 oracle 的偏移
+const RESERVE_X_OFFSET: usize = 152;
+const RESERVE_Y_OFFSET: usize = 184;
+const ORACLE_OFFSET: usize = 552;
+/// 程序每次 DLMM swap 传入的 BinArray 数量
+pub const BIN_ARRAYS_PER_SWAP: i64 = 3;
+/// DLMM leg 账户中 BinArray 的位置, 在 oracle 之后
+const DLMM_BIN_ARRAYS: core::ops::Range<usize> = 6..6 + BIN_ARRAYS_PER_SWAP as usize;
+
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+fn event_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], program_id).0
+}
+
+fn key_at(data: &[u8], offset: usize) -> Option<Pubkey> {
+    Some(Pubkey::new_from_array(
+        data.get(offset..offset + 32)?.try_into().ok()?,
+    ))
+}
+
+fn token_amount(account: &Account) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        account.data.get(64..72)?.try_into().ok()?,
+    ))
+}
+
+/// mint 账户的 owner, 快照中没有 mint 时按 Token 程序处理
+fn mint_token_program(store: &AccountStore, mint: &Pubkey) -> Pubkey {
+    store
+        .get(mint)
+        .map_or(TOKEN_PROGRAM_ID, |account| account.owner)
+}
+
+pub struct PumpAmmVenue {
+    pub address: Pubkey,
+    pub pool: PumpAmmPool,
+    pub fees: PumpAmmFees,
+    pub global_config: Pubkey,
+    pub protocol_fee_recipient: Pubkey,
+    pub coin_creator_vault_authority: Pubkey,
+    pub mint_token_program: Pubkey,
+    /// Pump 的 base 为 mint, quote 为 base_mint
+    pub base_reserve: u64,
+    pub quote_reserve: u64,
+}
+
+impl PumpAmmVenue {
+    fn load(store: &mut AccountStore, address: Pubkey, data: &[u8]) -> Result<Self, String> {
+        let pool = PumpAmmPool::from_bytes(data).ok_or("not a Pump AMM pool")?;
+        let key = |key: [u8; 32]| Pubkey::new_from_array(key);
+        let global_config =
+            Pubkey::find_program_address(&[b"global_config"], &PUMP_AMM_PROGRAM_ID).0;
+        let base_vault = key(pool.pool_base_token_account);
+        let quote_vault = key(pool.pool_quote_token_account);
+        store.fetch(&[global_config, base_vault, quote_vault, key(pool.base_mint)])?;
+
+        let global_config_data = &store.require(&global_config)?.data;
+        let fees = PumpAmmFees::from_bytes(global_config_data).ok_or("invalid global config")?;
+        let protocol_fee_recipient = key_at(global_config_data, PROTOCOL_FEE_RECIPIENTS_OFFSET)
+            .ok_or("invalid global config")?;
+        let coin_creator_vault_authority = Pubkey::find_program_address(
+            &[b"creator_vault", &pool.coin_creator],
+            &PUMP_AMM_PROGRAM_ID,
+        )
+        .0;
+        let base_reserve = token_amount(store.require(&base_vault)?).ok_or("invalid base vault")?;
+        let quote_reserve =
+            token_amount(store.require(&quote_vault)?).ok_or("invalid quote vault")?;
+        Ok(Self {
+            address,
+            mint_token_program: mint_token_program(store, &key(pool.base_mint)),
+            pool,
+            fees,
+            global_config,
+            protocol_fee_recipient,
+            coin_creator_vault_authority,
+            base_reserve,
+            quote_reserve,
+        })
+    }
+}
+
+pub struct DlmmVenue {
+    pub address: Pubkey,
+    pub lb_pair: LbPair,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub oracle: Pubkey,
+    pub mint_token_program: Pubkey,
+    /// active bin 两侧各 `BIN_ARRAYS_PER_SWAP - 1` 个 BinArray, 缺失的为 `None`
+    pub bin_arrays: Vec<(i64, Pubkey, Option<Vec<u8>>)>,
+}
+
+impl DlmmVenue {
+    fn load(store: &mut AccountStore, address: Pubkey, data: &[u8]) -> Result<Self, String> {
+        let lb_pair = LbPair::from_bytes(data).ok_or("not a DLMM lb pair")?;
+        let key = |offset| key_at(data, offset).ok_or("invalid lb pair");
+        let (reserve_x, reserve_y, oracle) = (
+            key(RESERVE_X_OFFSET)?,
+            key(RESERVE_Y_OFFSET)?,
+            key(ORACLE_OFFSET)?,
+        );
+
+        let active_index = BinArray::index_of(lb_pair.active_id);
+        let span = BIN_ARRAYS_PER_SWAP - 1;
+        let addresses: Vec<(i64, Pubkey)> = (active_index - span..=active_index + span)
+            .map(|index| (index, bin_array_address(&address, index)))
+            .collect();
+        let mut keys: Vec<Pubkey> = addresses.iter().map(|(_, key)| *key).collect();
+        keys.extend([
+            Pubkey::new_from_array(lb_pair.token_x_mint),
+            Pubkey::new_from_array(lb_pair.token_y_mint),
+        ]);
+        store.fetch(&keys)?;
+
+        let bin_arrays = addresses
+            .into_iter()
+            .map(|(index, key)| {
+                (
+                    index,
+                    key,
+                    store.get(&key).map(|account| account.data.clone()),
+                )
+            })
+            .collect();
+        Ok(Self {
+            address,
+            mint_token_program: mint_token_program(
+                store,
+                &Pubkey::new_from_array(lb_pair.token_x_mint),
+            ),
+            lb_pair,
+            reserve_x,
+            reserve_y,
+            oracle,
+            bin_arrays,
+        })
+    }
+
+    /// 输入为 X 时 active id 递减
+    fn swap_for_y(&self, base_mint: &Pubkey, buy: bool) -> bool {
+        (&Pubkey::new_from_array(self.lb_pair.token_x_mint) == base_mint) == buy
+    }
+
+    /// leg 传给 DLMM 的 3 个 BinArray 地址
+    ///
+    /// The loaded arrays along the swap direction come first, the other loaded
+    /// arrays of the pair pad the leg, and arrays missing from the snapshot
+    /// and the RPC fill the rest, as DLMM only reads the arrays it crosses.
+    fn leg_bin_arrays(&self, swap_for_y: bool) -> Vec<Pubkey> {
+        let mut keys: Vec<Pubkey> = self
+            .swap_bin_arrays(swap_for_y)
+            .into_iter()
+            .filter(|(_, _, data)| data.is_some())
+            .map(|(_, key, _)| *key)
+            .collect();
+        let active_index = BinArray::index_of(self.lb_pair.active_id);
+        let mut others: Vec<&(i64, Pubkey, Option<Vec<u8>>)> = self
+            .bin_arrays
+            .iter()
+            .filter(|(_, key, data)| data.is_some() && !keys.contains(key))
+            .collect();
+        others.sort_by_key(|(index, _, _)| (index - active_index).abs());
+        keys.extend(others.into_iter().map(|(_, key, _)| *key));
+        for offset in 0..BIN_ARRAYS_PER_SWAP {
+            let index = if swap_for_y {
+                active_index - offset
+            } else {
+                active_index + offset
+            };
+            let key = bin_array_address(&self.address, index);
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys.truncate(BIN_ARRAYS_PER_SWAP as usize);
+        keys
+    }
+
+    /// 从 active bin 起沿交易方向的 3 个 BinArray
+    fn swap_bin_arrays(&self, swap_for_y: bool) -> Vec<&(i64, Pubkey, Option<Vec<u8>>)> {
+        let active_index = BinArray::index_of(self.lb_pair.active_id);
+        let indexes: Vec<i64> = (0..BIN_ARRAYS_PER_SWAP)
+            .map(|offset| {
+                if swap_for_y {
+                    active_index - offset
+                } else {
+                    active_index + offset
+                }
+            })
+            .collect();
+        indexes
+            .iter()
+            .filter_map(|index| self.bin_arrays.iter().find(|(i, _, _)| i == index))
+            .collect()
+    }
+}
+
+pub fn bin_array_address(lb_pair: &Pubkey, index: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"bin_array", lb_pair.as_ref(), &index.to_le_bytes()],
+        &DLMM_PROGRAM_ID,
+    )
+    .0
+}
+
+pub enum Venue {
+    PumpAmm(PumpAmmVenue),
+    Dlmm(DlmmVenue),
+}
+
+impl Venue {
+    /// 按 owner 与 discriminator 识别 `address`, 其余账户按需通过 RPC 拉取
+    pub fn load(store: &mut AccountStore, address: Pubkey) -> Result<Self, String> {
+        store.fetch(&[address])?;
+        let account = store.require(&address)?;
+        let (owner, data) = (account.owner, account.data.clone());
+        if owner == PUMP_AMM_PROGRAM_ID && data.starts_with(&POOL_DISCRIMINATOR) {
+            PumpAmmVenue::load(store, address, &data).map(Venue::PumpAmm)
+        } else if owner == DLMM_PROGRAM_ID && data.starts_with(&LB_PAIR_DISCRIMINATOR) {
+            DlmmVenue::load(store, address, &data).map(Venue::Dlmm)
+        } else {
+            Err(format!(
+                "{address} is neither a Pump AMM pool nor a DLMM lb pair"
+            ))
+        }
+    }
+
+    /// 已加载账户中所有 Pump AMM 池子与 DLMM lb pair
+    pub fn discover(store: &AccountStore) -> Vec<Pubkey> {
+        let pump_amm = store
+            .owned_by(&PUMP_AMM_PROGRAM_ID)
+            .filter(|(_, account)| account.data.starts_with(&POOL_DISCRIMINATOR));
+        let dlmm = store
+            .owned_by(&DLMM_PROGRAM_ID)
+            .filter(|(_, account)| account.data.starts_with(&LB_PAIR_DISCRIMINATOR));
+        let mut addresses: Vec<Pubkey> = pump_amm.chain(dlmm).map(|(key, _)| *key).collect();
+        addresses.sort();
+        addresses
+    }
+
+    pub fn address(&self) -> Pubkey {
+        match self {
+            Venue::PumpAmm(venue) => venue.address,
+            Venue::Dlmm(venue) => venue.address,
+        }
+    }
+
+    pub fn dex(&self) -> SupportDex {
+        match self {
+            Venue::PumpAmm(_) => SupportDex::PumpAmm,
+            Venue::Dlmm(_) => SupportDex::DLMM,
+        }
+    }
+
+    /// 用户 mint ATA 所属的 token 程序, 与程序创建 ATA 时一致
+    pub fn mint_token_program(&self) -> Pubkey {
+        match self {
+            Venue::PumpAmm(venue) => venue.mint_token_program,
+            Venue::Dlmm(venue) => venue.mint_token_program,
+        }
+    }
+
+    /// 与 `base_mint` 配对的另一个 mint
+    pub fn mint(&self, base_mint: &Pubkey) -> Option<Pubkey> {
+        let (a, b) = match self {
+            Venue::PumpAmm(venue) => (venue.pool.base_mint, venue.pool.quote_mint),
+            Venue::Dlmm(venue) => (venue.lb_pair.token_x_mint, venue.lb_pair.token_y_mint),
+        };
+        let (a, b) = (Pubkey::new_from_array(a), Pubkey::new_from_array(b));
+        match (a == *base_mint, b == *base_mint) {
+            (false, true) => Some(a),
+            (true, false) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// `buy` 为用 base_mint 买入 mint, 否则卖出 mint 换回 base_mint
+    pub fn quote(
+        &self,
+        base_mint: &Pubkey,
+        buy: bool,
+        amount_in: u64,
+        unix_timestamp: i64,
+    ) -> Option<SwapQuote> {
+        match self {
+            Venue::PumpAmm(venue) => {
+                // 程序只支持 quote 为 base_mint 的池子
+                if Pubkey::new_from_array(venue.pool.quote_mint) != *base_mint {
+                    return None;
+                }
+                if buy {
+                    quote_buy(
+                        venue.base_reserve,
+                        venue.quote_reserve,
+                        amount_in,
+                        venue.fees.total_bps(&venue.pool),
+                    )
+                } else {
+                    quote_sell(
+                        venue.base_reserve,
+                        venue.quote_reserve,
+                        amount_in,
+                        &venue.fees.fee_bps(&venue.pool),
+                    )
+                }
+            }
+            Venue::Dlmm(venue) => {
+                // 程序把 mint 作为 token X 传给 DLMM, 只支持 token Y 为 base_mint 的 pair
+                if Pubkey::new_from_array(venue.lb_pair.token_y_mint) != *base_mint {
+                    return None;
+                }
+                let swap_for_y = venue.swap_for_y(base_mint, buy);
+                let bin_arrays: Vec<BinArray> = venue
+                    .swap_bin_arrays(swap_for_y)
+                    .into_iter()
+                    .filter_map(|(_, _, data)| BinArray::from_bytes(data.as_deref()?))
+                    .collect();
+                quote_exact_in(
+                    venue.lb_pair,
+                    &bin_arrays,
+                    amount_in,
+                    swap_for_y,
+                    unix_timestamp,
+                    || u64::MAX,
+                )
+                .filter(|quote| quote.amount_in == amount_in)
+            }
+        }
+    }
+
+    /// 池子不随价格变化的账户, 可放入 address lookup table
+    ///
+    /// The mint and the leg accounts, except the DLMM bin arrays, which follow
+    /// the active bin.
+    pub fn static_accounts(&self, base_mint: &Pubkey) -> Vec<Pubkey> {
+        let leg_accounts = self.leg_accounts(base_mint, true);
+        let is_static = |index: usize| match self {
+            Venue::PumpAmm(_) => true,
+            Venue::Dlmm(_) => !DLMM_BIN_ARRAYS.contains(&index),
+        };
+        self.mint(base_mint)
+            .into_iter()
+            .chain(
+                leg_accounts
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| is_static(*index))
+                    .map(|(_, meta)| meta.pubkey),
+            )
+            .collect()
+    }
+
+    /// 该 leg 的账户, 位于公共账户之后; `buy` 为买入 mint, 否则卖出
+    ///
+    /// Only the DLMM bin arrays depend on the direction.
+    pub fn leg_accounts(&self, base_mint: &Pubkey, buy: bool) -> Vec<AccountMeta> {
+        match self {
+            Venue::PumpAmm(venue) => {
+                let key = |key: [u8; 32]| Pubkey::new_from_array(key);
+                let quote_mint = key(venue.pool.quote_mint);
+                vec![
+                    AccountMeta::new_readonly(PUMP_AMM_PROGRAM_ID, false),
+                    AccountMeta::new_readonly(venue.global_config, false),
+                    AccountMeta::new_readonly(event_authority(&PUMP_AMM_PROGRAM_ID), false),
+                    AccountMeta::new_readonly(venue.protocol_fee_recipient, false),
+                    AccountMeta::new_readonly(venue.address, false),
+                    AccountMeta::new(key(venue.pool.pool_base_token_account), false),
+                    AccountMeta::new(key(venue.pool.pool_quote_token_account), false),
+                    AccountMeta::new(
+                        associated_token_address(
+                            &venue.protocol_fee_recipient,
+                            &quote_mint,
+                            &TOKEN_PROGRAM_ID,
+                        ),
+                        false,
+                    ),
+                    AccountMeta::new(
+                        associated_token_address(
+                            &venue.coin_creator_vault_authority,
+                            &quote_mint,
+                            &TOKEN_PROGRAM_ID,
+                        ),
+                        false,
+                    ),
+                    AccountMeta::new_readonly(venue.coin_creator_vault_authority, false),
+                    AccountMeta::new_readonly(venue.mint_token_program, false),
+                ]
+            }
+            Venue::Dlmm(venue) => {
+                let mut accounts = vec![
+                    AccountMeta::new_readonly(DLMM_PROGRAM_ID, false),
+                    AccountMeta::new_readonly(event_authority(&DLMM_PROGRAM_ID), false),
+                    AccountMeta::new(venue.address, false),
+                    AccountMeta::new(venue.reserve_x, false),
+                    AccountMeta::new(venue.reserve_y, false),
+                    AccountMeta::new(venue.oracle, false),
+                ];
+                let swap_for_y = venue.swap_for_y(base_mint, buy);
+                accounts.extend(
+                    venue
+                        .leg_bin_arrays(swap_for_y)
+                        .into_iter()
+                        .map(|bin_array| AccountMeta::new(bin_array, false)),
+                );
+                accounts.push(AccountMeta::new_readonly(venue.mint_token_program, false));
+                accounts
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! 按 bps 计算滑点限制
+//!
+//! With `ArbitrageIxData::slippage_bps` set, a leg is quoted from the pool
+//! accounts right before its CPI and the limit is derived from that quote, the
+//! way the Pump and DLMM SDKs derive `max_quote_amount_in` / `min_amount_out`.
+//! The limit is checked against the balance changes of the user accounts after
+//! the CPI, so the instruction fails with `SlippageExceeded` rather than the
+//! DEX error code, and transfer fees of Token-2022 mints count as slippage.
+use pinocchio::ProgramResult;
+
+use crate::error::LossProgramError;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+/// `slippage_bps` 的上限, 即 100%
+pub const MAX_SLIPPAGE_BPS: u16 = 10_000;
+
+/// 最多支付的输入, 滑点部分向下取整
+pub fn max_amount_in(quoted_amount_in: u64, slippage_bps: u16) -> u64 {
+    let slippage = (quoted_amount_in as u128) * (slippage_bps as u128) / BPS_DENOMINATOR;
+    quoted_amount_in.saturating_add(slippage as u64)
+}
+
+/// 最少收到的输出, 滑点部分向下取整
+pub fn min_amount_out(quoted_amount_out: u64, slippage_bps: u16) -> u64 {
+    let slippage = (quoted_amount_out as u128) * (slippage_bps as u128) / BPS_DENOMINATOR;
+    quoted_amount_out.saturating_sub(slippage as u64)
+}
+
+/// 一个 leg 由报价得到的限制
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SlippageLimit {
+    /// exact out 的 leg, 限制输入
+    MaxAmountIn { quoted: u64, limit: u64 },
+    /// exact in 的 leg, 限制输出
+    MinAmountOut { quoted: u64, limit: u64 },
+}
+
+impl SlippageLimit {
+    pub fn max_amount_in(quoted_amount_in: u64, slippage_bps: u16) -> Self {
+        Self::MaxAmountIn {
+            quoted: quoted_amount_in,
+            limit: max_amount_in(quoted_amount_in, slippage_bps),
+        }
+    }
+
+    pub fn min_amount_out(quoted_amount_out: u64, slippage_bps: u16) -> Self {
+        Self::MinAmountOut {
+            quoted: quoted_amount_out,
+            limit: min_amount_out(quoted_amount_out, slippage_bps),
+        }
+    }
+
+    /// 超出限制时记录报价与实际数量并返回 `SlippageExceeded`
+    ///
+    /// The log is written whatever the log level, so the failed transaction
+    /// still shows how far the execution was from the quote.
+    pub fn check(&self, amount_in: u64, amount_out: u64) -> ProgramResult {
+        match *self {
+            Self::MaxAmountIn { quoted, limit } if amount_in > limit => {
+                pinocchio_log::log!(
+                    "slippage: quoted in {} limit {} actual in {}",
+                    quoted,
+                    limit,
+                    amount_in
+                );
+            }
+            Self::MinAmountOut { quoted, limit } if amount_out < limit => {
+                pinocchio_log::log!(
+                    "slippage: quoted out {} limit {} actual out {}",
+                    quoted,
+                    limit,
+                    amount_out
+                );
+            }
+            _ => return Ok(()),
+        }
+        Err(LossProgramError::SlippageExceeded.into())
+    }
+}
@@ -1,3 +1,7 @@
+pub mod operator_limits;
+pub mod pnl_ledger;
 pub mod utils;
 
+pub use operator_limits::*;
+pub use pnl_ledger::*;
 pub use utils::*;
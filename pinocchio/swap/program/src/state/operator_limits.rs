@@ -0,0 +1,152 @@
+//! 每个 signer 的交易限额
+//!
+//! The admin caps what an operator key can trade: notional per trade, notional
+//! per slot window (or per epoch) and trades per slot. `DexSwap` takes the PDA of
+//! its signer right after the leg accounts, so a leaked hot key cannot leave the
+//! limits out. Only the program upgrade authority creates a PDA, and a signer
+//! without one cannot trade.
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{try_find_program_address, Pubkey},
+    sysvars::clock::Clock,
+    ProgramResult,
+};
+
+use crate::error::LossProgramError;
+use crate::instruction::OperatorLimitsIxData;
+
+use super::{load_acc_mut, DataLen, Initialized};
+
+/// PDA 种子 `[OPERATOR_LIMITS_SEED, operator]`
+pub const OPERATOR_LIMITS_SEED: &[u8] = b"operator_limits";
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OperatorLimits {
+    /// 唯一可以修改限额的账户, 创建时写入
+    pub admin: Pubkey,
+    pub operator: Pubkey,
+    pub max_notional_per_trade: u64,
+    pub max_window_notional: u64,
+    pub window_slots: u64,
+    pub max_trades_per_slot: u64,
+    /// 当前窗口的编号, slot / window_slots 或 epoch
+    pub window_id: u64,
+    /// 当前窗口内已交易的名义金额
+    pub window_notional: u64,
+    pub last_trade_slot: u64,
+    pub trades_in_slot: u64,
+    pub bump: u8,
+    pub is_initialized: u8,
+    _padding: [u8; 6],
+}
+
+impl DataLen for OperatorLimits {
+    const LEN: usize = core::mem::size_of::<OperatorLimits>();
+}
+
+impl Initialized for OperatorLimits {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized == 1
+    }
+}
+
+impl OperatorLimits {
+    pub fn new(admin: Pubkey, operator: Pubkey, bump: u8) -> Self {
+        Self {
+            admin,
+            operator,
+            bump,
+            is_initialized: 1,
+            ..Self::default()
+        }
+    }
+
+    /// `operator` 的限额 PDA 与 bump
+    pub fn find_address(operator: &Pubkey) -> Result<(Pubkey, u8), ProgramError> {
+        try_find_program_address(&[OPERATOR_LIMITS_SEED, operator], &crate::ID)
+            .ok_or(LossProgramError::PdaMismatch.into())
+    }
+
+    /// 更新限额, 窗口长度改变时当前窗口的累计金额重新计算
+    pub fn configure(&mut self, config: &OperatorLimitsIxData) {
+        if self.window_slots != config.window_slots {
+            self.window_id = u64::MAX;
+        }
+        self.max_notional_per_trade = config.max_notional_per_trade;
+        self.max_window_notional = config.max_window_notional;
+        self.window_slots = config.window_slots;
+        self.max_trades_per_slot = config.max_trades_per_slot;
+    }
+
+    /// 记录一笔交易, 超出任一限额时返回 `AmountTooLarge` 且不改动计数
+    pub fn record_trade(
+        &mut self,
+        notional: u64,
+        slot: u64,
+        epoch: u64,
+    ) -> Result<(), LossProgramError> {
+        let exceeds = |amount: u64, limit: u64| limit > 0 && amount > limit;
+        if exceeds(notional, self.max_notional_per_trade) {
+            return Err(LossProgramError::AmountTooLarge);
+        }
+
+        let window_id = match self.window_slots {
+            0 => epoch,
+            window_slots => slot / window_slots,
+        };
+        let window_notional = if window_id == self.window_id {
+            self.window_notional
+        } else {
+            0
+        }
+        .checked_add(notional)
+        .ok_or(LossProgramError::ArithmeticError)?;
+        if exceeds(window_notional, self.max_window_notional) {
+            return Err(LossProgramError::AmountTooLarge);
+        }
+
+        let trades_in_slot = if slot == self.last_trade_slot {
+            self.trades_in_slot
+                .checked_add(1)
+                .ok_or(LossProgramError::ArithmeticError)?
+        } else {
+            1
+        };
+        if exceeds(trades_in_slot, self.max_trades_per_slot) {
+            return Err(LossProgramError::AmountTooLarge);
+        }
+
+        self.window_id = window_id;
+        self.window_notional = window_notional;
+        self.last_trade_slot = slot;
+        self.trades_in_slot = trades_in_slot;
+        Ok(())
+    }
+
+    /// 按 `operator` 的限额记录一笔名义金额为 `notional` 的交易
+    ///
+    /// `account` must be the operator's PDA. Until the admin creates it, the
+    /// operator cannot trade: all-zero limits are the way to leave it unlimited.
+    pub fn enforce(
+        account: &AccountInfo,
+        operator: &Pubkey,
+        notional: u64,
+        clock: &Clock,
+    ) -> ProgramResult {
+        if account.data_is_empty() {
+            return Err(LossProgramError::AuthorizationError.into());
+        }
+        if !account.is_owned_by(&crate::ID) {
+            return Err(LossProgramError::InvalidOwner.into());
+        }
+
+        let mut data = account.try_borrow_mut_data()?;
+        let limits = unsafe { load_acc_mut::<OperatorLimits>(&mut data)? };
+        if &limits.operator != operator {
+            return Err(LossProgramError::PdaMismatch.into());
+        }
+        Ok(limits.record_trade(notional, clock.slot, clock.epoch)?)
+    }
+}
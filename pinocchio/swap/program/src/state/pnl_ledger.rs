@@ -0,0 +1,160 @@
+//! 每个 operator 的链上收益账本
+//!
+//! A zero-copy ring buffer of the last `PNL_LEDGER_CAPACITY` routes of an
+//! operator with running totals, so dashboards read the cumulative PnL from
+//! account state instead of replaying logs. `DexSwap` takes the PDA right after
+//! the operator limits and creates it, paid by the signer, on the first route.
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Signer,
+    program_error::ProgramError,
+    pubkey::{try_find_program_address, Pubkey},
+    seeds, ProgramResult,
+};
+
+use crate::error::LossProgramError;
+
+use super::{create_pda_account, load_acc_mut, load_acc_mut_unchecked, DataLen, Initialized};
+
+/// PDA 种子 `[PNL_LEDGER_SEED, operator]`
+pub const PNL_LEDGER_SEED: &[u8] = b"pnl_ledger";
+/// 环形缓冲区保留的记录数
+pub const PNL_LEDGER_CAPACITY: usize = 64;
+
+/// 一次成功执行的结果
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PnlEntry {
+    pub slot: u64,
+    /// 卖出的 base mint 数量
+    pub amount_in: u64,
+    /// 收回的 base mint 数量
+    pub amount_out: u64,
+    pub profit: i64,
+    /// `SupportDex` of the first leg
+    pub dex: u8,
+    pub legs: u8,
+    _padding: [u8; 6],
+}
+
+impl PnlEntry {
+    pub fn new(dex: u8, legs: u8, amount_in: u64, amount_out: u64, profit: i64, slot: u64) -> Self {
+        Self {
+            slot,
+            amount_in,
+            amount_out,
+            profit,
+            dex,
+            legs,
+            _padding: [0; 6],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PnlLedger {
+    pub operator: Pubkey,
+    /// 已记录的执行次数, 下一条记录写入 `entries[count % PNL_LEDGER_CAPACITY]`
+    pub count: u64,
+    pub total_amount_in: u64,
+    pub total_amount_out: u64,
+    /// 累计收益
+    pub total_profit: i64,
+    pub last_slot: u64,
+    pub bump: u8,
+    pub is_initialized: u8,
+    _padding: [u8; 6],
+    pub entries: [PnlEntry; PNL_LEDGER_CAPACITY],
+}
+
+impl DataLen for PnlLedger {
+    const LEN: usize = core::mem::size_of::<PnlLedger>();
+}
+
+impl Initialized for PnlLedger {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized == 1
+    }
+}
+
+impl PnlLedger {
+    pub fn new(operator: Pubkey, bump: u8) -> Self {
+        Self {
+            operator,
+            count: 0,
+            total_amount_in: 0,
+            total_amount_out: 0,
+            total_profit: 0,
+            last_slot: 0,
+            bump,
+            is_initialized: 1,
+            _padding: [0; 6],
+            entries: [PnlEntry::default(); PNL_LEDGER_CAPACITY],
+        }
+    }
+
+    /// `operator` 的账本 PDA 与 bump
+    pub fn find_address(operator: &Pubkey) -> Result<(Pubkey, u8), ProgramError> {
+        try_find_program_address(&[PNL_LEDGER_SEED, operator], &crate::ID)
+            .ok_or(LossProgramError::PdaMismatch.into())
+    }
+
+    /// 写入一条记录, 覆盖最旧的一条
+    pub fn record(&mut self, entry: PnlEntry) {
+        self.entries[(self.count % PNL_LEDGER_CAPACITY as u64) as usize] = entry;
+        self.count += 1;
+        self.total_amount_in = self.total_amount_in.saturating_add(entry.amount_in);
+        self.total_amount_out = self.total_amount_out.saturating_add(entry.amount_out);
+        self.total_profit = self.total_profit.saturating_add(entry.profit);
+        self.last_slot = entry.slot;
+    }
+
+    /// 缓冲区中的记录, 从旧到新
+    pub fn recent(&self) -> impl Iterator<Item = &PnlEntry> {
+        let len = self.count.min(PNL_LEDGER_CAPACITY as u64) as usize;
+        let start = (self.count as usize - len) % PNL_LEDGER_CAPACITY;
+        (0..len).map(move |offset| &self.entries[(start + offset) % PNL_LEDGER_CAPACITY])
+    }
+
+    /// 在 `operator` 的账本中追加 `entry`, 账本不存在时由 `operator` 付租金创建
+    pub fn append(
+        account: &AccountInfo,
+        operator: &AccountInfo,
+        system_program: &AccountInfo,
+        entry: PnlEntry,
+    ) -> ProgramResult {
+        if account.data_is_empty() {
+            let (address, bump) = Self::find_address(operator.key())?;
+            if &address != account.key() {
+                return Err(LossProgramError::PdaMismatch.into());
+            }
+            if system_program.key() != &pinocchio_system::ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let bump_seed = [bump];
+            let seeds = seeds!(PNL_LEDGER_SEED, operator.key(), &bump_seed);
+            create_pda_account(operator, account, Self::LEN, &[Signer::from(&seeds)])?;
+
+            let mut data = account.try_borrow_mut_data()?;
+            // 新账户的数据全为 0, 只需写入头部, 避免在栈上构造整个账本
+            let ledger = unsafe { load_acc_mut_unchecked::<PnlLedger>(&mut data)? };
+            ledger.operator = *operator.key();
+            ledger.bump = bump;
+            ledger.is_initialized = 1;
+            ledger.record(entry);
+            return Ok(());
+        }
+        if !account.is_owned_by(&crate::ID) {
+            return Err(LossProgramError::InvalidOwner.into());
+        }
+
+        let mut data = account.try_borrow_mut_data()?;
+        let ledger = unsafe { load_acc_mut::<PnlLedger>(&mut data)? };
+        if &ledger.operator != operator.key() {
+            return Err(LossProgramError::PdaMismatch.into());
+        }
+        ledger.record(entry);
+        Ok(())
+    }
+}
@@ -1,15 +1,33 @@
-use pinocchio::program_error::ProgramError;
-use pinocchio_log::log;
 use crate::error::LossProgramError;
+use crate::logging::{log_debug, LogLevel};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Signer,
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::{Allocate, Assign, Transfer};
 
 pub trait DataLen {
     const LEN: usize;
 }
 
+/// 指令数据的字节检查, 枚举与 bool 字段并非任意字节都合法
+pub trait IxData: DataLen {
+    /// Rejects bytes that are not a valid `Self`, `bytes` is `Self::LEN` long.
+    fn validate(_bytes: &[u8]) -> Result<(), ProgramError> {
+        Ok(())
+    }
+}
+
 pub trait Initialized {
     fn is_initialized(&self) -> bool;
 }
 
+/// # Safety
+///
+/// `T` must be a plain-old-data type for which every bit pattern of `bytes` is valid.
 #[inline(always)]
 pub unsafe fn load_acc<T: DataLen + Initialized>(bytes: &[u8]) -> Result<&T, ProgramError> {
     load_acc_unchecked::<T>(bytes).and_then(|acc| {
@@ -21,6 +39,9 @@ pub unsafe fn load_acc<T: DataLen + Initialized>(bytes: &[u8]) -> Result<&T, Pro
     })
 }
 
+/// # Safety
+///
+/// `T` must be a plain-old-data type for which every bit pattern of `bytes` is valid.
 #[inline(always)]
 pub unsafe fn load_acc_unchecked<T: DataLen>(bytes: &[u8]) -> Result<&T, ProgramError> {
     if bytes.len() != T::LEN {
@@ -29,6 +50,9 @@ pub unsafe fn load_acc_unchecked<T: DataLen>(bytes: &[u8]) -> Result<&T, Program
     Ok(&*(bytes.as_ptr() as *const T))
 }
 
+/// # Safety
+///
+/// `T` must be a plain-old-data type for which every bit pattern of `bytes` is valid.
 #[inline(always)]
 pub unsafe fn load_acc_mut<T: DataLen + Initialized>(
     bytes: &mut [u8],
@@ -42,6 +66,9 @@ pub unsafe fn load_acc_mut<T: DataLen + Initialized>(
     })
 }
 
+/// # Safety
+///
+/// `T` must be a plain-old-data type for which every bit pattern of `bytes` is valid.
 #[inline(always)]
 pub unsafe fn load_acc_mut_unchecked<T: DataLen>(bytes: &mut [u8]) -> Result<&mut T, ProgramError> {
     if bytes.len() != T::LEN {
@@ -50,20 +77,98 @@ pub unsafe fn load_acc_mut_unchecked<T: DataLen>(bytes: &mut [u8]) -> Result<&mu
     Ok(&mut *(bytes.as_mut_ptr() as *mut T))
 }
 
+/// SPL Token 账户的 amount 字段
+#[inline(always)]
+pub fn token_amount(acc: &AccountInfo) -> Result<u64, ProgramError> {
+    let data = acc.try_borrow_data()?;
+    if data.len() < 72 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(u64::from_le_bytes(data[64..72].try_into().unwrap()))
+}
+
+/// 余额从 `before` 到 `after` 的变化, 超出 i64 时饱和
+#[inline(always)]
+pub fn balance_change(before: u64, after: u64) -> i64 {
+    (after as i128 - before as i128).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+/// 余额至少增加了 `min_profit`, 不经过有符号转换
+#[inline(always)]
+pub fn meets_min_profit(before: u64, after: u64, min_profit: u64) -> bool {
+    before
+        .checked_add(min_profit)
+        .is_some_and(|required| after >= required)
+}
+
+/// 创建本程序所有的 PDA 账户, 租金不足的部分由 `payer` 补齐
+///
+/// `CreateAccount` fails on an address that already holds lamports, so anyone
+/// could block a PDA by sending it some. Topping up the rent, then allocating
+/// and assigning with the PDA signature works either way.
+pub fn create_pda_account(
+    payer: &AccountInfo,
+    account: &AccountInfo,
+    space: usize,
+    signers: &[Signer],
+) -> ProgramResult {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+    if lamports < rent {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: rent - lamports,
+        }
+        .invoke()?;
+    }
+    Allocate {
+        account,
+        space: space as u64,
+    }
+    .invoke_signed(signers)?;
+    Assign {
+        account,
+        owner: &crate::ID,
+    }
+    .invoke_signed(signers)
+}
+
+/// SPL Mint 账户的 decimals 字段
+#[inline(always)]
+pub fn mint_decimals(acc: &AccountInfo) -> Result<u8, ProgramError> {
+    let data = acc.try_borrow_data()?;
+    if data.len() < 82 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(data[44])
+}
+
+/// # Safety
+///
+/// `T` must be an alignment-1 type for which every bit pattern accepted by
+/// `T::validate` is valid.
 #[inline(always)]
-pub unsafe fn load_ix_data<T: DataLen>(bytes: &[u8]) -> Result<&T, ProgramError> {
-    log!("load_ix_data {}", T::LEN);
-    log!("byte len {}", bytes.len());
+pub unsafe fn load_ix_data<T: IxData>(bytes: &[u8]) -> Result<&T, ProgramError> {
+    log_debug!(LogLevel::MAX, "load_ix_data {}", T::LEN);
+    log_debug!(LogLevel::MAX, "byte len {}", bytes.len());
     if bytes.len() != T::LEN {
         return Err(LossProgramError::InvalidInstructionData.into());
     }
+    T::validate(bytes)?;
     Ok(&*(bytes.as_ptr() as *const T))
 }
 
+/// # Safety
+///
+/// `T::LEN` must not exceed `size_of::<T>()`.
 pub unsafe fn to_bytes<T: DataLen>(data: &T) -> &[u8] {
     core::slice::from_raw_parts(data as *const T as *const u8, T::LEN)
 }
 
+/// # Safety
+///
+/// `T::LEN` must not exceed `size_of::<T>()`.
 pub unsafe fn to_mut_bytes<T: DataLen>(data: &mut T) -> &mut [u8] {
     core::slice::from_raw_parts_mut(data as *mut T as *mut u8, T::LEN)
 }
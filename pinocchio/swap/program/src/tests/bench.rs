@@ -0,0 +1,169 @@
+//! Compute unit benchmark of every swap path.
+//!
+//! Each DEX is swapped through its mock with 1 to `MAX_LEGS` `DexSwap`
+//! instructions in one transaction. Only the units spent by swap_program are
+//! counted, the units of the DEX CPI itself are subtracted, so the numbers do
+//! not move with the mocks. The operator's PnL ledger exists before the swap,
+//! so no path pays the one-time creation of the PDA.
+//!
+//! Run with `cargo test --features bench-default bench -- --nocapture`. A run
+//! fails when a path uses more than `TOLERANCE_BPS` over `BASELINE_PATH`, or
+//! when the baseline has no entry for a path. Without a baseline file the run
+//! only prints the numbers. Set `UPDATE_CU_BASELINE=1` to write the measured
+//! numbers and commit the file.
+use std::{collections::BTreeMap, println, string::String};
+
+use litesvm::types::TransactionMetadata;
+
+use super::dlmm::*;
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::*;
+use crate::state::{PnlLedger, PNL_LEDGER_SEED};
+
+const BASELINE_PATH: &str = "src/tests/cu_baseline.json";
+const UPDATE_BASELINE_ENV: &str = "UPDATE_CU_BASELINE";
+
+/// Allowed regression over the baseline, 2%.
+const TOLERANCE_BPS: u64 = 200;
+
+const MAX_LEGS: usize = 3;
+
+/// Runs one DEX path with the given number of legs and returns its units.
+type Bench = fn(usize) -> u64;
+
+/// Units consumed by `program_id` minus the units of the programs it invoked.
+fn own_compute_units(
+    meta: &TransactionMetadata,
+    program_id: &Pubkey,
+    dex_program_id: &Pubkey,
+) -> u64 {
+    let consumed = |id: &Pubkey| -> u64 {
+        let prefix = format!("Program {id} consumed ");
+        meta.logs
+            .iter()
+            .filter_map(|log| log.strip_prefix(&prefix))
+            .map(|rest| rest.split(' ').next().unwrap().parse::<u64>().unwrap())
+            .sum()
+    };
+    consumed(program_id) - consumed(dex_program_id)
+}
+
+/// Writes an empty PnL ledger for `operator`, as after its first route.
+fn set_pnl_ledger(svm: &mut LiteSVM, operator: &Pubkey) {
+    let (address, bump) = Pubkey::find_program_address(
+        &[PNL_LEDGER_SEED, operator.as_ref()],
+        &Pubkey::new_from_array(crate::ID),
+    );
+    let ledger = PnlLedger::new(operator.to_bytes(), bump);
+    let data = unsafe { to_bytes(&ledger) }.to_vec();
+    svm.set_account(
+        address,
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: Pubkey::new_from_array(crate::ID),
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+fn bench_pump_amm(legs: usize) -> u64 {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    run(&mut svm, &user, ix, legs, &PUMP_PROGRAM_ID)
+}
+
+fn bench_dlmm(legs: usize) -> u64 {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    run(&mut svm, &user, ix, legs, &DLMM_PROGRAM_ID)
+}
+
+fn run(
+    svm: &mut LiteSVM,
+    user: &Keypair,
+    ix: Instruction,
+    legs: usize,
+    dex_program_id: &Pubkey,
+) -> u64 {
+    set_pnl_ledger(svm, &user.pubkey());
+    let mut ixs = vec![set_compute_unit_limit(1_400_000)];
+    ixs.extend(std::iter::repeat_n(ix, legs));
+    let res = send_tx(svm, &ixs, user).unwrap_or_else(|e| {
+        panic!(
+            "{legs} leg swap failed: {:?}\n{}",
+            e.err,
+            e.meta.pretty_logs()
+        )
+    });
+    own_compute_units(&res, &Pubkey::new_from_array(crate::ID), dex_program_id)
+}
+
+/// `None` when no baseline has been written yet.
+fn load_baseline() -> Option<BTreeMap<String, u64>> {
+    let data = std::fs::read_to_string(BASELINE_PATH).ok()?;
+    Some(
+        serde_json::from_str(&data)
+            .unwrap_or_else(|_| panic!("Malformed baseline at {BASELINE_PATH}")),
+    )
+}
+
+#[test]
+fn bench_compute_units() {
+    let benches: [(&str, Bench); 2] = [("pump_amm", bench_pump_amm), ("dlmm", bench_dlmm)];
+
+    let mut measured = BTreeMap::new();
+    for (dex, bench) in benches {
+        for legs in 1..=MAX_LEGS {
+            measured.insert(format!("{dex}/{legs}_legs"), bench(legs));
+        }
+    }
+
+    if std::env::var_os(UPDATE_BASELINE_ENV).is_some() {
+        std::fs::write(
+            BASELINE_PATH,
+            serde_json::to_string_pretty(&measured).unwrap() + "\n",
+        )
+        .unwrap_or_else(|_| panic!("Failed to write baseline at {BASELINE_PATH}"));
+        println!("Wrote {BASELINE_PATH}");
+        return;
+    }
+
+    let Some(baseline) = load_baseline() else {
+        println!("{:<20} {:>10}", "path", "units");
+        for (path, units) in &measured {
+            println!("{path:<20} {units:>10}");
+        }
+        println!("No baseline at {BASELINE_PATH}, run with {UPDATE_BASELINE_ENV}=1 to write it");
+        return;
+    };
+    println!("{:<20} {:>10} {:>10}", "path", "units", "baseline");
+    let mut regressions = Vec::new();
+    for (path, units) in &measured {
+        let Some(base) = baseline.get(path) else {
+            println!("{path:<20} {units:>10} {:>10}", "-");
+            regressions.push(format!("{path}: {units} CU, no baseline"));
+            continue;
+        };
+        println!("{path:<20} {units:>10} {base:>10}");
+        if *units * 10_000 > base * (10_000 + TOLERANCE_BPS) {
+            regressions.push(format!("{path}: {units} CU, baseline {base} CU"));
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "compute units regressed beyond {TOLERANCE_BPS} bps or have no baseline:\n{}",
+        regressions.join("\n")
+    );
+}
@@ -0,0 +1,168 @@
+use solana_keypair::Signer;
+
+use crate::compute::{
+    bin_budget, leg_units, RouteSetup, CREATE_LEDGER_UNITS, DLMM_BIN_UNITS, DLMM_LEG_UNITS,
+    SAFETY_MARGIN_UNITS,
+};
+use crate::events::SwapProgramEvent;
+
+use super::dlmm::*;
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::round_trip::*;
+use super::*;
+
+/// Below the units of a 20 bin DLMM route, well above what precedes the check.
+const SHORT_BUDGET: u32 = 60_000;
+
+fn short_budget_dlmm_ix(no_failure: bool) -> (LiteSVM, Keypair, Instruction, Pubkey, Pubkey) {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        no_failure,
+        ..arbitrage_ix_data(SupportDex::DLMM)
+    });
+    (svm, user, ix, base_mint_acc, user_mint_acc)
+}
+
+#[test]
+fn it_budgets_dlmm_bins_up_to_max_bin_to_process() {
+    assert_eq!(
+        leg_units(SupportDex::DLMM, 20),
+        DLMM_LEG_UNITS + 20 * DLMM_BIN_UNITS
+    );
+    assert_eq!(leg_units(SupportDex::DLMM, u64::MAX), u64::MAX);
+    assert_eq!(RouteSetup::default().units(), SAFETY_MARGIN_UNITS);
+
+    // a quote runs out of units after the budgeted bins
+    let remaining_units = bin_budget(2);
+    assert_eq!(
+        [remaining_units(), remaining_units(), remaining_units()],
+        [u64::MAX, u64::MAX, 0]
+    );
+}
+
+#[test]
+fn it_skips_the_route_under_no_failure_when_units_run_short() {
+    let (mut svm, user, ix, base_mint_acc, user_mint_acc) = short_budget_dlmm_ix(true);
+
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(SHORT_BUDGET), ix], &user)
+        .expect("a short budget under no_failure should be a no-op");
+
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+    assert_eq!(token_balance(&svm, &user_mint_acc), 0);
+    assert!(!res.logs.iter().any(|log| log.contains("invoke [2]")));
+    let [SwapProgramEvent::BudgetExhausted(event)] =
+        SwapProgramEvent::from_logs(&crate::ID, &res.logs)[..]
+    else {
+        panic!("expected a single BudgetExhaustedEvent");
+    };
+    let (dex, required_units, remaining_units) =
+        (event.dex, event.required_units, event.remaining_units);
+    assert_eq!(dex, SupportDex::DLMM as u8);
+    assert_eq!(
+        required_units,
+        SAFETY_MARGIN_UNITS + CREATE_LEDGER_UNITS + leg_units(SupportDex::DLMM, 20)
+    );
+    assert!(remaining_units < SHORT_BUDGET as u64);
+}
+
+#[test]
+fn it_fails_before_the_cpi_when_units_run_short() {
+    let (mut svm, user, ix, base_mint_acc, _) = short_budget_dlmm_ix(false);
+
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(SHORT_BUDGET), ix], &user)
+        .expect_err("a short budget should fail the route");
+
+    assert_custom_error(&res.err, 18); // LossProgramError::ComputeBudgetExhausted
+    assert!(!res.meta.logs.iter().any(|log| log.contains("invoke [2]")));
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
+
+#[test]
+fn it_fills_the_bins_a_dlmm_leg_budgets_for() {
+    let (mut svm, user) = setup("dlmm_pair.json", &DLMM_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 10_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // 2_488_728_927 TEST takes 3 bins of the fixture pair, the leg budgets 2
+    let mut ix = dlmm_swap_ix(
+        &FIXTURE_PAIR,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.data = dex_swap_data(ArbitrageIxData {
+        max_bin_to_process: 2,
+        amount_out: 2_488_728_927,
+        slippage_bps: 50,
+        ..arbitrage_ix_data(SupportDex::DLMM)
+    });
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("the leg should fill the budgeted bins");
+
+    // The first 2 bins hold 2_000_000_000 TEST
+    assert_eq!(token_balance(&svm, &user_mint_acc), 2_000_000_000);
+    let [SwapProgramEvent::Swap(swap), ..] = SwapProgramEvent::from_logs(&crate::ID, &res.logs)[..]
+    else {
+        panic!("expected a SwapEvent");
+    };
+    let amount_out = swap.amount_out;
+    assert_eq!(amount_out, 2_000_000_000);
+}
+
+#[test]
+fn it_skips_a_round_trip_under_no_failure_when_units_run_short() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+    let round_trip = |no_failure| {
+        round_trip_ix(
+            &user.pubkey(),
+            &base_mint_acc,
+            &user_mint_acc,
+            dlmm_swap_ix(&pair, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+            pump_amm_swap_ix(&pool, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+            RoundTripIxData {
+                no_failure,
+                ..round_trip_ix_data(SupportDex::DLMM, SupportDex::PumpAmm, 1_000_000)
+            },
+        )
+    };
+
+    let res = send_tx(
+        &mut svm,
+        &[set_compute_unit_limit(SHORT_BUDGET), round_trip(false)],
+        &user,
+    )
+    .expect_err("a short budget should fail the round trip");
+    assert_custom_error(&res.err, 18); // LossProgramError::ComputeBudgetExhausted
+
+    let res = send_tx(
+        &mut svm,
+        &[set_compute_unit_limit(SHORT_BUDGET), round_trip(true)],
+        &user,
+    )
+    .expect("a short budget under no_failure should be a no-op");
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+    assert_eq!(token_balance(&svm, &user_mint_acc), 0);
+    assert!(!res.logs.iter().any(|log| log.contains("invoke [2]")));
+    let [SwapProgramEvent::BudgetExhausted(event)] =
+        SwapProgramEvent::from_logs(&crate::ID, &res.logs)[..]
+    else {
+        panic!("expected a single BudgetExhaustedEvent");
+    };
+    let (dex, required_units) = (event.dex, event.required_units);
+    assert_eq!(dex, SupportDex::DLMM as u8);
+    assert_eq!(
+        required_units,
+        SAFETY_MARGIN_UNITS
+            + CREATE_LEDGER_UNITS
+            + leg_units(SupportDex::DLMM, 20)
+            + leg_units(SupportDex::PumpAmm, 20)
+    );
+}
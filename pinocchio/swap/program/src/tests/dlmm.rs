@@ -0,0 +1,184 @@
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Signer;
+use solana_pubkey::Pubkey;
+
+use super::*;
+use crate::dex::{DLMMSwap, LbPair};
+
+pub const DLMM_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
+pub const DLMM_EVENT_AUTHORITY: Pubkey =
+    Pubkey::from_str_const("D1ZN9Wj1fRSUQfCjhvnu1hqDMT7hzjzBBpi12nVniYD6");
+
+/// Pair level accounts of a DLMM `swap`, token X is `TEST_MINT` and token Y is WSOL.
+pub struct DlmmPairKeys {
+    pub lb_pair: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub oracle: Pubkey,
+    pub bin_arrays: [Pubkey; 3],
+}
+
+/// Accounts of `fixtures/dlmm_pair.json`.
+pub const FIXTURE_PAIR: DlmmPairKeys = DlmmPairKeys {
+    lb_pair: Pubkey::from_str_const("7Us7HckFMDReGTGMunSMSbp5ogBAsM8k77GcpuyYZyKi"),
+    reserve_x: Pubkey::from_str_const("CTr375mXRBU44mzzcELF9Xa4NXNNQE8ySmWeGEr9djhJ"),
+    reserve_y: Pubkey::from_str_const("AamXpwVtq8VW67XoXQ3HZ9JH3AWntiNs58XMdPUNc7GQ"),
+    oracle: Pubkey::from_str_const("GZtMCU1H8K9qqCsvYctrn149W9D7DPX2s8zogPrBp1Cn"),
+    bin_arrays: [
+        Pubkey::from_str_const("8w1E48HYVoDFALgAFd8c6E1LhvuUmhYF5Ctk4MQn7qmR"),
+        Pubkey::from_str_const("HGvGEDNh4rG6PwUQEbTLumbqeMvCAxjGiEm6zMyuwrnY"),
+        Pubkey::from_str_const("BE5seULJ5AmT4LLsQUxy89eRmUWcCYPgWbEiN9u6jsyd"),
+    ],
+};
+
+/// Quote amount sold by the program on every DLMM swap.
+pub const AMOUNT_IN: u64 = 100;
+
+pub fn dlmm_swap_ix(
+    keys: &DlmmPairKeys,
+    user: &Pubkey,
+    base_mint_acc: &Pubkey,
+    user_mint_acc: &Pubkey,
+) -> Instruction {
+    let mut accounts = common_accounts(user, base_mint_acc, user_mint_acc);
+    accounts.extend([
+        AccountMeta::new_readonly(DLMM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(DLMM_EVENT_AUTHORITY, false),
+        AccountMeta::new(keys.lb_pair, false),
+        AccountMeta::new(keys.reserve_x, false),
+        AccountMeta::new(keys.reserve_y, false),
+        AccountMeta::new(keys.oracle, false),
+    ]);
+    accounts.extend(
+        keys.bin_arrays
+            .iter()
+            .map(|bin_array| AccountMeta::new(*bin_array, false)),
+    );
+    accounts.extend([
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(operator_limits_address(user), false),
+        AccountMeta::new(pnl_ledger_address(user), false),
+    ]);
+    Instruction {
+        program_id: Pubkey::new_from_array(crate::ID),
+        accounts,
+        data: dex_swap_data(arbitrage_ix_data(SupportDex::DLMM)),
+    }
+}
+
+#[test]
+fn it_swaps_quote_for_token_on_dlmm() {
+    let (mut svm, user) = setup("dlmm_pair.json", &DLMM_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let reserve_x_before = token_balance(&svm, &FIXTURE_PAIR.reserve_x);
+    let reserve_y_before = token_balance(&svm, &FIXTURE_PAIR.reserve_y);
+
+    let ix = dlmm_swap_ix(
+        &FIXTURE_PAIR,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("dlmm swap should have succeeded");
+
+    // The swap is exact-in on the quote side, fees stay in the reserve.
+    assert_eq!(
+        token_balance(&svm, &base_mint_acc),
+        1_000_000_000 - AMOUNT_IN
+    );
+    assert_eq!(
+        token_balance(&svm, &FIXTURE_PAIR.reserve_y) - reserve_y_before,
+        AMOUNT_IN
+    );
+
+    let received = token_balance(&svm, &user_mint_acc);
+    assert!(received > 0);
+    assert_eq!(
+        reserve_x_before - token_balance(&svm, &FIXTURE_PAIR.reserve_x),
+        received
+    );
+}
+
+#[test]
+fn it_buys_an_exact_amount_on_dlmm() {
+    let (mut svm, user) = setup("dlmm_pair.json", &DLMM_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = dlmm_swap_ix(
+        &FIXTURE_PAIR,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.data = dex_swap_data(ArbitrageIxData {
+        amount_out: 1_000,
+        slippage_bps: 50,
+        ..arbitrage_ix_data(SupportDex::DLMM)
+    });
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("dlmm swap_exact_out should have succeeded");
+
+    // swap_exact_out delivers the requested amount, the input pays the fee on top
+    assert_eq!(token_balance(&svm, &user_mint_acc), 1_000);
+    let spent = 1_000_000_000 - token_balance(&svm, &base_mint_acc);
+    assert!(spent > 1_000 && spent <= 1_100);
+}
+
+#[test]
+fn it_swaps_on_dlmm_within_the_price_impact() {
+    let (mut svm, user) = setup("dlmm_pair.json", &DLMM_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+    let lb_pair = LbPair::from_bytes(&fixture_account_data(
+        "dlmm_pair.json",
+        &FIXTURE_PAIR.lb_pair,
+    ))
+    .unwrap();
+
+    let mut ix = dlmm_swap_ix(
+        &FIXTURE_PAIR,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.data = dex_swap_data(ArbitrageIxData {
+        max_price_impact_bps: 50,
+        active_id: lb_pair.active_id,
+        ..arbitrage_ix_data(SupportDex::DLMM)
+    });
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("dlmm swap_with_price_impact should have succeeded");
+
+    assert_eq!(
+        token_balance(&svm, &base_mint_acc),
+        1_000_000_000 - AMOUNT_IN
+    );
+    assert!(token_balance(&svm, &user_mint_acc) > 0);
+}
+
+#[test]
+fn it_rejects_dlmm_swap_with_missing_bin_arrays() {
+    let (mut svm, user) = setup("dlmm_pair.json", &DLMM_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = dlmm_swap_ix(
+        &FIXTURE_PAIR,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.accounts.remove(DLMMSwap::MINT_TOKEN_PROGRAM_INDEX - 1);
+
+    let res = send_tx(&mut svm, &[ix], &user);
+    assert!(
+        res.is_err(),
+        "swap with a missing bin array should have failed"
+    );
+    assert_eq!(token_balance(&svm, &base_mint_acc), 1_000_000_000);
+}
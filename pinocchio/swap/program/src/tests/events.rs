@@ -0,0 +1,93 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::string::String;
+
+use crate::events::{ArbitrageEvent, Event, SwapEvent, SwapProgramEvent};
+use crate::state::to_bytes;
+
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::*;
+
+fn data_log<T: Event>(event: &T) -> String {
+    format!(
+        "Program data: {} {}",
+        STANDARD.encode(T::DISCRIMINATOR),
+        STANDARD.encode(unsafe { to_bytes(event) })
+    )
+}
+
+#[test]
+fn it_decodes_only_events_of_the_program() {
+    let program_id = Pubkey::new_from_array(crate::ID);
+    let swap = SwapEvent {
+        dex: SupportDex::DLMM as u8,
+        pool: [7; 32],
+        amount_in: 100,
+        amount_out: 99_000,
+        slot: 42,
+    };
+    let arbitrage = ArbitrageEvent {
+        dex: SupportDex::DLMM as u8,
+        legs: 1,
+        amount_in: 100,
+        amount_out: 0,
+        profit: -100,
+        slot: 42,
+    };
+    let logs = vec![
+        format!("Program {program_id} invoke [1]"),
+        String::from("Program log: Ix:Swap"),
+        format!("Program {PUMP_PROGRAM_ID} invoke [2]"),
+        // Same discriminator, logged by the invoked program.
+        data_log(&swap),
+        format!("Program {PUMP_PROGRAM_ID} success"),
+        data_log(&swap),
+        String::from("Program data: bm90IGFuIGV2ZW50"),
+        data_log(&arbitrage),
+        format!("Program {program_id} consumed 1000 of 200000 compute units"),
+        format!("Program {program_id} success"),
+    ];
+
+    assert_eq!(
+        SwapProgramEvent::from_logs(&crate::ID, &logs),
+        vec![
+            SwapProgramEvent::Swap(swap),
+            SwapProgramEvent::Arbitrage(arbitrage)
+        ]
+    );
+}
+
+#[test]
+fn it_emits_swap_and_arbitrage_events() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    svm.warp_to_slot(42);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("mock pump amm swap should have succeeded");
+
+    let spent = USER_WSOL - token_balance(&svm, &base_mint_acc);
+    assert_eq!(
+        SwapProgramEvent::from_logs(&crate::ID, &res.logs),
+        vec![
+            SwapProgramEvent::Swap(SwapEvent {
+                dex: SupportDex::PumpAmm as u8,
+                pool: keys.pool.to_bytes(),
+                amount_in: spent,
+                amount_out: BASE_AMOUNT_OUT,
+                slot: 42,
+            }),
+            SwapProgramEvent::Arbitrage(ArbitrageEvent {
+                dex: SupportDex::PumpAmm as u8,
+                legs: 1,
+                amount_in: spent,
+                amount_out: 0,
+                profit: -(spent as i64),
+                slot: 42,
+            }),
+        ]
+    );
+}
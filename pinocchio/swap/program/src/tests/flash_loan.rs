@@ -0,0 +1,231 @@
+use std::string::String;
+
+use flash_lender_mock::MockLendingPool;
+use solana_instruction::error::InstructionError;
+use solana_transaction_error::TransactionError;
+
+use super::dlmm::*;
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::round_trip::*;
+use super::*;
+use crate::error::LossProgramError;
+use crate::flash_loan::{FlashLender, MOCK_LENDER};
+
+pub const LENDER_PROGRAM_ID: Pubkey = Pubkey::new_from_array(MOCK_LENDER.program_id);
+const FLASH_LENDER_MOCK_PATH: &str = "target/deploy/flash_lender_mock.so";
+
+const LENDER_LIQUIDITY: u64 = 100_000_000_000;
+const LENDER_FEE_BPS: u16 = 9;
+const LOAN: u64 = 1_000_000;
+
+/// Accounts of a mock lending pool of WSOL.
+pub struct LendingPoolKeys {
+    pub pool: Pubkey,
+    pub vault: Pubkey,
+    pub state: MockLendingPool,
+}
+
+pub fn set_mock_lending_pool(svm: &mut LiteSVM) -> LendingPoolKeys {
+    svm.add_program_from_file(LENDER_PROGRAM_ID, FLASH_LENDER_MOCK_PATH)
+        .unwrap_or_else(|_| {
+            panic!("{FLASH_LENDER_MOCK_PATH} not found, run `cargo build-sbf` first")
+        });
+
+    let (pool, bump) = Pubkey::find_program_address(
+        &[flash_lender_mock::LENDING_POOL_SEED, WSOL_MINT.as_ref()],
+        &LENDER_PROGRAM_ID,
+    );
+    let vault = set_token_account(svm, &pool, &WSOL_MINT, LENDER_LIQUIDITY);
+    let state = MockLendingPool {
+        mint: WSOL_MINT.to_bytes(),
+        vault: vault.to_bytes(),
+        fee_bps: LENDER_FEE_BPS,
+        outstanding: 0,
+        bump,
+    };
+    set_account(svm, pool, LENDER_PROGRAM_ID, state.to_bytes().to_vec());
+    LendingPoolKeys { pool, vault, state }
+}
+
+/// `RoundTrip` buying on the mock DLMM pair and selling on the mock Pump AMM
+/// pool with `LOAN` WSOL borrowed.
+fn flash_loan_round_trip_ix(
+    user: &Pubkey,
+    base_mint_acc: &Pubkey,
+    user_mint_acc: &Pubkey,
+    pair: &DlmmPairKeys,
+    pool: &PumpAmmPoolKeys,
+) -> Instruction {
+    round_trip_ix(
+        user,
+        base_mint_acc,
+        user_mint_acc,
+        dlmm_swap_ix(pair, user, base_mint_acc, user_mint_acc),
+        pump_amm_swap_ix(pool, user, base_mint_acc, user_mint_acc),
+        RoundTripIxData {
+            flash_loan_amount: LOAN,
+            ..round_trip_ix_data(SupportDex::DLMM, SupportDex::PumpAmm, 0)
+        },
+    )
+}
+
+/// Appends the lender accounts, calling `lender_program` on `lender`.
+fn with_lender(
+    mut ix: Instruction,
+    lender_program: Pubkey,
+    lender: &LendingPoolKeys,
+) -> Instruction {
+    ix.accounts.extend([
+        AccountMeta::new_readonly(lender_program, false),
+        AccountMeta::new(lender.pool, false),
+        AccountMeta::new(lender.vault, false),
+    ]);
+    ix
+}
+
+fn lender_invocations(logs: &[String]) -> usize {
+    let invoke = format!("Program {LENDER_PROGRAM_ID} invoke");
+    logs.iter().filter(|log| log.starts_with(&invoke)).count()
+}
+
+#[test]
+fn it_reads_the_mock_lending_pool_with_the_mock_lender_layout() {
+    let state = MockLendingPool {
+        mint: WSOL_MINT.to_bytes(),
+        vault: Pubkey::new_unique().to_bytes(),
+        fee_bps: LENDER_FEE_BPS,
+        outstanding: 0,
+        bump: 255,
+    };
+    let data = state.to_bytes();
+    let lender = FlashLender::find(&LENDER_PROGRAM_ID.to_bytes()).unwrap();
+    assert_eq!(*lender, MOCK_LENDER);
+    assert_eq!(data.len(), lender.pool_len);
+    assert_eq!(
+        data[lender.vault_offset..lender.vault_offset + 32],
+        state.vault
+    );
+    assert_eq!(
+        data[lender.fee_bps_offset..lender.fee_bps_offset + 2],
+        LENDER_FEE_BPS.to_le_bytes()
+    );
+    assert!(FlashLender::find(&PUMP_PROGRAM_ID.to_bytes()).is_none());
+}
+
+#[test]
+fn it_repays_a_flash_loan_round_trip_out_of_its_profit() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    let lender = set_mock_lending_pool(&mut svm);
+    // TEST costs half as much WSOL on the DLMM pair as on the Pump AMM pool
+    set_token_account(&mut svm, &pair.lb_pair, &WSOL_MINT, WSOL_RESERVE / 2);
+    // The user holds no inventory, the route only has the borrowed WSOL.
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 0);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let ix = with_lender(
+        flash_loan_round_trip_ix(&user.pubkey(), &base_mint_acc, &user_mint_acc, &pair, &pool),
+        LENDER_PROGRAM_ID,
+        &lender,
+    );
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(600_000), ix], &user)
+        .expect("the round trip should repay the loan with profit");
+
+    let (bought, _) = dlmm_mock::quote_swap(WSOL_RESERVE / 2, TOKEN_RESERVE, LOAN).unwrap();
+    let (_, received) = pump_amm_mock::quote_sell(TOKEN_RESERVE, WSOL_RESERVE, bought).unwrap();
+    let owed = lender.state.owed(LOAN).unwrap();
+    assert!(owed > LOAN);
+    assert_eq!(lender_invocations(&res.logs), 2);
+    assert_eq!(
+        token_balance(&svm, &lender.vault),
+        LENDER_LIQUIDITY + owed - LOAN
+    );
+    assert_eq!(token_balance(&svm, &base_mint_acc), received - owed);
+    assert_eq!(token_balance(&svm, &user_mint_acc), 0);
+}
+
+#[test]
+fn it_rejects_a_flash_loan_route_below_min_profit() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    let lender = set_mock_lending_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // Same price on both pools, the fees make a loss
+    let ix = with_lender(
+        flash_loan_round_trip_ix(&user.pubkey(), &base_mint_acc, &user_mint_acc, &pair, &pool),
+        LENDER_PROGRAM_ID,
+        &lender,
+    );
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(600_000), ix], &user)
+        .expect_err("a losing round trip cannot pay back the loan with profit");
+
+    // The loan was borrowed and repaid out of the user's own WSOL before the
+    // profit check failed.
+    assert_eq!(lender_invocations(&res.meta.logs), 2);
+    assert_custom_error(&res.err, LossProgramError::ProfitBelowThreshold as u32);
+    assert_eq!(token_balance(&svm, &lender.vault), LENDER_LIQUIDITY);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
+
+#[test]
+fn it_rejects_a_flash_loan_that_cannot_be_repaid() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    let lender = set_mock_lending_pool(&mut svm);
+    // The user holds no inventory, the route only has the borrowed WSOL.
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 0);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let ix = with_lender(
+        flash_loan_round_trip_ix(&user.pubkey(), &base_mint_acc, &user_mint_acc, &pair, &pool),
+        LENDER_PROGRAM_ID,
+        &lender,
+    );
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(600_000), ix], &user)
+        .expect_err("the fees of the two legs are taken out of the loan");
+
+    assert_eq!(lender_invocations(&res.meta.logs), 1);
+    assert_custom_error(&res.err, LossProgramError::FlashLoanNotRepaid as u32);
+    assert_eq!(token_balance(&svm, &lender.vault), LENDER_LIQUIDITY);
+}
+
+#[test]
+fn it_only_borrows_from_a_listed_lender() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    let lender = set_mock_lending_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // An executable program missing from `FLASH_LENDERS`
+    let ix = with_lender(
+        flash_loan_round_trip_ix(&user.pubkey(), &base_mint_acc, &user_mint_acc, &pair, &pool),
+        PUMP_PROGRAM_ID,
+        &lender,
+    );
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(600_000), ix], &user)
+        .expect_err("only a listed lender may be called");
+
+    assert!(matches!(
+        res.err,
+        TransactionError::InstructionError(1, InstructionError::IncorrectProgramId)
+    ));
+    assert_eq!(lender_invocations(&res.meta.logs), 0);
+}
+
+#[test]
+// pinocchio 的 `NotEnoughAccountKeys` 仍映射到这个已弃用的变体
+#[allow(deprecated)]
+fn it_requires_the_lender_accounts_in_flash_loan_mode() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let ix = flash_loan_round_trip_ix(&user.pubkey(), &base_mint_acc, &user_mint_acc, &pair, &pool);
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(600_000), ix], &user)
+        .expect_err("lender accounts are missing");
+
+    assert!(matches!(
+        res.err,
+        TransactionError::InstructionError(1, InstructionError::NotEnoughAccountKeys)
+    ));
+}
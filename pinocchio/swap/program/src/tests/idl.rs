@@ -0,0 +1,138 @@
+//! The checked-in client IDL matches the layouts the program reads.
+use crate::dex::{DLMMSwap, PumpAmmSwap};
+use crate::events::{ArbitrageEvent, BudgetExhaustedEvent, SwapEvent};
+use crate::idl::*;
+use crate::instruction::layout::COMMON_ACCOUNTS;
+use crate::instruction::{OperatorLimitsIxData, QuoteIxData, RoundTripIxData};
+use crate::state::DataLen;
+
+use super::*;
+
+const CLIENT_IDL_PATH: &str = "../client/idl/swap_program.json";
+
+fn packed_len(fields: Fields) -> usize {
+    fields.iter().map(|(_, ty)| ty.size()).sum()
+}
+
+#[test]
+fn it_matches_the_checked_in_idl() {
+    let checked_in: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(CLIENT_IDL_PATH).unwrap()).unwrap();
+    assert!(
+        checked_in == idl(),
+        "{CLIENT_IDL_PATH} is stale, run `cargo run --features idl --bin idl > {CLIENT_IDL_PATH}`"
+    );
+}
+
+#[test]
+fn it_describes_every_byte_of_the_packed_structs() {
+    assert_eq!(packed_len(ARBITRAGE_IX_DATA_FIELDS), ArbitrageIxData::LEN);
+    assert_eq!(packed_len(QUOTE_IX_DATA_FIELDS), QuoteIxData::LEN);
+    assert_eq!(packed_len(ROUND_TRIP_IX_DATA_FIELDS), RoundTripIxData::LEN);
+    assert_eq!(
+        packed_len(OPERATOR_LIMITS_IX_DATA_FIELDS),
+        OperatorLimitsIxData::LEN
+    );
+    assert_eq!(packed_len(SWAP_EVENT_FIELDS), SwapEvent::LEN);
+    assert_eq!(packed_len(ARBITRAGE_EVENT_FIELDS), ArbitrageEvent::LEN);
+    assert_eq!(
+        packed_len(BUDGET_EXHAUSTED_EVENT_FIELDS),
+        BudgetExhaustedEvent::LEN
+    );
+
+    for (i, dex) in SUPPORT_DEX_VARIANTS.iter().enumerate() {
+        assert_eq!(SupportDex::try_from(&(i as u8)).unwrap(), *dex);
+    }
+    assert!(SupportDex::try_from(&(SUPPORT_DEX_VARIANTS.len() as u8)).is_err());
+}
+
+#[test]
+fn it_numbers_the_errors_by_their_codes() {
+    for (code, error) in LOSS_PROGRAM_ERRORS.iter().enumerate() {
+        assert_eq!(*error as usize, code);
+    }
+    let errors = idl()["errors"].as_array().unwrap().clone();
+    let mut codes: Vec<u64> = errors.iter().map(|e| e["code"].as_u64().unwrap()).collect();
+    codes.dedup();
+    assert_eq!(codes.len(), errors.len());
+    assert_eq!(
+        errors.len(),
+        LOSS_PROGRAM_ERRORS.len() + PUMP_AMM_ERRORS.len() + DLMM_ERRORS.len()
+    );
+}
+
+#[test]
+fn it_indexes_the_leg_accounts_the_program_reads() {
+    let name = |accounts: &[_], index: usize| {
+        let accounts: Vec<_> = COMMON_ACCOUNTS.iter().chain(accounts).collect();
+        accounts[index].name
+    };
+
+    assert_eq!(
+        name(&PumpAmmSwap::ACCOUNTS, PumpAmmSwap::POOL_INDEX),
+        "pool_acc"
+    );
+    assert_eq!(
+        PumpAmmSwap::POOL_ACCOUNTS
+            .map(|i| name(&PumpAmmSwap::ACCOUNTS, i))
+            .collect::<Vec<_>>(),
+        ["pool_acc", "pool_base_token_acc", "pool_quote_token_acc"]
+    );
+    assert_eq!(
+        name(
+            &PumpAmmSwap::ACCOUNTS,
+            PumpAmmSwap::MINT_TOKEN_PROGRAM_INDEX
+        ),
+        "mint_token_program"
+    );
+    assert_eq!(name(&DLMMSwap::ACCOUNTS, DLMMSwap::POOL_INDEX), "lb_pair");
+    assert_eq!(
+        DLMMSwap::POOL_ACCOUNTS
+            .map(|i| name(&DLMMSwap::ACCOUNTS, i))
+            .collect::<Vec<_>>(),
+        [
+            "lb_pair",
+            "reserve_x",
+            "reserve_y",
+            "oracle",
+            "bin_1",
+            "bin_2",
+            "bin_3"
+        ]
+    );
+    assert_eq!(
+        name(&DLMMSwap::ACCOUNTS, DLMMSwap::MINT_TOKEN_PROGRAM_INDEX),
+        "token_x_program"
+    );
+
+    // the operator limits PDA follows the leg accounts, optional accounts come last
+    for leg_accounts in [&PumpAmmSwap::ACCOUNTS[..], &DLMMSwap::ACCOUNTS[..]] {
+        let accounts = swap_accounts(leg_accounts);
+        assert_eq!(
+            accounts[COMMON_ACCOUNTS.len() + leg_accounts.len()].name,
+            "operator_limits"
+        );
+        let first_optional = accounts.iter().position(|a| a.optional).unwrap();
+        assert!(accounts[first_optional..].iter().all(|a| a.optional));
+    }
+
+    // a round trip reads its sell leg right after the buy leg, then the PDAs
+    for (buy, sell) in [
+        (&PumpAmmSwap::ACCOUNTS[..], &DLMMSwap::ACCOUNTS[..]),
+        (&DLMMSwap::ACCOUNTS[..], &PumpAmmSwap::ACCOUNTS[..]),
+    ] {
+        let accounts = round_trip_accounts(buy, sell);
+        assert_eq!(
+            accounts[COMMON_ACCOUNTS.len() + buy.len()].name,
+            sell[0].name
+        );
+        assert_eq!(
+            accounts[COMMON_ACCOUNTS.len() + buy.len() + sell.len()].name,
+            "operator_limits"
+        );
+        let mut names: Vec<_> = accounts.iter().map(|a| a.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), accounts.len());
+    }
+}
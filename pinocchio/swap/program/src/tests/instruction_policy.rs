@@ -0,0 +1,105 @@
+//! Transaction layout checks read from the instructions sysvar.
+use solana_keypair::Signer;
+
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::*;
+use crate::introspection::INSTRUCTION_POLICY_FLAG;
+
+const INSTRUCTIONS_SYSVAR_ID: Pubkey =
+    Pubkey::from_str_const("Sysvar1nstructions1111111111111111111111111");
+
+/// A mock Pump AMM swap with the policy enabled and the sysvar appended.
+fn policy_swap(svm: &mut LiteSVM, user: &Keypair) -> (Instruction, PumpAmmPoolKeys, Pubkey) {
+    let keys = set_mock_pump_pool(svm);
+    let base_mint_acc = set_token_account(svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(svm, &user.pubkey(), &TEST_MINT, 0);
+    let mut ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        flags: INSTRUCTION_POLICY_FLAG,
+        ..arbitrage_ix_data(SupportDex::PumpAmm)
+    });
+    ix.accounts
+        .push(AccountMeta::new_readonly(INSTRUCTIONS_SYSVAR_ID, false));
+    (ix, keys, base_mint_acc)
+}
+
+/// System transfer of 0.001 SOL from `user` to `to`.
+fn transfer(user: &Keypair, to: Pubkey) -> Instruction {
+    Instruction {
+        program_id: SYSTEM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(user.pubkey(), true),
+            AccountMeta::new(to, false),
+        ],
+        data: [2u32.to_le_bytes().as_slice(), &1_000_000u64.to_le_bytes()].concat(),
+    }
+}
+
+#[test]
+fn it_accepts_a_swap_after_the_compute_budget() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let (ix, _, base_mint_acc) = policy_swap(&mut svm, &user);
+
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("the swap should pass the policy");
+    assert!(token_balance(&svm, &base_mint_acc) < USER_WSOL);
+}
+
+#[test]
+fn it_requires_a_compute_budget_instruction() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let (ix, _, base_mint_acc) = policy_swap(&mut svm, &user);
+
+    let res = send_tx(&mut svm, &[ix], &user).expect_err("the swap should fail");
+    assert_custom_error(&res.err, 20); // LossProgramError::MissingComputeBudget
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
+
+#[test]
+fn it_rejects_other_programs_before_the_swap() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let (ix, _, _) = policy_swap(&mut svm, &user);
+    let tip = transfer(&user, Pubkey::new_unique());
+
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), tip, ix], &user)
+        .expect_err("the swap should fail");
+    assert_custom_error(&res.err, 21); // LossProgramError::InstructionPositionNotAllowed
+}
+
+#[test]
+fn it_rejects_instructions_sharing_the_pool_accounts() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let (ix, keys, base_mint_acc) = policy_swap(&mut svm, &user);
+
+    for account in [
+        keys.pool,
+        keys.pool_base_token_acc,
+        keys.pool_quote_token_acc,
+    ] {
+        let res = send_tx(
+            &mut svm,
+            &[
+                set_compute_unit_limit(400_000),
+                ix.clone(),
+                transfer(&user, account),
+            ],
+            &user,
+        )
+        .expect_err("the swap should fail");
+        assert_custom_error(&res.err, 22); // LossProgramError::PoolAccountShared
+        assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+    }
+
+    // a tip after the swap, outside the pool accounts, is fine
+    send_tx(
+        &mut svm,
+        &[
+            set_compute_unit_limit(400_000),
+            ix,
+            transfer(&user, Pubkey::new_unique()),
+        ],
+        &user,
+    )
+    .expect("the swap should pass the policy");
+}
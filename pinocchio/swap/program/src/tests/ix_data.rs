@@ -0,0 +1,157 @@
+use pinocchio::program_error::ProgramError;
+
+use super::*;
+use crate::error::LossProgramError;
+use crate::instruction::{process_instruction, QuoteIxData, RoundTripIxData};
+use crate::state::{load_ix_data, DataLen};
+
+#[test]
+fn it_rejects_ix_data_with_invalid_enum_or_bool_bytes() {
+    let mut data = dex_swap_data(arbitrage_ix_data(SupportDex::DLMM)).split_off(1);
+    assert!(unsafe { load_ix_data::<ArbitrageIxData>(&data) }.is_ok());
+
+    // dex 7 is past `SupportDex::WhirlPool`
+    data[0] = 7;
+    assert_eq!(
+        unsafe { load_ix_data::<ArbitrageIxData>(&data) }.unwrap_err(),
+        ProgramError::InvalidInstructionData
+    );
+    data[0] = SupportDex::DLMM as u8;
+    data[core::mem::offset_of!(ArbitrageIxData, no_failure)] = 2;
+    assert_eq!(
+        unsafe { load_ix_data::<ArbitrageIxData>(&data) }.unwrap_err(),
+        LossProgramError::InvalidInstructionData.into()
+    );
+
+    data[core::mem::offset_of!(ArbitrageIxData, no_failure)] = 1;
+    let slippage_bps = core::mem::offset_of!(ArbitrageIxData, slippage_bps);
+    data[slippage_bps..slippage_bps + 2].copy_from_slice(&10_000u16.to_le_bytes());
+    assert!(unsafe { load_ix_data::<ArbitrageIxData>(&data) }.is_ok());
+    data[slippage_bps..slippage_bps + 2].copy_from_slice(&10_001u16.to_le_bytes());
+    assert_eq!(
+        unsafe { load_ix_data::<ArbitrageIxData>(&data) }.unwrap_err(),
+        LossProgramError::InvalidInstructionData.into()
+    );
+
+    data[slippage_bps..slippage_bps + 2].copy_from_slice(&0u16.to_le_bytes());
+
+    // the price impact mode only applies to exact in DLMM legs
+    let with_price_impact = |ix_data: ArbitrageIxData| {
+        let data = dex_swap_data(ArbitrageIxData {
+            max_price_impact_bps: 100,
+            ..ix_data
+        });
+        unsafe { load_ix_data::<ArbitrageIxData>(&data[1..]) }.map(|_| ())
+    };
+    assert_eq!(
+        with_price_impact(arbitrage_ix_data(SupportDex::DLMM)),
+        Ok(())
+    );
+    assert_eq!(
+        with_price_impact(arbitrage_ix_data(SupportDex::PumpAmm)),
+        Err(LossProgramError::InvalidInstructionData.into())
+    );
+    assert_eq!(
+        with_price_impact(ArbitrageIxData {
+            amount_out: 1_000,
+            ..arbitrage_ix_data(SupportDex::DLMM)
+        }),
+        Err(LossProgramError::InvalidInstructionData.into())
+    );
+
+    // a round trip needs two different DEXes that both have an adapter
+    let round_trip = |buy_dex, sell_dex| {
+        let data = round_trip_data(round_trip_ix_data(buy_dex, sell_dex, 1_000));
+        unsafe { load_ix_data::<RoundTripIxData>(&data[1..]) }.map(|_| ())
+    };
+    assert_eq!(round_trip(SupportDex::PumpAmm, SupportDex::DLMM), Ok(()));
+    assert_eq!(round_trip(SupportDex::DLMM, SupportDex::PumpAmm), Ok(()));
+    for (buy_dex, sell_dex) in [
+        (SupportDex::DLMM, SupportDex::DLMM),
+        (SupportDex::PumpAmm, SupportDex::PumpAmm),
+        (SupportDex::RaydiumCP, SupportDex::DLMM),
+        (SupportDex::PumpAmm, SupportDex::WhirlPool),
+    ] {
+        assert_eq!(
+            round_trip(buy_dex, sell_dex),
+            Err(LossProgramError::InvalidInstructionData.into())
+        );
+    }
+    // a flash-loan round trip spends the loan, not `amount_in`
+    let flash_loan = |amount_in| {
+        let data = round_trip_data(RoundTripIxData {
+            flash_loan_amount: 1_000,
+            ..round_trip_ix_data(SupportDex::DLMM, SupportDex::PumpAmm, amount_in)
+        });
+        unsafe { load_ix_data::<RoundTripIxData>(&data[1..]) }.map(|_| ())
+    };
+    assert_eq!(flash_loan(0), Ok(()));
+    assert_eq!(
+        flash_loan(1_000),
+        Err(LossProgramError::InvalidInstructionData.into())
+    );
+    let mut round_trip = round_trip_data(round_trip_ix_data(
+        SupportDex::DLMM,
+        SupportDex::PumpAmm,
+        1_000,
+    ));
+    round_trip[1 + core::mem::offset_of!(RoundTripIxData, no_failure)] = 2;
+    assert_eq!(
+        unsafe { load_ix_data::<RoundTripIxData>(&round_trip[1..]) }.unwrap_err(),
+        LossProgramError::InvalidInstructionData.into()
+    );
+
+    let mut quote = [0u8; QuoteIxData::LEN];
+    quote[0] = u8::MAX;
+    assert_eq!(
+        unsafe { load_ix_data::<QuoteIxData>(&quote) }.unwrap_err(),
+        ProgramError::InvalidInstructionData
+    );
+    assert_eq!(
+        unsafe { load_ix_data::<QuoteIxData>(&quote[1..]) }.unwrap_err(),
+        LossProgramError::InvalidInstructionData.into()
+    );
+}
+
+#[test]
+fn it_returns_errors_for_malformed_instructions() {
+    let process = |data: &[u8]| process_instruction(&crate::ID, &[], data);
+
+    assert_eq!(process(&[]), Err(ProgramError::InvalidInstructionData));
+    assert_eq!(process(&[4]), Err(ProgramError::InvalidInstructionData));
+
+    let mut data = dex_swap_data(arbitrage_ix_data(SupportDex::DLMM));
+    assert_eq!(process(&data), Err(ProgramError::NotEnoughAccountKeys));
+    data[1] = 7;
+    assert_eq!(process(&data), Err(ProgramError::InvalidInstructionData));
+    assert_eq!(
+        process(&data[..data.len() - 1]),
+        Err(LossProgramError::InvalidInstructionData.into())
+    );
+
+    let mut round_trip =
+        round_trip_data(round_trip_ix_data(SupportDex::DLMM, SupportDex::PumpAmm, 0));
+    assert_eq!(
+        process(&round_trip),
+        Err(LossProgramError::AmountTooSmall.into())
+    );
+    round_trip[3] = 1;
+    assert_eq!(
+        process(&round_trip),
+        Err(ProgramError::NotEnoughAccountKeys)
+    );
+    round_trip[2] = 7;
+    assert_eq!(
+        process(&round_trip),
+        Err(ProgramError::InvalidInstructionData)
+    );
+
+    let quote = [
+        LossProgramInstruction::Quote as u8,
+        SupportDex::PumpAmm as u8,
+    ];
+    assert_eq!(
+        process(&quote),
+        Err(LossProgramError::InvalidInstructionData.into())
+    );
+}
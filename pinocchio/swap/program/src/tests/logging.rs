@@ -0,0 +1,49 @@
+use crate::logging::LogLevel;
+
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::*;
+
+#[test]
+fn it_caps_the_requested_log_level_at_compile_time() {
+    assert_eq!(LogLevel::from_flags(0), LogLevel::Off);
+    assert_eq!(LogLevel::from_flags(1), LogLevel::Info.min(LogLevel::MAX));
+    assert_eq!(LogLevel::from_flags(2), LogLevel::Debug.min(LogLevel::MAX));
+    // Other flag bits do not change the level.
+    assert_eq!(LogLevel::from_flags(0b1111_1100), LogLevel::Off);
+}
+
+/// `swap_program.so` built without `log-info`/`log-debug` only emits events,
+/// whatever level the instruction asks for.
+#[test]
+fn it_only_emits_events_without_log_features() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        flags: LogLevel::Debug as u8,
+        ..arbitrage_ix_data(SupportDex::PumpAmm)
+    });
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("mock pump amm swap should have succeeded");
+
+    // Only the logs written while swap_program itself is running count.
+    let program_id = format!("{}", Pubkey::new_from_array(crate::ID));
+    let mut stack = Vec::new();
+    for log in &res.logs {
+        if log.starts_with("Program log: ") {
+            assert_ne!(
+                stack.last(),
+                Some(&program_id.as_str()),
+                "unexpected log {log:?}"
+            );
+        } else if log.contains(" invoke [") {
+            stack.push(log.split(' ').nth(1).unwrap());
+        } else if log.ends_with(" success") || log.contains(" failed: ") {
+            stack.pop();
+        }
+    }
+}
@@ -0,0 +1,591 @@
+//! CPI tests against the mock DEX programs of `mocks/`.
+//!
+//! The mocks are deployed at the real DEX program ids, take the same
+//! discriminators and account order, and record what they received in a
+//! `Program data:` log, so these tests pin the exact CPI swap_program builds
+//! without any mainnet state. Build them with `cargo build-sbf` from the
+//! workspace root, next to swap_program.
+use base64::{engine::general_purpose::STANDARD, Engine};
+use dlmm_mock::MockLbPair;
+use litesvm::types::TransactionMetadata;
+use pump_amm_mock::{MockGlobalConfig, MockPool};
+use solana_instruction::error::InstructionError;
+use solana_transaction_error::TransactionError;
+
+use crate::dex::{DLMMSwap, PumpAmmSwap};
+
+use super::dlmm::*;
+use super::pump_amm::*;
+use super::*;
+
+pub const PUMP_AMM_MOCK_PATH: &str = "target/deploy/pump_amm_mock.so";
+pub const DLMM_MOCK_PATH: &str = "target/deploy/dlmm_mock.so";
+
+const MINT_LEN: usize = 82;
+
+/// Initial reserves of the mock pools, `TEST_MINT` against WSOL.
+pub const TOKEN_RESERVE: u64 = 800_000_000_000_000;
+pub const WSOL_RESERVE: u64 = 40_000_000_000;
+
+pub const USER_WSOL: u64 = 1_000_000_000;
+
+/// What a mock program received, decoded from its `Program data:` record.
+struct Received {
+    data: Vec<u8>,
+    accounts: Vec<(Pubkey, u8)>,
+}
+
+impl Received {
+    /// Returns the record of the first mock call of the transaction.
+    fn from_logs(meta: &TransactionMetadata) -> Self {
+        meta.logs
+            .iter()
+            .filter_map(|log| log.strip_prefix("Program data: "))
+            .map(|fields| {
+                fields
+                    .split(' ')
+                    .map(|field| STANDARD.decode(field).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .find(|fields| fields[0] == mock_utils::RECORD_TAG)
+            .map(|fields| Self {
+                data: fields[1].clone(),
+                accounts: fields[3..]
+                    .iter()
+                    .zip(&fields[2])
+                    .map(|(key, flags)| (Pubkey::try_from(key.as_slice()).unwrap(), *flags))
+                    .collect(),
+            })
+            .expect("no mock record in the logs")
+    }
+
+    fn assert_accounts(&self, expected: &[AccountMeta]) {
+        assert_eq!(self.accounts.len(), expected.len());
+        for (slot, ((key, flags), meta)) in self.accounts.iter().zip(expected).enumerate() {
+            assert_eq!(*key, meta.pubkey, "account {slot}");
+            assert_eq!(
+                flags & mock_utils::RECORD_SIGNER != 0,
+                meta.is_signer,
+                "signer flag of account {slot}"
+            );
+            assert_eq!(
+                flags & mock_utils::RECORD_WRITABLE != 0,
+                meta.is_writable,
+                "writable flag of account {slot}"
+            );
+        }
+    }
+}
+
+pub fn setup_mock(dex_program_id: &Pubkey, mock_path: &str) -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(crate::ID, SWAP_PROGRAM_PATH)
+        .expect("swap_program.so not found, run `cargo build-sbf` first");
+    svm.add_program_from_file(*dex_program_id, mock_path)
+        .unwrap_or_else(|_| panic!("{mock_path} not found, run `cargo build-sbf` first"));
+
+    for mint in [WSOL_MINT, TEST_MINT] {
+        let mut data = vec![0u8; MINT_LEN];
+        data[44] = 9;
+        // is_initialized
+        data[45] = 1;
+        set_account(&mut svm, mint, TOKEN_PROGRAM_ID, data);
+    }
+
+    let user = new_operator(&mut svm);
+    (svm, user)
+}
+
+pub fn set_account(svm: &mut LiteSVM, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    let lamports = svm.minimum_balance_for_rent_exemption(data.len());
+    svm.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+pub fn assert_custom_error(err: &TransactionError, code: u32) {
+    assert!(
+        matches!(err, TransactionError::InstructionError(_, InstructionError::Custom(c)) if *c == code),
+        "expected custom error {code}, got {err:?}"
+    );
+}
+
+/// Creates a mock Pump AMM pool of `TEST_MINT`/WSOL and the global config of the mock.
+pub fn set_mock_pump_pool(svm: &mut LiteSVM) -> PumpAmmPoolKeys {
+    let (pool, bump) = Pubkey::find_program_address(
+        &[
+            pump_amm_mock::POOL_SEED,
+            TEST_MINT.as_ref(),
+            WSOL_MINT.as_ref(),
+        ],
+        &PUMP_PROGRAM_ID,
+    );
+    let coin_creator = Pubkey::new_unique();
+    let (coin_creator_vault_authority, _) = Pubkey::find_program_address(
+        &[pump_amm_mock::CREATOR_VAULT_SEED, coin_creator.as_ref()],
+        &PUMP_PROGRAM_ID,
+    );
+
+    let keys = PumpAmmPoolKeys {
+        pool,
+        pool_base_token_acc: set_token_account(svm, &pool, &TEST_MINT, TOKEN_RESERVE),
+        pool_quote_token_acc: set_token_account(svm, &pool, &WSOL_MINT, WSOL_RESERVE),
+        protocol_fee_recipient: PUMP_FEE_WALLET,
+        protocol_fee_recipient_token_acc: set_token_account(svm, &PUMP_FEE_WALLET, &WSOL_MINT, 0),
+        coin_creator_vault_ata: set_token_account(
+            svm,
+            &coin_creator_vault_authority,
+            &WSOL_MINT,
+            0,
+        ),
+        coin_creator_vault_authority,
+    };
+    let state = MockPool {
+        base_mint: TEST_MINT.to_bytes(),
+        quote_mint: WSOL_MINT.to_bytes(),
+        pool_base_token_account: keys.pool_base_token_acc.to_bytes(),
+        pool_quote_token_account: keys.pool_quote_token_acc.to_bytes(),
+        coin_creator: coin_creator.to_bytes(),
+        bump,
+    };
+    set_account(svm, pool, PUMP_PROGRAM_ID, state.to_bytes().to_vec());
+    let mut protocol_fee_recipients = [[0u8; 32]; 8];
+    protocol_fee_recipients[0] = keys.protocol_fee_recipient.to_bytes();
+    let config = MockGlobalConfig {
+        protocol_fee_recipients,
+    };
+    set_account(
+        svm,
+        PUMP_GLOBAL_CONFIG,
+        PUMP_PROGRAM_ID,
+        config.to_bytes().to_vec(),
+    );
+    keys
+}
+
+/// Creates a mock DLMM pair with token X `TEST_MINT` and token Y WSOL.
+pub fn set_mock_dlmm_pair(svm: &mut LiteSVM) -> DlmmPairKeys {
+    let (lb_pair, bump) = Pubkey::find_program_address(
+        &[
+            dlmm_mock::LB_PAIR_SEED,
+            TEST_MINT.as_ref(),
+            WSOL_MINT.as_ref(),
+        ],
+        &DLMM_PROGRAM_ID,
+    );
+    let keys = DlmmPairKeys {
+        lb_pair,
+        reserve_x: set_token_account(svm, &lb_pair, &TEST_MINT, TOKEN_RESERVE),
+        reserve_y: set_token_account(svm, &lb_pair, &WSOL_MINT, WSOL_RESERVE),
+        oracle: Pubkey::new_unique(),
+        bin_arrays: [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ],
+    };
+    for address in keys.bin_arrays.iter().chain([&keys.oracle]) {
+        set_account(svm, *address, DLMM_PROGRAM_ID, vec![0u8; 8]);
+    }
+    let state = MockLbPair {
+        token_x_mint: TEST_MINT.to_bytes(),
+        token_y_mint: WSOL_MINT.to_bytes(),
+        reserve_x: keys.reserve_x.to_bytes(),
+        reserve_y: keys.reserve_y.to_bytes(),
+        oracle: keys.oracle.to_bytes(),
+        bump,
+    };
+    set_account(svm, lb_pair, DLMM_PROGRAM_ID, state.to_bytes().to_vec());
+    keys
+}
+
+#[test]
+fn it_shares_the_discriminators_with_the_mocks() {
+    assert_eq!(
+        pump_amm_mock::BUY_DISCRIMINATOR,
+        crate::dex::BUY_DISCRIMINATOR
+    );
+    assert_eq!(
+        pump_amm_mock::SELL_DISCRIMINATOR,
+        crate::dex::SELL_DISCRIMINATOR
+    );
+    assert_eq!(
+        dlmm_mock::SWAP_DISCRIMINATOR,
+        crate::dex::SWAP_DISCRIMINATOR
+    );
+    assert_eq!(
+        dlmm_mock::SWAP_EXACT_OUT_DISCRIMINATOR,
+        crate::dex::SWAP_EXACT_OUT_DISCRIMINATOR
+    );
+    assert_eq!(
+        dlmm_mock::SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR,
+        crate::dex::SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR
+    );
+    assert_eq!(
+        flash_lender_mock::BORROW_DISCRIMINATOR,
+        crate::flash_loan::MOCK_LENDER.borrow_discriminator
+    );
+    assert_eq!(
+        flash_lender_mock::REPAY_DISCRIMINATOR,
+        crate::flash_loan::MOCK_LENDER.repay_discriminator
+    );
+}
+
+#[test]
+fn it_sends_pump_amm_buy_in_idl_order() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("mock pump amm swap should have succeeded");
+
+    let received = Received::from_logs(&res);
+    let mut data = pump_amm_mock::BUY_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&BASE_AMOUNT_OUT.to_le_bytes());
+    data.extend_from_slice(&MAX_QUOTE_AMOUNT_IN.to_le_bytes());
+    assert_eq!(received.data, data);
+    received.assert_accounts(&[
+        AccountMeta::new_readonly(keys.pool, false),
+        AccountMeta::new(user.pubkey(), true),
+        AccountMeta::new_readonly(PUMP_GLOBAL_CONFIG, false),
+        AccountMeta::new_readonly(TEST_MINT, false),
+        AccountMeta::new_readonly(WSOL_MINT, false),
+        AccountMeta::new(user_mint_acc, false),
+        AccountMeta::new(base_mint_acc, false),
+        AccountMeta::new(keys.pool_base_token_acc, false),
+        AccountMeta::new(keys.pool_quote_token_acc, false),
+        AccountMeta::new_readonly(keys.protocol_fee_recipient, false),
+        AccountMeta::new(keys.protocol_fee_recipient_token_acc, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(PUMP_EVENT_AUTHORITY, false),
+        AccountMeta::new_readonly(PUMP_PROGRAM_ID, false),
+        AccountMeta::new(keys.coin_creator_vault_ata, false),
+        AccountMeta::new_readonly(keys.coin_creator_vault_authority, false),
+    ]);
+
+    let (quote, total) =
+        pump_amm_mock::quote_buy(TOKEN_RESERVE, WSOL_RESERVE, BASE_AMOUNT_OUT).unwrap();
+    assert_eq!(token_balance(&svm, &user_mint_acc), BASE_AMOUNT_OUT);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL - total);
+    assert_eq!(
+        token_balance(&svm, &keys.pool_quote_token_acc),
+        WSOL_RESERVE + quote.quote_amount + quote.lp_fee
+    );
+    assert_eq!(
+        token_balance(&svm, &keys.protocol_fee_recipient_token_acc),
+        quote.protocol_fee
+    );
+    assert_eq!(
+        token_balance(&svm, &keys.coin_creator_vault_ata),
+        quote.coin_creator_fee
+    );
+}
+
+#[test]
+fn it_reports_the_misplaced_pump_amm_account() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let mut keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // The protocol fee goes to a token account of someone else.
+    keys.protocol_fee_recipient_token_acc =
+        set_token_account(&mut svm, &Pubkey::new_unique(), &WSOL_MINT, 0);
+
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    let res = send_tx(&mut svm, &[ix], &user).expect_err("mock should have rejected the swap");
+    assert_custom_error(&res.err, 110);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
+
+#[test]
+fn it_creates_the_missing_output_ata() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    // First trade of TEST, the user has no account for it yet
+    let user_mint_acc = associated_token_address(&user.pubkey(), &TEST_MINT);
+    assert!(svm.get_account(&user_mint_acc).is_none());
+
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("swap_program should create the output ATA");
+
+    let account = svm.get_account(&user_mint_acc).unwrap();
+    assert_eq!(account.owner, TOKEN_PROGRAM_ID);
+    assert_eq!(&account.data[32..64], user.pubkey().as_ref());
+    assert_eq!(token_balance(&svm, &user_mint_acc), BASE_AMOUNT_OUT);
+}
+
+/// Moves an account under another program, e.g. a mint to Token-2022.
+fn set_owner(svm: &mut LiteSVM, address: &Pubkey, owner: Pubkey) {
+    let mut account = svm.get_account(address).unwrap();
+    account.owner = owner;
+    svm.set_account(*address, account).unwrap();
+}
+
+#[test]
+fn it_sends_the_token_program_of_each_pump_amm_mint() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+    // TEST becomes a Token-2022 mint, WSOL stays on the Token program
+    for address in [TEST_MINT, user_mint_acc, keys.pool_base_token_acc] {
+        set_owner(&mut svm, &address, TOKEN_2022_PROGRAM_ID);
+    }
+
+    let mut ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.accounts[PumpAmmSwap::MINT_TOKEN_PROGRAM_INDEX] =
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false);
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("mock pump amm swap should have succeeded");
+
+    let received = Received::from_logs(&res);
+    // base_token_program, quote_token_program
+    assert_eq!(received.accounts[11].0, TOKEN_2022_PROGRAM_ID);
+    assert_eq!(received.accounts[12].0, TOKEN_PROGRAM_ID);
+    assert_eq!(token_balance(&svm, &user_mint_acc), BASE_AMOUNT_OUT);
+}
+
+#[test]
+fn it_rejects_a_token_program_not_owning_the_mint() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.accounts[PumpAmmSwap::MINT_TOKEN_PROGRAM_INDEX] =
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false);
+    let res = send_tx(&mut svm, &[ix], &user).expect_err("TEST is owned by the Token program");
+
+    // PumpAmmError::InvalidTokenProgram
+    assert_custom_error(&res.err, 404);
+}
+
+#[test]
+fn it_sends_dlmm_swap_in_idl_order() {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("mock dlmm swap should have succeeded");
+
+    let received = Received::from_logs(&res);
+    let mut data = dlmm_mock::SWAP_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&AMOUNT_IN.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    assert_eq!(received.data, data);
+    let mut expected = vec![
+        AccountMeta::new(keys.lb_pair, false),
+        AccountMeta::new_readonly(DLMM_PROGRAM_ID, false),
+        AccountMeta::new(keys.reserve_x, false),
+        AccountMeta::new(keys.reserve_y, false),
+        AccountMeta::new(base_mint_acc, false),
+        AccountMeta::new(user_mint_acc, false),
+        AccountMeta::new_readonly(TEST_MINT, false),
+        AccountMeta::new_readonly(WSOL_MINT, false),
+        AccountMeta::new(keys.oracle, false),
+        AccountMeta::new_readonly(DLMM_PROGRAM_ID, false),
+        AccountMeta::new(user.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(DLMM_EVENT_AUTHORITY, false),
+        AccountMeta::new_readonly(DLMM_PROGRAM_ID, false),
+    ];
+    expected.extend(
+        keys.bin_arrays
+            .iter()
+            .map(|bin_array| AccountMeta::new(*bin_array, false)),
+    );
+    received.assert_accounts(&expected);
+
+    let (amount_out, _) = dlmm_mock::quote_swap(WSOL_RESERVE, TOKEN_RESERVE, AMOUNT_IN).unwrap();
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL - AMOUNT_IN);
+    assert_eq!(token_balance(&svm, &user_mint_acc), amount_out);
+    assert_eq!(
+        token_balance(&svm, &keys.reserve_y),
+        WSOL_RESERVE + AMOUNT_IN
+    );
+    assert_eq!(
+        token_balance(&svm, &keys.reserve_x),
+        TOKEN_RESERVE - amount_out
+    );
+}
+
+#[test]
+fn it_sends_the_token_program_of_each_dlmm_mint() {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+    // TEST (token X) becomes a Token-2022 mint, WSOL stays on the Token program
+    for address in [TEST_MINT, user_mint_acc, keys.reserve_x] {
+        set_owner(&mut svm, &address, TOKEN_2022_PROGRAM_ID);
+    }
+
+    let mut ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.accounts[DLMMSwap::MINT_TOKEN_PROGRAM_INDEX] =
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false);
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("mock dlmm swap should have succeeded");
+
+    let received = Received::from_logs(&res);
+    // token_x_program, token_y_program
+    assert_eq!(received.accounts[11].0, TOKEN_2022_PROGRAM_ID);
+    assert_eq!(received.accounts[12].0, TOKEN_PROGRAM_ID);
+    let (amount_out, _) = dlmm_mock::quote_swap(WSOL_RESERVE, TOKEN_RESERVE, AMOUNT_IN).unwrap();
+    assert_eq!(token_balance(&svm, &user_mint_acc), amount_out);
+}
+
+#[test]
+fn it_rejects_a_dlmm_token_program_not_owning_the_mint() {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.accounts[DLMMSwap::MINT_TOKEN_PROGRAM_INDEX] =
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false);
+    let res = send_tx(&mut svm, &[ix], &user).expect_err("TEST is owned by the Token program");
+
+    // DlmmError::InvalidTokenProgram
+    assert_custom_error(&res.err, 505);
+}
+
+#[test]
+fn it_sends_dlmm_swap_exact_out_with_the_fixed_max_in() {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let amount_out = 10_000_000;
+    let mut ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        amount_out,
+        ..arbitrage_ix_data(SupportDex::DLMM)
+    });
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("mock dlmm swap_exact_out should have succeeded");
+
+    // same accounts as `swap`, only the discriminator and the arguments change
+    let received = Received::from_logs(&res);
+    let mut data = dlmm_mock::SWAP_EXACT_OUT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&MAX_QUOTE_AMOUNT_IN.to_le_bytes());
+    data.extend_from_slice(&amount_out.to_le_bytes());
+    assert_eq!(received.data, data);
+    assert_eq!(received.accounts.len(), 15 + keys.bin_arrays.len());
+
+    let (amount_in, _) =
+        dlmm_mock::quote_swap_exact_out(WSOL_RESERVE, TOKEN_RESERVE, amount_out).unwrap();
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL - amount_in);
+    assert_eq!(token_balance(&svm, &user_mint_acc), amount_out);
+}
+
+#[test]
+fn it_keeps_the_dlmm_slippage_error_of_swap_exact_out() {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // 1_000_000_000_000 tokens cost about 50_000_000 lamports, above the fixed max in
+    let mut ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        amount_out: 1_000_000_000_000,
+        ..arbitrage_ix_data(SupportDex::DLMM)
+    });
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("the mock should have rejected the max in amount");
+    // mock_utils::SLIPPAGE_EXCEEDED
+    assert_custom_error(&res.err, 1);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
+
+#[test]
+fn it_sends_dlmm_swap_with_price_impact() {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        max_price_impact_bps: 10,
+        active_id: -42,
+        ..arbitrage_ix_data(SupportDex::DLMM)
+    });
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("mock dlmm swap_with_price_impact should have succeeded");
+
+    // amount_in, Some(active_id), max_price_impact_bps
+    let received = Received::from_logs(&res);
+    let mut data = dlmm_mock::SWAP_WITH_PRICE_IMPACT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&AMOUNT_IN.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(&(-42i32).to_le_bytes());
+    data.extend_from_slice(&10u16.to_le_bytes());
+    assert_eq!(received.data, data);
+    assert_eq!(received.accounts.len(), 15 + keys.bin_arrays.len());
+
+    let (amount_out, _) = dlmm_mock::quote_swap(WSOL_RESERVE, TOKEN_RESERVE, AMOUNT_IN).unwrap();
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL - AMOUNT_IN);
+    assert_eq!(token_balance(&svm, &user_mint_acc), amount_out);
+}
+
+#[test]
+fn it_keeps_the_dlmm_price_impact_error() {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // 100 lamports into a reserve of 1_000 move the price by about 9%
+    set_token_account(&mut svm, &keys.lb_pair, &WSOL_MINT, 1_000);
+    let mut ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        max_price_impact_bps: 100,
+        ..arbitrage_ix_data(SupportDex::DLMM)
+    });
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("the mock should have rejected the price impact");
+    // mock_utils::SLIPPAGE_EXCEEDED
+    assert_custom_error(&res.err, 1);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
+
+#[test]
+fn it_reports_the_misplaced_dlmm_account() {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    let mut keys = set_mock_dlmm_pair(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // The oracle is not the one of the pair.
+    keys.oracle = keys.bin_arrays[0];
+
+    let ix = dlmm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    let res = send_tx(&mut svm, &[ix], &user).expect_err("mock should have rejected the swap");
+    assert_custom_error(&res.err, 108);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
@@ -0,0 +1,319 @@
+//! LiteSVM integration suite for swap_program.
+//!
+//! The pool fixtures under `fixtures/` are `AccountsSnapshot`s in the format
+//! surfpool exports. Build the program with `cargo build-sbf` first, then run
+//! `cargo test --features test-default`.
+//!
+//! The snapshots hold pool state only, not the DEX programs. Those come from
+//! the program and programdata accounts `fixtures/embed_programs.py` adds to a
+//! snapshot, or else from `fixtures/programs/<program id>.so`. Neither is
+//! committed, so provide one of them once, with network access, before the first run.
+#[cfg(feature = "bench-default")]
+mod bench;
+mod compute_budget;
+mod dlmm;
+mod events;
+mod flash_loan;
+#[cfg(feature = "idl")]
+mod idl;
+mod instruction_policy;
+mod ix_data;
+mod logging;
+mod mock_cpi;
+mod operator_limits;
+mod oracle;
+mod pnl_ledger;
+mod pump_amm;
+mod quote;
+mod reentrancy;
+mod replay;
+mod round_trip;
+#[cfg(feature = "searcher")]
+mod searcher;
+mod slippage;
+mod wsol;
+
+use std::{format, string::ToString, vec, vec::Vec};
+
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_account::Account;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::{Keypair, Signer};
+use solana_pubkey::Pubkey;
+use surfpool_types::AccountsSnapshot;
+
+use crate::instruction::{ArbitrageIxData, LossProgramInstruction, RoundTripIxData, SupportDex};
+use crate::state::{to_bytes, OperatorLimits, OPERATOR_LIMITS_SEED, PNL_LEDGER_SEED};
+
+pub const SWAP_PROGRAM_PATH: &str = "target/deploy/swap_program.so";
+pub const FIXTURES_DIR: &str = "src/tests/fixtures";
+
+pub const TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ComputeBudget111111111111111111111111111111");
+
+/// Native SOL wrapped mint, `base_mint` of every fixture.
+pub const WSOL_MINT: Pubkey = Pubkey::from_str_const("So11111111111111111111111111111111111111112");
+/// Token traded against WSOL in every fixture.
+pub const TEST_MINT: Pubkey =
+    Pubkey::from_str_const("FtTSDNLD5mMLn3anqEQpy44cRdrtAJRrLX2MKXxfpump");
+
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+pub struct SurfpoolSnapshotLoader;
+
+impl SurfpoolSnapshotLoader {
+    /// Loads every account of the snapshot into `svm`.
+    ///
+    /// Executable accounts are set last: LiteSVM compiles a program as soon as it
+    /// is set, so an upgradeable program needs its programdata account in place.
+    pub fn load(snapshot_path: &str, svm: &mut LiteSVM) {
+        let (programs, accounts): (Vec<_>, Vec<_>) = Self::read(snapshot_path)
+            .into_iter()
+            .partition(|(_, account)| account.executable);
+
+        for (pubkey, account) in accounts.into_iter().chain(programs) {
+            svm.set_account(Pubkey::from_str_const(&pubkey), account.into())
+                .unwrap();
+        }
+    }
+
+    /// Parses the snapshot without loading it anywhere.
+    pub fn read(snapshot_path: &str) -> AccountsSnapshot {
+        let data = std::fs::read_to_string(snapshot_path)
+            .unwrap_or_else(|_| panic!("Failed to read snapshot at {snapshot_path}"));
+
+        serde_json::from_str(&data)
+            .unwrap_or_else(|_| panic!("Failed to deserialize snapshot at {snapshot_path}"))
+    }
+
+    /// Loads `fixtures/programs/<program_id>.so` unless the snapshot already
+    /// provided the program.
+    pub fn load_program(program_id: &Pubkey, svm: &mut LiteSVM) {
+        if svm
+            .get_account(program_id)
+            .is_some_and(|account| account.executable)
+        {
+            return;
+        }
+        let path = format!("{FIXTURES_DIR}/programs/{program_id}.so");
+        svm.add_program_from_file(*program_id, &path)
+            .unwrap_or_else(|_| {
+                panic!(
+                    "{program_id} is neither in the snapshot nor at {path}, \
+                     run `python3 {FIXTURES_DIR}/embed_programs.py`"
+                )
+            });
+    }
+}
+
+/// Creates a LiteSVM instance with swap_program, the given snapshot and a funded
+/// user the admin already set up.
+pub fn setup(fixture: &str, dex_program_id: &Pubkey) -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(crate::ID, SWAP_PROGRAM_PATH)
+        .expect("swap_program.so not found, run `cargo build-sbf` first");
+
+    SurfpoolSnapshotLoader::load(&format!("{FIXTURES_DIR}/{fixture}"), &mut svm);
+    SurfpoolSnapshotLoader::load_program(dex_program_id, &mut svm);
+
+    let user = new_operator(&mut svm);
+    (svm, user)
+}
+
+/// Funded operator whose limits PDA exists, with no limits set.
+pub fn new_operator(svm: &mut LiteSVM) -> Keypair {
+    let operator = Keypair::new();
+    svm.airdrop(&operator.pubkey(), 10_000_000_000).unwrap();
+    let (address, bump) = Pubkey::find_program_address(
+        &[OPERATOR_LIMITS_SEED, operator.pubkey().as_ref()],
+        &Pubkey::new_from_array(crate::ID),
+    );
+    let limits = OperatorLimits::new([0; 32], operator.pubkey().to_bytes(), bump);
+    let data = unsafe { to_bytes(&limits) }.to_vec();
+    svm.set_account(
+        address,
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: Pubkey::new_from_array(crate::ID),
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+    operator
+}
+
+/// Data of one snapshot account, for host tests that need no LiteSVM.
+pub fn fixture_account_data(fixture: &str, address: &Pubkey) -> Vec<u8> {
+    let snapshot = SurfpoolSnapshotLoader::read(&format!("{FIXTURES_DIR}/{fixture}"));
+    let account: Account = snapshot
+        .get(&address.to_string())
+        .unwrap_or_else(|| panic!("{address} is not in {fixture}"))
+        .clone()
+        .into();
+    account.data
+}
+
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Writes an initialized SPL token account holding `amount` at the owner's ATA.
+pub fn set_token_account(svm: &mut LiteSVM, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+    let address = associated_token_address(owner, mint);
+    let rent = svm.minimum_balance_for_rent_exemption(TOKEN_ACCOUNT_LEN);
+
+    let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+    data[0..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    // AccountState::Initialized
+    data[108] = 1;
+    let mut lamports = rent;
+    if *mint == WSOL_MINT {
+        // is_native: Some(rent exempt reserve)
+        data[109..113].copy_from_slice(&1u32.to_le_bytes());
+        data[113..121].copy_from_slice(&rent.to_le_bytes());
+        lamports += amount;
+    }
+
+    svm.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner: TOKEN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+    address
+}
+
+pub fn token_balance(svm: &LiteSVM, address: &Pubkey) -> u64 {
+    let account = svm
+        .get_account(address)
+        .unwrap_or_else(|| panic!("token account {address} not found"));
+    u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+}
+
+/// `ArbitrageIxData` of the fixture swaps, with every optional mode off.
+pub fn arbitrage_ix_data(dex: SupportDex) -> ArbitrageIxData {
+    ArbitrageIxData {
+        dex,
+        max_bin_to_process: 20,
+        min_profit_threshold: 10,
+        no_failure: true,
+        flags: 0,
+        max_price_deviation_bps: 0,
+        wrap_lamports: 0,
+        slippage_bps: 0,
+        amount_out: 0,
+        max_price_impact_bps: 0,
+        active_id: 0,
+        price_feed_id: [0; 32],
+    }
+}
+
+/// Serializes a `DexSwap` instruction the same way the generated client does.
+pub fn dex_swap_data(ix_data: ArbitrageIxData) -> Vec<u8> {
+    let mut data = vec![LossProgramInstruction::DexSwap as u8];
+    data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+    data
+}
+
+/// `RoundTripIxData` spending `amount_in` WSOL, with no profit required.
+pub fn round_trip_ix_data(
+    buy_dex: SupportDex,
+    sell_dex: SupportDex,
+    amount_in: u64,
+) -> RoundTripIxData {
+    RoundTripIxData {
+        buy_dex,
+        sell_dex,
+        amount_in,
+        min_profit_threshold: 0,
+        max_bin_to_process: 20,
+        flags: 0,
+        flash_loan_amount: 0,
+        no_failure: false,
+    }
+}
+
+/// Serializes a `RoundTrip` instruction the same way the generated client does.
+pub fn round_trip_data(ix_data: RoundTripIxData) -> Vec<u8> {
+    let mut data = vec![LossProgramInstruction::RoundTrip as u8];
+    data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+    data
+}
+
+/// Accounts shared by every `DexSwap` layout, in instruction order.
+pub fn common_accounts(
+    user: &Pubkey,
+    base_mint_acc: &Pubkey,
+    user_mint_acc: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(WSOL_MINT, false),
+        AccountMeta::new(*user, false),
+        AccountMeta::new(*base_mint_acc, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TEST_MINT, false),
+        AccountMeta::new(*user_mint_acc, false),
+    ]
+}
+
+/// Limits PDA of `operator`, taken by every `DexSwap` right after the leg accounts.
+pub fn operator_limits_address(operator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[OPERATOR_LIMITS_SEED, operator.as_ref()],
+        &Pubkey::new_from_array(crate::ID),
+    )
+    .0
+}
+
+/// Ledger PDA of `operator`, taken by every `DexSwap` after its limits PDA.
+pub fn pnl_ledger_address(operator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[PNL_LEDGER_SEED, operator.as_ref()],
+        &Pubkey::new_from_array(crate::ID),
+    )
+    .0
+}
+
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![2u8];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+#[allow(clippy::result_large_err)]
+pub fn send_tx(svm: &mut LiteSVM, ixs: &[Instruction], user: &Keypair) -> TransactionResult {
+    let tx = solana_transaction::Transaction::new_signed_with_payer(
+        ixs,
+        Some(&user.pubkey()),
+        &[user],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx)
+}
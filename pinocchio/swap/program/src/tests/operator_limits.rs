@@ -0,0 +1,352 @@
+use solana_keypair::Signer;
+
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::*;
+use crate::error::LossProgramError;
+use crate::instruction::OperatorLimitsIxData;
+use crate::state::{DataLen, OperatorLimits};
+
+const SLOT: u64 = 1_000;
+const EPOCH: u64 = 3;
+
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+    solana_pubkey::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+fn limits(config: OperatorLimitsIxData) -> OperatorLimits {
+    let mut limits = OperatorLimits::new([1; 32], [2; 32], 255);
+    limits.configure(&config);
+    limits
+}
+
+pub fn set_operator_limits_ix(
+    admin: &Pubkey,
+    operator: &Pubkey,
+    operator_signs: bool,
+    config: OperatorLimitsIxData,
+) -> Instruction {
+    let mut data = vec![LossProgramInstruction::SetOperatorLimits as u8];
+    data.extend_from_slice(unsafe { to_bytes(&config) });
+    Instruction {
+        program_id: Pubkey::new_from_array(crate::ID),
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(*operator, operator_signs),
+            AccountMeta::new(operator_limits_address(operator), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(program_data_address(), false),
+        ],
+        data,
+    }
+}
+
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[&crate::ID], &BPF_LOADER_UPGRADEABLE_ID).0
+}
+
+/// Writes a programdata account naming `admin` as the upgrade authority, the
+/// test loader deploys swap_program without one.
+pub fn set_upgrade_authority(svm: &mut LiteSVM, admin: &Pubkey) {
+    let mut data = vec![3, 0, 0, 0];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(admin.as_ref());
+    svm.set_account(
+        program_data_address(),
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+/// Funded operator without a limits PDA.
+fn new_bare_operator(svm: &mut LiteSVM) -> Keypair {
+    let operator = Keypair::new();
+    svm.airdrop(&operator.pubkey(), 10_000_000_000).unwrap();
+    operator
+}
+
+/// Funded admin that is the upgrade authority of swap_program.
+fn new_admin(svm: &mut LiteSVM) -> Keypair {
+    let admin = Keypair::new();
+    svm.airdrop(&admin.pubkey(), 1_000_000_000).unwrap();
+    set_upgrade_authority(svm, &admin.pubkey());
+    admin
+}
+
+#[allow(clippy::result_large_err)]
+pub fn send_signed(
+    svm: &mut LiteSVM,
+    ixs: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> TransactionResult {
+    let tx = solana_transaction::Transaction::new_signed_with_payer(
+        ixs,
+        Some(&payer.pubkey()),
+        signers,
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx)
+}
+
+fn read_limits(svm: &LiteSVM, operator: &Pubkey) -> OperatorLimits {
+    let account = svm
+        .get_account(&operator_limits_address(operator))
+        .expect("operator limits not created");
+    assert_eq!(account.owner, Pubkey::new_from_array(crate::ID));
+    assert_eq!(account.data.len(), OperatorLimits::LEN);
+    unsafe { (account.data.as_ptr() as *const OperatorLimits).read_unaligned() }
+}
+
+/// Mock Pump AMM pool and a user whose limits were set by the upgrade authority.
+pub fn limited_pump_amm_swap(
+    config: OperatorLimitsIxData,
+) -> (LiteSVM, Keypair, Keypair, Instruction, Pubkey) {
+    let (mut svm, _) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let user = new_bare_operator(&mut svm);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let admin = new_admin(&mut svm);
+    let ix = set_operator_limits_ix(&admin.pubkey(), &user.pubkey(), true, config);
+    send_signed(&mut svm, &[ix], &admin, &[&admin, &user])
+        .expect("admin and operator should create the limits");
+
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    (svm, user, admin, ix, base_mint_acc)
+}
+
+#[test]
+fn it_caps_the_notional_of_a_trade_and_of_a_window() {
+    let mut limits = limits(OperatorLimitsIxData {
+        max_notional_per_trade: 100,
+        max_window_notional: 250,
+        window_slots: 10,
+        ..Default::default()
+    });
+
+    assert_eq!(
+        limits.record_trade(101, SLOT, EPOCH),
+        Err(LossProgramError::AmountTooLarge)
+    );
+    assert_eq!(limits.record_trade(100, SLOT, EPOCH), Ok(()));
+    assert_eq!(limits.record_trade(100, SLOT + 1, EPOCH), Ok(()));
+    assert_eq!(
+        limits.record_trade(51, SLOT + 9, EPOCH),
+        Err(LossProgramError::AmountTooLarge)
+    );
+    assert_eq!(limits.window_notional, 200);
+    // The next window of 10 slots starts from 0
+    assert_eq!(limits.record_trade(100, SLOT + 10, EPOCH), Ok(()));
+    assert_eq!(limits.window_notional, 100);
+}
+
+#[test]
+fn it_caps_trades_per_slot_and_notional_per_epoch() {
+    let mut limits = limits(OperatorLimitsIxData {
+        max_window_notional: 1_000,
+        max_trades_per_slot: 2,
+        ..Default::default()
+    });
+
+    assert_eq!(limits.record_trade(400, SLOT, EPOCH), Ok(()));
+    assert_eq!(limits.record_trade(400, SLOT, EPOCH), Ok(()));
+    assert_eq!(
+        limits.record_trade(1, SLOT, EPOCH),
+        Err(LossProgramError::AmountTooLarge)
+    );
+    assert_eq!(
+        limits.record_trade(400, SLOT + 1, EPOCH),
+        Err(LossProgramError::AmountTooLarge)
+    );
+    assert_eq!(limits.record_trade(400, SLOT + 1, EPOCH + 1), Ok(()));
+    assert_eq!(limits.trades_in_slot, 1);
+}
+
+#[test]
+fn it_leaves_an_unlimited_operator_alone() {
+    let mut limits = limits(OperatorLimitsIxData::default());
+    for _ in 0..3 {
+        assert_eq!(limits.record_trade(u64::MAX / 4, SLOT, EPOCH), Ok(()));
+    }
+    assert_eq!(
+        limits.record_trade(u64::MAX, SLOT, EPOCH),
+        Err(LossProgramError::ArithmeticError)
+    );
+}
+
+#[test]
+fn it_counts_trades_in_a_slot_without_wrapping() {
+    let mut limits = limits(OperatorLimitsIxData::default());
+    limits.last_trade_slot = SLOT;
+    limits.trades_in_slot = u64::MAX;
+    assert_eq!(
+        limits.record_trade(1, SLOT, EPOCH),
+        Err(LossProgramError::ArithmeticError)
+    );
+}
+
+#[test]
+fn it_rejects_a_trade_above_the_operator_limit() {
+    let (mut svm, user, _, ix, base_mint_acc) = limited_pump_amm_swap(OperatorLimitsIxData {
+        max_notional_per_trade: 1,
+        ..Default::default()
+    });
+
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("the buy spends more than 1 lamport of WSOL");
+    assert_custom_error(&res.err, 9); // LossProgramError::AmountTooLarge
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
+
+#[test]
+fn it_counts_the_trades_of_the_slot() {
+    let (mut svm, user, _, ix, base_mint_acc) = limited_pump_amm_swap(OperatorLimitsIxData {
+        max_trades_per_slot: 1,
+        ..Default::default()
+    });
+
+    let res = send_tx(
+        &mut svm,
+        &[set_compute_unit_limit(800_000), ix.clone(), ix.clone()],
+        &user,
+    )
+    .expect_err("the second trade of the slot is over the limit");
+    assert_custom_error(&res.err, 9); // LossProgramError::AmountTooLarge
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("a single trade fits the limit");
+    let limits = read_limits(&svm, &user.pubkey());
+    let (last_trade_slot, trades_in_slot) = (limits.last_trade_slot, limits.trades_in_slot);
+    assert_eq!(
+        last_trade_slot,
+        svm.get_sysvar::<solana_clock::Clock>().slot
+    );
+    assert_eq!(trades_in_slot, 1);
+    assert!(limits.window_notional > 0);
+}
+
+#[test]
+fn it_lets_only_the_admin_change_the_limits() {
+    let config = OperatorLimitsIxData {
+        max_notional_per_trade: 1,
+        ..Default::default()
+    };
+    let (mut svm, user, admin, _, _) = limited_pump_amm_swap(config);
+
+    // The operator cannot loosen its own limits
+    let ix = set_operator_limits_ix(
+        &user.pubkey(),
+        &user.pubkey(),
+        true,
+        OperatorLimitsIxData::default(),
+    );
+    let res = send_tx(&mut svm, &[ix], &user).expect_err("only the admin updates the limits");
+    assert_custom_error(&res.err, LossProgramError::AuthorizationError as u32);
+
+    // The admin updates them without the operator
+    let raised = OperatorLimitsIxData {
+        max_notional_per_trade: 1_000,
+        ..config
+    };
+    let ix = set_operator_limits_ix(&admin.pubkey(), &user.pubkey(), false, raised);
+    send_signed(&mut svm, &[ix], &admin, &[&admin]).expect("the admin updates the limits");
+    let limits = read_limits(&svm, &user.pubkey());
+    let (stored_admin, max_notional_per_trade) = (limits.admin, limits.max_notional_per_trade);
+    assert_eq!(stored_admin, admin.pubkey().to_bytes());
+    assert_eq!(max_notional_per_trade, 1_000);
+}
+
+#[test]
+fn it_creates_the_limits_only_with_the_operator_signature() {
+    let (mut svm, _) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let user = new_bare_operator(&mut svm);
+    let admin = new_admin(&mut svm);
+
+    let ix = set_operator_limits_ix(
+        &admin.pubkey(),
+        &user.pubkey(),
+        false,
+        OperatorLimitsIxData::default(),
+    );
+    send_signed(&mut svm, &[ix], &admin, &[&admin])
+        .expect_err("creating the limits takes the operator signature");
+    assert!(svm
+        .get_account(&operator_limits_address(&user.pubkey()))
+        .is_none());
+}
+
+#[test]
+fn it_creates_the_limits_only_with_the_upgrade_authority() {
+    let (mut svm, _) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let user = new_bare_operator(&mut svm);
+    new_admin(&mut svm);
+
+    let other = Keypair::new();
+    svm.airdrop(&other.pubkey(), 1_000_000_000).unwrap();
+    let ix = set_operator_limits_ix(
+        &other.pubkey(),
+        &user.pubkey(),
+        true,
+        OperatorLimitsIxData::default(),
+    );
+    let res = send_signed(&mut svm, &[ix], &other, &[&other, &user])
+        .expect_err("only the upgrade authority creates the limits");
+    assert_custom_error(&res.err, LossProgramError::AuthorizationError as u32);
+    assert!(svm
+        .get_account(&operator_limits_address(&user.pubkey()))
+        .is_none());
+}
+
+#[test]
+fn it_creates_the_limits_on_a_prefunded_address() {
+    let (mut svm, _) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let user = new_bare_operator(&mut svm);
+    let admin = new_admin(&mut svm);
+    // Anyone can send lamports to the PDA before it exists
+    svm.airdrop(&operator_limits_address(&user.pubkey()), 1)
+        .unwrap();
+
+    let ix = set_operator_limits_ix(
+        &admin.pubkey(),
+        &user.pubkey(),
+        true,
+        OperatorLimitsIxData::default(),
+    );
+    send_signed(&mut svm, &[ix], &admin, &[&admin, &user])
+        .expect("a pre-funded limits PDA should still be created");
+    let account = svm
+        .get_account(&operator_limits_address(&user.pubkey()))
+        .unwrap();
+    assert_eq!(
+        account.lamports,
+        svm.minimum_balance_for_rent_exemption(OperatorLimits::LEN)
+    );
+    assert_eq!(
+        read_limits(&svm, &user.pubkey()).admin,
+        admin.pubkey().to_bytes()
+    );
+}
+
+#[test]
+fn it_rejects_a_swap_without_operator_limits() {
+    let (mut svm, _) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let user = new_bare_operator(&mut svm);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("an operator without limits cannot trade");
+    assert_custom_error(&res.err, LossProgramError::AuthorizationError as u32);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
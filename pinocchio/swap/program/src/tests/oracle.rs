@@ -0,0 +1,189 @@
+use solana_clock::Clock;
+
+use crate::error::LossProgramError;
+use crate::oracle::{OraclePrice, MAXIMUM_AGE, PRICE_UPDATE_V2_DISCRIMINATOR};
+
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::*;
+
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// `PriceUpdateV2::LEN`, sized for a partially verified update
+const PRICE_UPDATE_V2_LEN: usize = 134;
+const NOW: i64 = 1_750_000_000;
+/// Feed of the price updates the tests post
+const FEED_ID: [u8; 32] = [0xef; 32];
+
+/// The mock pool sells 10_000_000 TEST for 505 WSOL including fees, both mints
+/// having 9 decimals.
+const POOL_PRICE: i64 = 5_000;
+const POOL_PRICE_EXPONENT: i32 = -8;
+
+/// Borsh encoding of a `PriceUpdateV2`, `None` verification means `Full`.
+fn price_update_data(
+    price: i64,
+    exponent: i32,
+    publish_time: i64,
+    partial_signatures: Option<u8>,
+) -> Vec<u8> {
+    let mut data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+    // write_authority
+    data.extend_from_slice(&[0u8; 32]);
+    match partial_signatures {
+        Some(num_signatures) => data.extend_from_slice(&[0, num_signatures]),
+        None => data.push(1),
+    }
+    data.extend_from_slice(&FEED_ID);
+    data.extend_from_slice(&price.to_le_bytes());
+    // conf
+    data.extend_from_slice(&1u64.to_le_bytes());
+    data.extend_from_slice(&exponent.to_le_bytes());
+    data.extend_from_slice(&publish_time.to_le_bytes());
+    // prev_publish_time, ema_price, ema_conf, posted_slot
+    data.extend_from_slice(&(publish_time - 1).to_le_bytes());
+    data.extend_from_slice(&price.to_le_bytes());
+    data.extend_from_slice(&1u64.to_le_bytes());
+    data.extend_from_slice(&1u64.to_le_bytes());
+    data.resize(PRICE_UPDATE_V2_LEN, 0);
+    data
+}
+
+fn set_price_update(svm: &mut LiteSVM, price: i64, exponent: i32, publish_time: i64) -> Pubkey {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = NOW;
+    svm.set_sysvar(&clock);
+
+    let address = Pubkey::new_unique();
+    let data = price_update_data(price, exponent, publish_time, None);
+    set_account(svm, address, PYTH_RECEIVER_PROGRAM_ID, data);
+    address
+}
+
+fn oracle_guarded_ix(
+    svm: &mut LiteSVM,
+    user: &Keypair,
+    price_update: Pubkey,
+    max_price_deviation_bps: u16,
+) -> (Instruction, Pubkey) {
+    let keys = set_mock_pump_pool(svm);
+    let base_mint_acc = set_token_account(svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        max_price_deviation_bps,
+        price_feed_id: FEED_ID,
+        ..arbitrage_ix_data(SupportDex::PumpAmm)
+    });
+    ix.accounts
+        .push(AccountMeta::new_readonly(price_update, false));
+    (ix, user_mint_acc)
+}
+
+#[test]
+fn it_parses_only_fully_verified_price_updates() {
+    let full = price_update_data(POOL_PRICE, POOL_PRICE_EXPONENT, NOW, None);
+    assert_eq!(
+        OraclePrice::from_bytes(&full, &FEED_ID),
+        Some(OraclePrice {
+            price: POOL_PRICE,
+            exponent: POOL_PRICE_EXPONENT,
+            publish_time: NOW,
+        })
+    );
+
+    let partial = price_update_data(POOL_PRICE, POOL_PRICE_EXPONENT, NOW, Some(3));
+    assert_eq!(OraclePrice::from_bytes(&partial, &FEED_ID), None);
+    let negative = price_update_data(-POOL_PRICE, POOL_PRICE_EXPONENT, NOW, None);
+    assert_eq!(OraclePrice::from_bytes(&negative, &FEED_ID), None);
+    assert_eq!(OraclePrice::from_bytes(&full, &[0x01; 32]), None);
+}
+
+#[test]
+fn it_measures_the_execution_price_deviation() {
+    let oracle_price = OraclePrice {
+        price: POOL_PRICE,
+        exponent: POOL_PRICE_EXPONENT,
+        publish_time: NOW,
+    };
+    // 505 / 10_000_000 is 1% above the oracle price
+    assert!(oracle_price
+        .check_execution(505, 10_000_000, 9, 9, 100)
+        .is_ok());
+    assert_eq!(
+        oracle_price.check_execution(505, 10_000_000, 9, 9, 99),
+        Err(LossProgramError::PriceDeviationExceeded.into())
+    );
+    assert_eq!(
+        oracle_price.check_execution(495, 10_000_000, 9, 9, 99),
+        Err(LossProgramError::PriceDeviationExceeded.into())
+    );
+
+    // 1 USDC (6 decimals) for 1 token (9 decimals) at a price of 1.00000000
+    let parity = OraclePrice {
+        price: 100_000_000,
+        exponent: -8,
+        publish_time: NOW,
+    };
+    assert!(parity
+        .check_execution(1_000_000, 1_000_000_000, 6, 9, 1)
+        .is_ok());
+    // A leg that returned nothing never matches the oracle price
+    assert!(parity.check_execution(1_000_000, 0, 6, 9, 10_000).is_err());
+}
+
+#[test]
+fn it_executes_within_the_oracle_deviation() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let price_update = set_price_update(&mut svm, POOL_PRICE, POOL_PRICE_EXPONENT, NOW);
+    let (ix, user_mint_acc) = oracle_guarded_ix(&mut svm, &user, price_update, 200);
+
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user).unwrap();
+
+    assert_eq!(token_balance(&svm, &user_mint_acc), BASE_AMOUNT_OUT);
+}
+
+#[test]
+fn it_rejects_an_execution_price_away_from_the_oracle() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    // The pool trades 25% above this oracle price
+    let price_update = set_price_update(&mut svm, 4_000, POOL_PRICE_EXPONENT, NOW);
+    let (ix, _) = oracle_guarded_ix(&mut svm, &user, price_update, 200);
+
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("the pool price is manipulated");
+
+    assert_custom_error(&res.err, LossProgramError::PriceDeviationExceeded as u32);
+}
+
+#[test]
+fn it_rejects_a_stale_oracle_price() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let publish_time = NOW - MAXIMUM_AGE - 1;
+    let price_update = set_price_update(&mut svm, POOL_PRICE, POOL_PRICE_EXPONENT, publish_time);
+    let (ix, _) = oracle_guarded_ix(&mut svm, &user, price_update, 200);
+
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("the price is older than MAXIMUM_AGE");
+
+    assert_custom_error(&res.err, LossProgramError::StaleOraclePrice as u32);
+}
+
+#[test]
+fn it_rejects_a_price_update_of_another_feed() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let price_update = set_price_update(&mut svm, POOL_PRICE, POOL_PRICE_EXPONENT, NOW);
+    let (mut ix, user_mint_acc) = oracle_guarded_ix(&mut svm, &user, price_update, 200);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        max_price_deviation_bps: 200,
+        price_feed_id: [0x01; 32],
+        ..arbitrage_ix_data(SupportDex::PumpAmm)
+    });
+
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("the update prices another feed");
+
+    assert_custom_error(&res.err, LossProgramError::InvalidOracleAccount as u32);
+    assert_eq!(token_balance(&svm, &user_mint_acc), 0);
+}
@@ -0,0 +1,150 @@
+use solana_keypair::Signer;
+
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::*;
+use crate::dex::PumpAmmSwap;
+use crate::state::{
+    balance_change, meets_min_profit, DataLen, PnlEntry, PnlLedger, PNL_LEDGER_CAPACITY,
+};
+
+fn read_ledger(svm: &LiteSVM, operator: &Pubkey) -> PnlLedger {
+    let account = svm
+        .get_account(&pnl_ledger_address(operator))
+        .expect("pnl ledger not created");
+    assert_eq!(account.owner, Pubkey::new_from_array(crate::ID));
+    assert_eq!(account.data.len(), PnlLedger::LEN);
+    unsafe { (account.data.as_ptr() as *const PnlLedger).read_unaligned() }
+}
+
+#[test]
+fn it_keeps_the_latest_entries_and_the_running_totals() {
+    let mut ledger = PnlLedger::new([1; 32], 255);
+    assert_eq!(ledger.recent().count(), 0);
+
+    let routes = PNL_LEDGER_CAPACITY as u64 + 3;
+    for slot in 0..routes {
+        ledger.record(PnlEntry::new(
+            SupportDex::DLMM as u8,
+            1,
+            100,
+            90 + slot % 20,
+            slot as i64 % 20 - 10,
+            slot,
+        ));
+    }
+
+    assert_eq!(ledger.count, routes);
+    assert_eq!(ledger.last_slot, routes - 1);
+    assert_eq!(ledger.total_amount_in, 100 * routes);
+    let total_profit: i64 = (0..routes).map(|slot| slot as i64 % 20 - 10).sum();
+    assert_eq!(ledger.total_profit, total_profit);
+    assert_eq!(
+        ledger.total_amount_out as i64 - ledger.total_amount_in as i64,
+        total_profit
+    );
+
+    // The 3 oldest routes were overwritten
+    let slots: Vec<u64> = ledger.recent().map(|entry| entry.slot).collect();
+    assert_eq!(slots, (3..routes).collect::<Vec<_>>());
+}
+
+#[test]
+fn it_compares_profits_beyond_the_i64_range() {
+    assert_eq!(balance_change(10, 25), 15);
+    assert_eq!(balance_change(25, 10), -15);
+    assert_eq!(balance_change(0, u64::MAX), i64::MAX);
+    assert_eq!(balance_change(u64::MAX, 0), i64::MIN);
+
+    assert!(meets_min_profit(100, 110, 10));
+    assert!(!meets_min_profit(100, 109, 10));
+    // A threshold of 2^63 or more used to wrap to a negative i64
+    assert!(!meets_min_profit(100, 200, 1 << 63));
+    assert!(!meets_min_profit(100, u64::MAX, u64::MAX));
+    assert!(meets_min_profit(0, u64::MAX, u64::MAX));
+}
+
+#[test]
+fn it_creates_the_ledger_on_the_first_route_and_appends_after() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+
+    send_tx(
+        &mut svm,
+        &[set_compute_unit_limit(400_000), ix.clone()],
+        &user,
+    )
+    .expect("the first route should create the ledger");
+    let spent = USER_WSOL - token_balance(&svm, &base_mint_acc);
+    svm.warp_to_slot(svm.get_sysvar::<solana_clock::Clock>().slot + 1);
+    svm.expire_blockhash();
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("the second route should append to the ledger");
+
+    let ledger = read_ledger(&svm, &user.pubkey());
+    let (operator, count, total_amount_in, total_amount_out, total_profit) = (
+        ledger.operator,
+        ledger.count,
+        ledger.total_amount_in,
+        ledger.total_amount_out,
+        ledger.total_profit,
+    );
+    assert_eq!(operator, user.pubkey().to_bytes());
+    assert_eq!(count, 2);
+    assert_eq!(
+        total_amount_in,
+        USER_WSOL - token_balance(&svm, &base_mint_acc)
+    );
+    assert_eq!(total_amount_out, 0);
+    assert_eq!(total_profit, -(total_amount_in as i64));
+
+    let entries: Vec<PnlEntry> = ledger.recent().copied().collect();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].dex, SupportDex::PumpAmm as u8);
+    assert_eq!(entries[0].legs, 1);
+    assert_eq!(entries[0].amount_in, spent);
+    assert_eq!(entries[1].slot, entries[0].slot + 1);
+}
+
+#[test]
+fn it_rejects_the_ledger_of_another_operator() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    let ledger_index = PumpAmmSwap::ACCOUNTS_LEN + 1;
+    ix.accounts[ledger_index].pubkey = pnl_ledger_address(&Pubkey::new_unique());
+
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("the ledger must be the signer's");
+    assert_custom_error(&res.err, 0); // LossProgramError::PdaMismatch
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
+
+#[test]
+fn it_creates_the_ledger_on_a_prefunded_address() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+    // Anyone can send lamports to the PDA before the first route
+    svm.airdrop(&pnl_ledger_address(&user.pubkey()), 1).unwrap();
+
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("a pre-funded ledger should still be created");
+    let account = svm
+        .get_account(&pnl_ledger_address(&user.pubkey()))
+        .unwrap();
+    assert_eq!(
+        account.lamports,
+        svm.minimum_balance_for_rent_exemption(PnlLedger::LEN)
+    );
+    let count = read_ledger(&svm, &user.pubkey()).count;
+    assert_eq!(count, 1);
+}
@@ -0,0 +1,136 @@
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Signer;
+use solana_pubkey::Pubkey;
+
+use super::*;
+
+pub const PUMP_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA");
+pub const PUMP_GLOBAL_CONFIG: Pubkey =
+    Pubkey::from_str_const("ADyA8hdefvWN2dbGGWFotbzWxrAvLW83WG6QCVXvJKqw");
+pub const PUMP_EVENT_AUTHORITY: Pubkey =
+    Pubkey::from_str_const("GS4CU59F31iL7aR2Q8zVS8DRrcRnXX1yjQ66TqNVQnaR");
+pub const PUMP_FEE_WALLET: Pubkey =
+    Pubkey::from_str_const("JCRGumoE9Qi5BBgULTgdgTLjSgkCMSbF62ZZfGs84JeU");
+pub const PUMP_FEE_WALLET_TOKEN_ACC: Pubkey =
+    Pubkey::from_str_const("DWpvfqzGWuVy9jVSKSShdM2733nrEsnnhsUStYbkj6Nn");
+
+/// Pool level accounts of a Pump AMM `buy`.
+pub struct PumpAmmPoolKeys {
+    pub pool: Pubkey,
+    pub pool_base_token_acc: Pubkey,
+    pub pool_quote_token_acc: Pubkey,
+    pub protocol_fee_recipient: Pubkey,
+    pub protocol_fee_recipient_token_acc: Pubkey,
+    pub coin_creator_vault_ata: Pubkey,
+    pub coin_creator_vault_authority: Pubkey,
+}
+
+/// Accounts of `fixtures/pump_amm_pool.json`.
+pub const FIXTURE_POOL: PumpAmmPoolKeys = PumpAmmPoolKeys {
+    pool: Pubkey::from_str_const("EeP6p43EddpyX58erBbbGZ36b7X8V89wfydX2Phow59h"),
+    pool_base_token_acc: Pubkey::from_str_const("B3CscBfyojcsCg6wwHQFHsDmyA4WmdaSW9aXhStWj35b"),
+    pool_quote_token_acc: Pubkey::from_str_const("GTBWE9vzvPxf3d9sfXoCM5Zrzs5hQNsoZEREypSiuCoo"),
+    protocol_fee_recipient: PUMP_FEE_WALLET,
+    protocol_fee_recipient_token_acc: PUMP_FEE_WALLET_TOKEN_ACC,
+    coin_creator_vault_ata: Pubkey::from_str_const("8HqjdkVr4JQMv89E4yFWoRnfMn33P9NKT8v6XHFa3uQd"),
+    coin_creator_vault_authority: Pubkey::from_str_const(
+        "7BeN7qJWUyh4RnN29xK3oVwMpe2m7hrY5bZF3PMbQmNG",
+    ),
+};
+
+/// Base amount bought by the program on every Pump AMM swap.
+pub const BASE_AMOUNT_OUT: u64 = 10_000_000;
+/// Quote amount the program allows the buy to spend.
+pub const MAX_QUOTE_AMOUNT_IN: u64 = 1_000_000;
+
+pub fn pump_amm_swap_ix(
+    keys: &PumpAmmPoolKeys,
+    user: &Pubkey,
+    base_mint_acc: &Pubkey,
+    user_mint_acc: &Pubkey,
+) -> Instruction {
+    let mut accounts = common_accounts(user, base_mint_acc, user_mint_acc);
+    accounts.extend([
+        AccountMeta::new_readonly(PUMP_PROGRAM_ID, false),
+        AccountMeta::new_readonly(PUMP_GLOBAL_CONFIG, false),
+        AccountMeta::new_readonly(PUMP_EVENT_AUTHORITY, false),
+        AccountMeta::new_readonly(keys.protocol_fee_recipient, false),
+        AccountMeta::new_readonly(keys.pool, false),
+        AccountMeta::new(keys.pool_base_token_acc, false),
+        AccountMeta::new(keys.pool_quote_token_acc, false),
+        AccountMeta::new(keys.protocol_fee_recipient_token_acc, false),
+        AccountMeta::new(keys.coin_creator_vault_ata, false),
+        AccountMeta::new_readonly(keys.coin_creator_vault_authority, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    ]);
+    accounts.extend([
+        AccountMeta::new(operator_limits_address(user), false),
+        AccountMeta::new(pnl_ledger_address(user), false),
+    ]);
+    Instruction {
+        program_id: Pubkey::new_from_array(crate::ID),
+        accounts,
+        data: dex_swap_data(arbitrage_ix_data(SupportDex::PumpAmm)),
+    }
+}
+
+#[test]
+fn it_buys_base_on_pump_amm() {
+    let (mut svm, user) = setup("pump_amm_pool.json", &PUMP_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let pool_base_before = token_balance(&svm, &FIXTURE_POOL.pool_base_token_acc);
+    let pool_quote_before = token_balance(&svm, &FIXTURE_POOL.pool_quote_token_acc);
+    let protocol_fee_before = token_balance(&svm, &FIXTURE_POOL.protocol_fee_recipient_token_acc);
+    let creator_fee_before = token_balance(&svm, &FIXTURE_POOL.coin_creator_vault_ata);
+
+    let ix = pump_amm_swap_ix(
+        &FIXTURE_POOL,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("pump amm swap should have succeeded");
+
+    // The buy is exact-out on the base side.
+    assert_eq!(token_balance(&svm, &user_mint_acc), BASE_AMOUNT_OUT);
+    assert_eq!(
+        pool_base_before - token_balance(&svm, &FIXTURE_POOL.pool_base_token_acc),
+        BASE_AMOUNT_OUT
+    );
+
+    // Everything the user spent ends up in the pool, the protocol or the creator vault.
+    let spent = 1_000_000_000 - token_balance(&svm, &base_mint_acc);
+    assert!(spent > 0 && spent <= MAX_QUOTE_AMOUNT_IN);
+    let received = (token_balance(&svm, &FIXTURE_POOL.pool_quote_token_acc) - pool_quote_before)
+        + (token_balance(&svm, &FIXTURE_POOL.protocol_fee_recipient_token_acc)
+            - protocol_fee_before)
+        + (token_balance(&svm, &FIXTURE_POOL.coin_creator_vault_ata) - creator_fee_before);
+    assert_eq!(received, spent);
+}
+
+#[test]
+fn it_rejects_pump_amm_swap_without_signer() {
+    let (mut svm, user) = setup("pump_amm_pool.json", &PUMP_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // Another wallet pays, the owner of the token accounts does not sign.
+    let payer = solana_keypair::Keypair::new();
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+    let mut ix = pump_amm_swap_ix(
+        &FIXTURE_POOL,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.accounts[0].is_signer = false;
+
+    let res = send_tx(&mut svm, &[ix], &payer);
+    assert!(res.is_err(), "unsigned swap should have failed");
+    assert_eq!(token_balance(&svm, &base_mint_acc), 1_000_000_000);
+    assert_eq!(token_balance(&svm, &user_mint_acc), 0);
+}
@@ -0,0 +1,433 @@
+use std::cell::Cell;
+
+use solana_instruction::Instruction;
+use solana_keypair::{Keypair, Signer};
+use solana_pubkey::Pubkey;
+
+use super::dlmm::{dlmm_swap_ix, AMOUNT_IN, DLMM_PROGRAM_ID, FIXTURE_PAIR};
+use super::mock_cpi::assert_custom_error;
+use super::pump_amm::{pump_amm_swap_ix, FIXTURE_POOL, PUMP_GLOBAL_CONFIG, PUMP_PROGRAM_ID};
+use super::*;
+use crate::compute::QUOTE_BIN_UNITS;
+use crate::dex::{
+    quote_buy, quote_buy_exact_out, quote_buy_within, quote_exact_in, quote_exact_out, quote_sell,
+    BinArray, LbPair, PumpAmmFees, PumpAmmPool, SwapQuote,
+};
+use crate::instruction::QuoteIxData;
+
+/// `Clock::unix_timestamp` of the host quotes, long after the fixture pair last traded.
+const NOW: i64 = 1_750_000_000;
+
+fn quote_data(dex: SupportDex, amount_in: u64) -> Vec<u8> {
+    let mut data = vec![LossProgramInstruction::Quote as u8];
+    data.extend_from_slice(unsafe { to_bytes(&QuoteIxData { dex, amount_in }) });
+    data
+}
+
+/// Swap program and snapshot only, a quote never calls the DEX program.
+fn setup_without_dex(fixture: &str) -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(crate::ID, SWAP_PROGRAM_PATH)
+        .expect("swap_program.so not found, run `cargo build-sbf` first");
+    SurfpoolSnapshotLoader::load(&format!("{FIXTURES_DIR}/{fixture}"), &mut svm);
+
+    let user = Keypair::new();
+    svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+    (svm, user)
+}
+
+fn simulate_quote(svm: &LiteSVM, ix: Instruction, user: &Keypair) -> SwapQuote {
+    let tx = solana_transaction::Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&user.pubkey()),
+        &[user],
+        svm.latest_blockhash(),
+    );
+    let res = svm
+        .simulate_transaction(tx)
+        .expect("quote should have succeeded");
+    assert_eq!(
+        res.meta.return_data.program_id,
+        Pubkey::new_from_array(crate::ID)
+    );
+    SwapQuote::from_bytes(&res.meta.return_data.data).expect("return data should be a SwapQuote")
+}
+
+fn fixture_pair() -> (LbPair, Vec<Vec<u8>>) {
+    let lb_pair = LbPair::from_bytes(&fixture_account_data(
+        "dlmm_pair.json",
+        &FIXTURE_PAIR.lb_pair,
+    ))
+    .unwrap();
+    let bin_arrays = FIXTURE_PAIR
+        .bin_arrays
+        .iter()
+        .map(|bin_array| fixture_account_data("dlmm_pair.json", bin_array))
+        .collect();
+    (lb_pair, bin_arrays)
+}
+
+#[test]
+fn it_quotes_pump_amm_buys_on_the_fixture_pool() {
+    let pool = PumpAmmPool::from_bytes(&fixture_account_data(
+        "pump_amm_pool.json",
+        &FIXTURE_POOL.pool,
+    ))
+    .unwrap();
+    assert_eq!(pool.base_mint, TEST_MINT.to_bytes());
+    assert_eq!(pool.quote_mint, WSOL_MINT.to_bytes());
+    let fees = PumpAmmFees::from_bytes(&fixture_account_data(
+        "pump_amm_pool.json",
+        &PUMP_GLOBAL_CONFIG,
+    ))
+    .unwrap();
+    // lp 20 + protocol 5 + coin creator 5, the fixture pool has a coin creator
+    assert_eq!(fees.total_bps(&pool), 30);
+
+    let base_reserve = 800_000_000_000_000;
+    let quote_reserve = 40_000_000_000;
+    assert_eq!(
+        quote_buy(base_reserve, quote_reserve, 1_000_000, 30),
+        Some(SwapQuote {
+            amount_in: 1_000_000,
+            amount_out: 19_939_662_999,
+            fee: 2_992,
+            price_impact_bps: 0,
+        })
+    );
+    // 10% of the quote reserve moves the price by 9%
+    assert_eq!(
+        quote_buy(base_reserve, quote_reserve, 4_000_000_000, 30),
+        Some(SwapQuote {
+            amount_in: 4_000_000_000,
+            amount_out: 72_529_465_089_852,
+            fee: 11_964_108,
+            price_impact_bps: 906,
+        })
+    );
+    assert_eq!(quote_buy(base_reserve, 0, 1_000_000, 30), None);
+
+    // fees read from a corrupt config saturate instead of overflowing
+    let corrupt = PumpAmmFees {
+        lp_fee_bps: u64::MAX,
+        ..fees
+    };
+    assert_eq!(corrupt.total_bps(&pool), u64::MAX);
+    assert_eq!(
+        quote_buy(base_reserve, quote_reserve, 1_000_000, u64::MAX),
+        None
+    );
+    assert_eq!(
+        quote_sell(base_reserve, u64::MAX, u64::MAX, &[u64::MAX; 3]),
+        None
+    );
+}
+
+#[test]
+fn it_quotes_pump_amm_sells_with_each_fee_rounded_up() {
+    let base_reserve = 800_000_000_000_000;
+    let quote_reserve = 40_000_000_000;
+    let fee_bps = [20, 5, 5];
+    assert_eq!(
+        quote_sell(base_reserve, quote_reserve, 20_000_000_000, &fee_bps),
+        Some(SwapQuote {
+            amount_in: 20_000_000_000,
+            amount_out: 996_975,
+            fee: 3_000,
+            price_impact_bps: 0,
+        })
+    );
+    // 10% of the base reserve moves the price by 9%
+    assert_eq!(
+        quote_sell(base_reserve, quote_reserve, 80_000_000_000_000, &fee_bps),
+        Some(SwapQuote {
+            amount_in: 80_000_000_000_000,
+            amount_out: 3_625_454_544,
+            fee: 10_909_092,
+            price_impact_bps: 909,
+        })
+    );
+    // 101 lamports out pay 1 lamport per fee, not 1 lamport on the 30 bps total
+    let quote = quote_sell(base_reserve, quote_reserve, 2_040_000, &fee_bps).unwrap();
+    assert_eq!((quote.amount_out, quote.fee), (98, 3));
+    assert_eq!(quote_sell(base_reserve, quote_reserve, 1, &fee_bps), None);
+    assert_eq!(quote_sell(0, quote_reserve, 1_000, &fee_bps), None);
+}
+
+#[test]
+fn it_quotes_pump_amm_exact_out_buys_with_each_fee_rounded_up() {
+    let base_reserve = 800_000_000_000_000;
+    let quote_reserve = 40_000_000_000;
+    let fee_bps = [20, 5, 5];
+    let quote = quote_buy_exact_out(base_reserve, quote_reserve, 10_000_000, &fee_bps).unwrap();
+    // 10_000_000 base cost 500.00000625 lamports before fees, rounded up to 501,
+    // then 2 + 1 + 1 lamports of fees; the rounding alone shows as 19 bps of impact
+    assert_eq!(
+        quote,
+        SwapQuote {
+            amount_in: 505,
+            amount_out: 10_000_000,
+            fee: 4,
+            price_impact_bps: 19,
+        }
+    );
+    // the quoted input buys back at least the requested base, and never costs more
+    let buy = quote_buy_within(base_reserve, quote_reserve, quote.amount_in, &fee_bps).unwrap();
+    assert!(buy.amount_out >= quote.amount_out);
+    assert!(buy.amount_in <= quote.amount_in);
+
+    assert_eq!(
+        quote_buy_exact_out(base_reserve, quote_reserve, base_reserve, &fee_bps),
+        None
+    );
+    assert_eq!(
+        quote_buy_exact_out(base_reserve, quote_reserve, 0, &fee_bps),
+        None
+    );
+    // fees read from a corrupt config overflow the input instead of wrapping
+    assert_eq!(
+        quote_buy_exact_out(base_reserve, quote_reserve, 100_000_000_000, &[u64::MAX; 3]),
+        None
+    );
+}
+
+#[test]
+fn it_buys_on_pump_amm_the_most_base_the_input_pays_for() {
+    let base_reserve = 800_000_000_000_000;
+    let quote_reserve = 40_000_000_000;
+    let fee_bps = [20, 5, 5];
+    let cost = |base_amount_out| {
+        quote_buy_exact_out(base_reserve, quote_reserve, base_amount_out, &fee_bps)
+            .unwrap()
+            .amount_in
+    };
+
+    // `quote_buy` rounds the 30 bps once, Pump rounds each fee up on its own
+    for (amount_in, overestimate_cost, amount_out) in [
+        (1_000_000, 1_000_001, 19_939_643_000),
+        (505, 507, 10_019_999),
+    ] {
+        let overestimate = quote_buy(base_reserve, quote_reserve, amount_in, 30).unwrap();
+        assert_eq!(cost(overestimate.amount_out), overestimate_cost);
+
+        let quote = quote_buy_within(base_reserve, quote_reserve, amount_in, &fee_bps).unwrap();
+        assert_eq!({ quote.amount_out }, amount_out);
+        assert_eq!({ quote.amount_in }, cost(amount_out));
+        assert!(quote.amount_in <= amount_in);
+        assert!(cost(amount_out + 1) > amount_in);
+    }
+
+    for amount_in in [1_000, 123_456_789, 4_000_000_000] {
+        let quote = quote_buy_within(base_reserve, quote_reserve, amount_in, &fee_bps).unwrap();
+        assert!(quote.amount_in <= amount_in);
+        assert!(cost(quote.amount_out + 1) > amount_in);
+    }
+    // 3 lamports cover the three rounded up fees of a 1 lamport curve input only
+    assert_eq!(
+        quote_buy_within(base_reserve, quote_reserve, 3, &fee_bps),
+        None
+    );
+    assert_eq!(quote_buy_within(base_reserve, 0, 1_000_000, &fee_bps), None);
+    // fees read from a corrupt config leave nothing to buy instead of overflowing
+    assert_eq!(
+        quote_buy_within(base_reserve, quote_reserve, 1_000_000, &[u64::MAX; 3]),
+        None
+    );
+}
+
+#[test]
+fn it_walks_dlmm_bins_on_the_fixture_pair() {
+    let (lb_pair, bin_array_data) = fixture_pair();
+    let bin_arrays: Vec<_> = bin_array_data
+        .iter()
+        .map(|data| BinArray::from_bytes(data).unwrap())
+        .collect();
+    assert_eq!(lb_pair.token_y_mint, WSOL_MINT.to_bytes());
+
+    // Inside the active bin: base fee only, at a price of exactly 1
+    assert_eq!(
+        quote_exact_in(lb_pair, &bin_arrays, 100, false, NOW, || u64::MAX),
+        Some(SwapQuote {
+            amount_in: 100,
+            amount_out: 99,
+            fee: 1,
+            price_impact_bps: 0,
+        })
+    );
+    // Across 3 bins, the variable fee grows with every bin crossed
+    assert_eq!(
+        quote_exact_in(lb_pair, &bin_arrays, 2_500_000_000, false, NOW, || u64::MAX),
+        Some(SwapQuote {
+            amount_in: 2_500_000_000,
+            amount_out: 2_488_728_927,
+            fee: 6_324_373,
+            price_impact_bps: 19,
+        })
+    );
+    // More than the 210 bins of the given bin arrays hold
+    assert_eq!(
+        quote_exact_in(lb_pair, &bin_arrays, 300_000_000_000, false, NOW, || {
+            u64::MAX
+        }),
+        None
+    );
+}
+
+#[test]
+fn it_quotes_dlmm_exact_out_against_the_exact_in_walk() {
+    let (lb_pair, bin_array_data) = fixture_pair();
+    let bin_arrays: Vec<_> = bin_array_data
+        .iter()
+        .map(|data| BinArray::from_bytes(data).unwrap())
+        .collect();
+
+    // Each quote buys back exactly the output of the exact-in walk it mirrors
+    for (amount_in, amount_out, fee, price_impact_bps) in [
+        (100, 99, 1, 0),
+        (2_007_543_987, 2_000_000_000, 5_043_987, 12),
+        (2_500_000_000, 2_488_728_927, 6_324_373, 19),
+    ] {
+        assert_eq!(
+            quote_exact_out(lb_pair, &bin_arrays, amount_out, false, NOW, || u64::MAX),
+            Some(SwapQuote {
+                amount_in,
+                amount_out,
+                fee,
+                price_impact_bps,
+            })
+        );
+    }
+    // More than the 210 bins of the given bin arrays hold
+    assert_eq!(
+        quote_exact_out(lb_pair, &bin_arrays, 300_000_000_000, false, NOW, || {
+            u64::MAX
+        }),
+        None
+    );
+    assert_eq!(
+        quote_exact_out(lb_pair, &bin_arrays, 99, false, NOW, || 0),
+        None
+    );
+}
+
+#[test]
+fn it_quotes_the_bins_filled_before_the_budget_runs_out() {
+    let (lb_pair, bin_array_data) = fixture_pair();
+    let bin_arrays: Vec<_> = bin_array_data
+        .iter()
+        .map(|data| BinArray::from_bytes(data).unwrap())
+        .collect();
+
+    // Enough units for 2 bins, the third would need 3
+    let bins_walked = Cell::new(0);
+    let remaining_units = || {
+        bins_walked.set(bins_walked.get() + 1);
+        if bins_walked.get() > 2 {
+            QUOTE_BIN_UNITS - 1
+        } else {
+            u64::MAX
+        }
+    };
+    assert_eq!(
+        quote_exact_in(
+            lb_pair,
+            &bin_arrays,
+            2_500_000_000,
+            false,
+            NOW,
+            remaining_units
+        ),
+        Some(SwapQuote {
+            amount_in: 2_007_543_987,
+            amount_out: 2_000_000_000,
+            fee: 5_043_987,
+            price_impact_bps: 12,
+        })
+    );
+    assert_eq!(
+        quote_exact_in(lb_pair, &bin_arrays, 100, false, NOW, || 0),
+        None
+    );
+}
+
+#[test]
+fn it_returns_the_pump_amm_quote_without_cpi() {
+    let (svm, user) = setup_without_dex("pump_amm_pool.json");
+    let base_mint_acc = associated_token_address(&user.pubkey(), &WSOL_MINT);
+    let user_mint_acc = associated_token_address(&user.pubkey(), &TEST_MINT);
+
+    let mut ix = pump_amm_swap_ix(
+        &FIXTURE_POOL,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.data = quote_data(SupportDex::PumpAmm, 1_000_000);
+
+    // The quote is what an exact-out buy capped at amount_in delivers and spends
+    let base_reserve = token_balance(&svm, &FIXTURE_POOL.pool_base_token_acc);
+    let quote_reserve = token_balance(&svm, &FIXTURE_POOL.pool_quote_token_acc);
+    let fee_bps = [20, 5, 5];
+    let quote = simulate_quote(&svm, ix, &user);
+    assert_eq!(
+        quote,
+        quote_buy_within(base_reserve, quote_reserve, 1_000_000, &fee_bps).unwrap()
+    );
+    assert!(quote.amount_in <= 1_000_000);
+    assert_eq!(
+        quote_buy_exact_out(base_reserve, quote_reserve, quote.amount_out, &fee_bps),
+        Some(quote)
+    );
+    assert!(!svm
+        .get_account(&PUMP_PROGRAM_ID)
+        .is_some_and(|program| program.executable));
+}
+
+#[test]
+fn it_quotes_the_dlmm_swap_it_executes() {
+    let (mut svm, user) = setup("dlmm_pair.json", &DLMM_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let swap_ix = dlmm_swap_ix(
+        &FIXTURE_PAIR,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    let mut quote_ix = swap_ix.clone();
+    quote_ix.data = quote_data(SupportDex::DLMM, AMOUNT_IN);
+    let quote = simulate_quote(&svm, quote_ix, &user);
+
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), swap_ix], &user)
+        .expect("dlmm swap should have succeeded");
+    let (amount_in, amount_out) = (quote.amount_in, quote.amount_out);
+    assert_eq!(amount_in, AMOUNT_IN);
+    assert_eq!(token_balance(&svm, &user_mint_acc), amount_out);
+}
+
+#[test]
+fn it_rejects_a_quote_for_another_pair() {
+    let (svm, user) = setup_without_dex("dlmm_pair.json");
+    let base_mint_acc = associated_token_address(&user.pubkey(), &WSOL_MINT);
+    let user_mint_acc = associated_token_address(&user.pubkey(), &TEST_MINT);
+
+    let mut ix = dlmm_swap_ix(
+        &FIXTURE_PAIR,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.data = quote_data(SupportDex::DLMM, AMOUNT_IN);
+    // output mint swapped for the base mint
+    ix.accounts[7].pubkey = WSOL_MINT;
+
+    let tx = solana_transaction::Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&user.pubkey()),
+        &[&user],
+        svm.latest_blockhash(),
+    );
+    let err = svm.simulate_transaction(tx).unwrap_err();
+    assert_custom_error(&err.err, 502); // DlmmError::InvalidTokenPair
+}
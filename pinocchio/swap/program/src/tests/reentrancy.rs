@@ -0,0 +1,118 @@
+//! Instructions reached through CPI, from a router or a DEX calling back.
+use solana_keypair::Signer;
+
+use super::mock_cpi::*;
+use super::operator_limits::{send_signed, set_operator_limits_ix};
+use super::pump_amm::*;
+use super::*;
+use crate::instruction::{OperatorLimitsIxData, QuoteIxData};
+
+const CPI_CALLER_MOCK_PATH: &str = "target/deploy/cpi_caller_mock.so";
+const CPI_CALLER_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0xCA; 32]);
+
+fn add_cpi_caller(svm: &mut LiteSVM) {
+    svm.add_program_from_file(CPI_CALLER_PROGRAM_ID, CPI_CALLER_MOCK_PATH)
+        .unwrap_or_else(|_| {
+            panic!("{CPI_CALLER_MOCK_PATH} not found, run `cargo build-sbf` first")
+        });
+}
+
+/// `ix` sent through the CPI caller mock, one level below the top.
+fn through_cpi(ix: &Instruction) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(ix.program_id, false)];
+    accounts.extend(ix.accounts.iter().cloned());
+    Instruction {
+        program_id: CPI_CALLER_PROGRAM_ID,
+        accounts,
+        data: ix.data.clone(),
+    }
+}
+
+#[test]
+fn it_rejects_a_swap_invoked_through_cpi() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    add_cpi_caller(&mut svm);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+
+    let res = send_tx(
+        &mut svm,
+        &[set_compute_unit_limit(400_000), through_cpi(&ix)],
+        &user,
+    )
+    .expect_err("a nested swap should fail");
+    assert_custom_error(&res.err, 19); // LossProgramError::NestedInvocation
+    assert!(!res
+        .meta
+        .logs
+        .iter()
+        .any(|log| log.starts_with(&format!("Program {PUMP_PROGRAM_ID} invoke"))));
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("the same swap should succeed at the top level");
+}
+
+#[test]
+fn it_rejects_operator_limits_set_through_cpi() {
+    let (mut svm, admin) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    add_cpi_caller(&mut svm);
+    let operator = Keypair::new();
+    let ix = set_operator_limits_ix(
+        &admin.pubkey(),
+        &operator.pubkey(),
+        true,
+        OperatorLimitsIxData::default(),
+    );
+
+    let res = send_signed(&mut svm, &[through_cpi(&ix)], &admin, &[&admin, &operator])
+        .expect_err("nested limits should fail");
+    assert_custom_error(&res.err, 19); // LossProgramError::NestedInvocation
+    assert!(svm
+        .get_account(&operator_limits_address(&operator.pubkey()))
+        .is_none_or(|account| account.data.is_empty()));
+}
+
+#[test]
+fn it_quotes_through_cpi() {
+    // a quote never calls the DEX program, the snapshot is enough
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(crate::ID, SWAP_PROGRAM_PATH)
+        .expect("swap_program.so not found, run `cargo build-sbf` first");
+    SurfpoolSnapshotLoader::load(&format!("{FIXTURES_DIR}/pump_amm_pool.json"), &mut svm);
+    add_cpi_caller(&mut svm);
+    let user = Keypair::new();
+    svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+    let base_mint_acc = associated_token_address(&user.pubkey(), &WSOL_MINT);
+    let user_mint_acc = associated_token_address(&user.pubkey(), &TEST_MINT);
+
+    let mut ix = pump_amm_swap_ix(
+        &FIXTURE_POOL,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.data = vec![LossProgramInstruction::Quote as u8];
+    ix.data.extend_from_slice(unsafe {
+        to_bytes(&QuoteIxData {
+            dex: SupportDex::PumpAmm,
+            amount_in: 1_000_000,
+        })
+    });
+
+    let tx = solana_transaction::Transaction::new_signed_with_payer(
+        &[through_cpi(&ix)],
+        Some(&user.pubkey()),
+        &[&user],
+        svm.latest_blockhash(),
+    );
+    let res = svm
+        .simulate_transaction(tx)
+        .expect("a quote should be available through cpi");
+    assert_eq!(
+        res.meta.return_data.program_id,
+        Pubkey::new_from_array(crate::ID)
+    );
+}
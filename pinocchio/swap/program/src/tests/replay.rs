@@ -0,0 +1,720 @@
+//! Replays recorded transactions against the current swap_program build.
+//!
+//! A recording is a JSON file holding the instructions of one transaction, the
+//! `AccountsSnapshot` surfpool exported right before it ran, and what happened
+//! on chain: the error as printed by `{:?}`, the logs and the post-state of the
+//! accounts worth checking. `RecordedTransaction::replay` runs the instructions
+//! in LiteSVM with signature checks off, since the production keys are not at
+//! hand, and `Replay::diff` lists every way the outcome moved.
+//!
+//! A production failure comes out of surfpool as two files: the surfnet's
+//! `getTransaction` response, requested with `"encoding": "base64"`, and the
+//! `surfnet_exportSnapshot` result with the `preTransaction` scope of its
+//! signature and `includeProgramAccounts`, so the DEX programs travel with the
+//! accounts. `RecordedTransaction::from_surfpool` turns them into a recording,
+//! `fixtures/failed_arbitrage_*.json` is one of them.
+//!
+//! To reproduce a failed arbitrage, save its recording next to the fixtures and
+//! call `RecordedTransaction::read(path).replay().assert_matches(..)`.
+use std::{collections::BTreeMap, fmt, format, string::String, vec::Vec};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use litesvm::{types::TransactionMetadata, LiteSVM};
+use serde::{Deserialize, Serialize};
+use solana_account::Account;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Signer;
+use solana_message::VersionedMessage;
+use solana_pubkey::Pubkey;
+use solana_transaction::versioned::VersionedTransaction;
+use solana_transaction_error::TransactionError;
+use surfpool_types::{AccountSnapshot, AccountsSnapshot};
+
+use super::mock_cpi::*;
+use super::operator_limits::limited_pump_amm_swap;
+use super::pump_amm::*;
+use super::*;
+use crate::instruction::OperatorLimitsIxData;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedInstruction {
+    pub program_id: String,
+    pub accounts: Vec<RecordedAccountMeta>,
+    /// Base64 encoded instruction data
+    pub data: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedOutcome {
+    /// `TransactionError` as printed by `{:?}`, e.g. `InstructionError(1, Custom(9))`
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    /// Accounts to compare after the replay, an account closed on chain has 0 lamports
+    pub post_state: AccountsSnapshot,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedTransaction {
+    pub payer: String,
+    pub slot: Option<u64>,
+    pub unix_timestamp: Option<i64>,
+    pub instructions: Vec<RecordedInstruction>,
+    pub pre_state: AccountsSnapshot,
+    pub outcome: RecordedOutcome,
+}
+
+/// `getTransaction` result of a surfnet, with the base64 encoding.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurfpoolTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Wire transaction and its encoding, `["<base64>", "base64"]`
+    pub transaction: (String, String),
+    pub meta: SurfpoolTransactionMeta,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurfpoolTransactionMeta {
+    pub err: Option<TransactionError>,
+    #[serde(default)]
+    pub log_messages: Vec<String>,
+    /// Accounts a v0 message loaded from its lookup tables
+    #[serde(default)]
+    pub loaded_addresses: SurfpoolLoadedAddresses,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SurfpoolLoadedAddresses {
+    pub writable: Vec<String>,
+    pub readonly: Vec<String>,
+}
+
+impl SurfpoolTransaction {
+    /// Reads a saved response, bare or inside its JSON-RPC envelope.
+    pub fn read(path: &str) -> Self {
+        let json = std::fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("Failed to read transaction at {path}"));
+        let mut value: serde_json::Value = serde_json::from_str(&json)
+            .unwrap_or_else(|e| panic!("Failed to parse transaction at {path}: {e}"));
+        if let Some(result) = value.get_mut("result") {
+            value = result.take();
+        }
+        serde_json::from_value(value)
+            .unwrap_or_else(|e| panic!("Failed to deserialize transaction at {path}: {e}"))
+    }
+
+    pub fn decode(&self) -> VersionedTransaction {
+        let (data, encoding) = &self.transaction;
+        assert_eq!(encoding, "base64", "request the transaction as base64");
+        let wire = BASE64_STANDARD
+            .decode(data)
+            .expect("transaction should be base64");
+        bincode::deserialize(&wire).expect("transaction should be a wire transaction")
+    }
+
+    /// Every account of the message with its writability, the static keys
+    /// first and then the lookup table addresses, as the runtime orders them.
+    fn account_keys(&self, message: &VersionedMessage) -> Vec<(Pubkey, bool)> {
+        let header = message.header();
+        let signers = header.num_required_signatures as usize;
+        let writable_signers = signers - header.num_readonly_signed_accounts as usize;
+        let static_keys = message.static_account_keys();
+        let writable_unsigned = static_keys.len() - header.num_readonly_unsigned_accounts as usize;
+        let loaded = &self.meta.loaded_addresses;
+        static_keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let is_writable = if index < signers {
+                    index < writable_signers
+                } else {
+                    index < writable_unsigned
+                };
+                (*key, is_writable)
+            })
+            .chain(
+                loaded
+                    .writable
+                    .iter()
+                    .map(|key| (Pubkey::from_str_const(key), true)),
+            )
+            .chain(
+                loaded
+                    .readonly
+                    .iter()
+                    .map(|key| (Pubkey::from_str_const(key), false)),
+            )
+            .collect()
+    }
+}
+
+/// Outcome of one replay in LiteSVM.
+pub struct Replay {
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    pub post_state: BTreeMap<Pubkey, Account>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Difference {
+    Error {
+        recorded: Option<String>,
+        replayed: Option<String>,
+    },
+    Log {
+        line: usize,
+        recorded: Option<String>,
+        replayed: Option<String>,
+    },
+    Account {
+        address: Pubkey,
+        field: &'static str,
+        recorded: String,
+        replayed: String,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::Error { recorded, replayed } => {
+                write!(f, "error: recorded {recorded:?}, replayed {replayed:?}")
+            }
+            Difference::Log {
+                line,
+                recorded,
+                replayed,
+            } => write!(
+                f,
+                "log {line}:\n  - {}\n  + {}",
+                recorded.as_deref().unwrap_or("<none>"),
+                replayed.as_deref().unwrap_or("<none>")
+            ),
+            Difference::Account {
+                address,
+                field,
+                recorded,
+                replayed,
+            } => write!(
+                f,
+                "{address} {field}: recorded {recorded}, replayed {replayed}"
+            ),
+        }
+    }
+}
+
+fn snapshot_account(account: &Account) -> AccountSnapshot {
+    AccountSnapshot::new(
+        account.lamports,
+        account.owner.to_string(),
+        account.executable,
+        account.rent_epoch,
+        BASE64_STANDARD.encode(&account.data),
+        None,
+    )
+}
+
+/// The compute units of a program differ from build to build.
+fn mask_compute_units(line: &str) -> String {
+    match line.split_once(" consumed ") {
+        Some((program, _)) if line.ends_with(" compute units") => {
+            format!("{program} consumed _ of _ compute units")
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// SPL token amount, for a readable diff of token accounts.
+fn token_account_amount(account: &Account) -> Option<u64> {
+    if account.owner != TOKEN_PROGRAM_ID && account.owner != TOKEN_2022_PROGRAM_ID {
+        return None;
+    }
+    Some(u64::from_le_bytes(
+        account.data.get(64..72)?.try_into().ok()?,
+    ))
+}
+
+impl RecordedInstruction {
+    fn to_instruction(&self) -> Instruction {
+        Instruction {
+            program_id: Pubkey::from_str_const(&self.program_id),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: Pubkey::from_str_const(&meta.pubkey),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: BASE64_STANDARD
+                .decode(&self.data)
+                .expect("instruction data should be base64"),
+        }
+    }
+
+    fn from_instruction(ix: &Instruction) -> Self {
+        Self {
+            program_id: ix.program_id.to_string(),
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| RecordedAccountMeta {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: BASE64_STANDARD.encode(&ix.data),
+        }
+    }
+}
+
+impl RecordedTransaction {
+    pub fn read(path: &str) -> Self {
+        let json = std::fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("Failed to read recording at {path}"));
+        serde_json::from_str(&json)
+            .unwrap_or_else(|e| panic!("Failed to deserialize recording at {path}: {e}"))
+    }
+
+    pub fn write(&self, path: &str) {
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap())
+            .unwrap_or_else(|_| panic!("Failed to write recording at {path}"));
+    }
+
+    /// Builds a recording from what surfpool exported for a transaction.
+    ///
+    /// `pre_snapshot_path` is the `preTransaction` snapshot of its signature. The
+    /// optional `post_snapshot_path` is a snapshot taken right after it landed,
+    /// of which the writable accounts of the transaction become the post-state.
+    pub fn from_surfpool(
+        transaction_path: &str,
+        pre_snapshot_path: &str,
+        post_snapshot_path: Option<&str>,
+    ) -> Self {
+        let exported = SurfpoolTransaction::read(transaction_path);
+        let transaction = exported.decode();
+        let message = &transaction.message;
+        let keys = exported.account_keys(message);
+        let signers = message.header().num_required_signatures as usize;
+        let meta = |index: u8| {
+            let (pubkey, is_writable) = keys[index as usize];
+            RecordedAccountMeta {
+                pubkey: pubkey.to_string(),
+                is_signer: (index as usize) < signers,
+                is_writable,
+            }
+        };
+        let instructions = message
+            .instructions()
+            .iter()
+            .map(|ix| RecordedInstruction {
+                program_id: keys[ix.program_id_index as usize].0.to_string(),
+                accounts: ix.accounts.iter().map(|&index| meta(index)).collect(),
+                data: BASE64_STANDARD.encode(&ix.data),
+            })
+            .collect();
+
+        let post_state = post_snapshot_path
+            .map(SurfpoolSnapshotLoader::read)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(address, _)| {
+                keys.iter()
+                    .any(|(key, is_writable)| *is_writable && key.to_string() == *address)
+            })
+            .collect();
+
+        Self {
+            payer: keys[0].0.to_string(),
+            slot: Some(exported.slot),
+            unix_timestamp: exported.block_time,
+            instructions,
+            pre_state: SurfpoolSnapshotLoader::read(pre_snapshot_path),
+            outcome: RecordedOutcome {
+                err: exported.meta.err.map(|err| format!("{err:?}")),
+                logs: exported.meta.log_messages,
+                post_state,
+            },
+        }
+    }
+
+    /// Runs `ixs` in `svm` and records them the way a production recording reads.
+    ///
+    /// The pre-state holds every account the transaction references except
+    /// swap_program itself, the post-state its writable accounts.
+    pub fn capture(svm: &mut LiteSVM, ixs: &[Instruction], payer: &Keypair) -> Self {
+        let program_id = Pubkey::new_from_array(crate::ID);
+        let mut addresses: Vec<Pubkey> = ixs
+            .iter()
+            .flat_map(|ix| {
+                core::iter::once(ix.program_id).chain(ix.accounts.iter().map(|meta| meta.pubkey))
+            })
+            .chain([payer.pubkey()])
+            .filter(|address| address != &program_id)
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+        let mut writable: Vec<Pubkey> = ixs
+            .iter()
+            .flat_map(|ix| ix.accounts.iter())
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect();
+        writable.sort();
+        writable.dedup();
+
+        let pre_state = addresses
+            .iter()
+            .filter_map(|address| {
+                Some((
+                    address.to_string(),
+                    snapshot_account(&svm.get_account(address)?),
+                ))
+            })
+            .collect();
+        let clock = svm.get_sysvar::<solana_clock::Clock>();
+        let res = send_tx(svm, ixs, payer);
+        let (err, meta) = match res {
+            Ok(meta) => (None, meta),
+            Err(failed) => (Some(format!("{:?}", failed.err)), failed.meta),
+        };
+        let post_state = writable
+            .iter()
+            .map(|address| {
+                let account = svm.get_account(address).unwrap_or_default();
+                (address.to_string(), snapshot_account(&account))
+            })
+            .collect();
+
+        Self {
+            payer: payer.pubkey().to_string(),
+            slot: Some(clock.slot),
+            unix_timestamp: Some(clock.unix_timestamp),
+            instructions: ixs
+                .iter()
+                .map(RecordedInstruction::from_instruction)
+                .collect(),
+            pre_state,
+            outcome: RecordedOutcome {
+                err,
+                logs: meta.logs,
+                post_state,
+            },
+        }
+    }
+
+    /// Loads the pre-state and the current swap_program build, then sends the
+    /// recorded instructions. The DEX programs come from the pre-state only.
+    pub fn replay(&self) -> Replay {
+        let mut svm = LiteSVM::new().with_sigverify(false);
+        let (programs, accounts): (Vec<_>, Vec<_>) = self
+            .pre_state
+            .iter()
+            .partition(|(_, account)| account.executable);
+        for (address, account) in accounts.into_iter().chain(programs) {
+            let address = Pubkey::from_str_const(address);
+            // The runtime's own programs stay as LiteSVM ships them
+            if account.executable
+                && svm
+                    .get_account(&address)
+                    .is_some_and(|account| account.executable)
+            {
+                continue;
+            }
+            svm.set_account(address, account.clone().into()).unwrap();
+        }
+        svm.add_program_from_file(crate::ID, SWAP_PROGRAM_PATH)
+            .expect("swap_program.so not found, run `cargo build-sbf` first");
+
+        let ixs: Vec<Instruction> = self
+            .instructions
+            .iter()
+            .map(RecordedInstruction::to_instruction)
+            .collect();
+        if let Some(slot) = self.slot {
+            svm.warp_to_slot(slot);
+        }
+        if let Some(unix_timestamp) = self.unix_timestamp {
+            let mut clock = svm.get_sysvar::<solana_clock::Clock>();
+            clock.unix_timestamp = unix_timestamp;
+            svm.set_sysvar(&clock);
+        }
+
+        let payer = Pubkey::from_str_const(&self.payer);
+        let mut tx = solana_transaction::Transaction::new_with_payer(&ixs, Some(&payer));
+        tx.message.recent_blockhash = svm.latest_blockhash();
+        let (err, meta): (Option<String>, TransactionMetadata) = match svm.send_transaction(tx) {
+            Ok(meta) => (None, meta),
+            Err(failed) => (Some(format!("{:?}", failed.err)), failed.meta),
+        };
+
+        let post_state = self
+            .outcome
+            .post_state
+            .keys()
+            .map(|address| {
+                let address = Pubkey::from_str_const(address);
+                (address, svm.get_account(&address).unwrap_or_default())
+            })
+            .collect();
+        Replay {
+            err,
+            logs: meta.logs,
+            post_state,
+        }
+    }
+}
+
+impl Replay {
+    /// Every difference to the recorded outcome, compute units aside.
+    pub fn diff(&self, recorded: &RecordedOutcome) -> Vec<Difference> {
+        let mut differences = Vec::new();
+        if self.err != recorded.err {
+            differences.push(Difference::Error {
+                recorded: recorded.err.clone(),
+                replayed: self.err.clone(),
+            });
+        }
+
+        for line in 0..recorded.logs.len().max(self.logs.len()) {
+            let recorded = recorded.logs.get(line).map(|log| mask_compute_units(log));
+            let replayed = self.logs.get(line).map(|log| mask_compute_units(log));
+            if recorded != replayed {
+                differences.push(Difference::Log {
+                    line,
+                    recorded,
+                    replayed,
+                });
+            }
+        }
+
+        for (address, expected) in &recorded.post_state {
+            let expected: Account = expected.clone().into();
+            let address = Pubkey::from_str_const(address);
+            let actual = &self.post_state[&address];
+            let mut push = |field, recorded: String, replayed: String| {
+                if recorded != replayed {
+                    differences.push(Difference::Account {
+                        address,
+                        field,
+                        recorded,
+                        replayed,
+                    });
+                }
+            };
+            push(
+                "lamports",
+                expected.lamports.to_string(),
+                actual.lamports.to_string(),
+            );
+            // A closed account keeps neither its owner nor its data
+            if expected.lamports == 0 && actual.lamports == 0 {
+                continue;
+            }
+            push(
+                "owner",
+                expected.owner.to_string(),
+                actual.owner.to_string(),
+            );
+            if let (Some(recorded), Some(replayed)) = (
+                token_account_amount(&expected),
+                token_account_amount(actual),
+            ) {
+                if recorded != replayed {
+                    push("token amount", recorded.to_string(), replayed.to_string());
+                    continue;
+                }
+            }
+            push(
+                "data length",
+                expected.data.len().to_string(),
+                actual.data.len().to_string(),
+            );
+            if let Some(offset) = expected
+                .data
+                .iter()
+                .zip(&actual.data)
+                .position(|(recorded, replayed)| recorded != replayed)
+            {
+                push(
+                    "data",
+                    format!("{:#04x} at {offset}", expected.data[offset]),
+                    format!("{:#04x} at {offset}", actual.data[offset]),
+                );
+            }
+        }
+        differences
+    }
+
+    /// Panics with every difference to `recorded`.
+    pub fn assert_matches(&self, recorded: &RecordedOutcome) {
+        let differences = self.diff(recorded);
+        assert!(
+            differences.is_empty(),
+            "replay differs from the recording:\n{}",
+            differences
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+}
+
+/// Writes `recording` to a file and reads it back, as a stored recording would be.
+fn round_trip(recording: &RecordedTransaction, name: &str) -> RecordedTransaction {
+    let path =
+        std::env::temp_dir().join(format!("swap_program_{name}_{}.json", std::process::id()));
+    let path = path.to_str().unwrap();
+    recording.write(path);
+    let read = RecordedTransaction::read(path);
+    std::fs::remove_file(path).unwrap();
+    read
+}
+
+#[test]
+fn it_reproduces_a_recorded_failed_arbitrage() {
+    let (mut svm, user, _, ix, base_mint_acc) = limited_pump_amm_swap(OperatorLimitsIxData {
+        max_notional_per_trade: 1,
+        ..Default::default()
+    });
+    let recording = round_trip(
+        &RecordedTransaction::capture(&mut svm, &[set_compute_unit_limit(400_000), ix], &user),
+        "failed_arbitrage",
+    );
+    assert_eq!(
+        recording.outcome.err.as_deref(),
+        Some("InstructionError(1, Custom(9))")
+    );
+    assert!(recording
+        .outcome
+        .post_state
+        .contains_key(&base_mint_acc.to_string()));
+
+    // A fresh LiteSVM fails the same way from the recorded pre-state alone
+    let replay = recording.replay();
+    replay.assert_matches(&recording.outcome);
+    assert_eq!(
+        token_account_amount(&replay.post_state[&base_mint_acc]),
+        Some(USER_WSOL)
+    );
+}
+
+#[test]
+fn it_reports_how_the_replay_moved_from_the_recording() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let keys = set_mock_pump_pool(&mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+    let ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    let mut recording =
+        RecordedTransaction::capture(&mut svm, &[set_compute_unit_limit(400_000), ix], &user);
+    assert_eq!(recording.outcome.err, None);
+    recording.replay().assert_matches(&recording.outcome);
+
+    // Pretend production saw the buy fail on the limit, with less output
+    let mut user_mint: Account = recording.outcome.post_state[&user_mint_acc.to_string()]
+        .clone()
+        .into();
+    let received = token_account_amount(&user_mint).unwrap();
+    user_mint.data[64..72].copy_from_slice(&(received - 1).to_le_bytes());
+    recording
+        .outcome
+        .post_state
+        .insert(user_mint_acc.to_string(), snapshot_account(&user_mint));
+    recording.outcome.err = Some("InstructionError(1, Custom(9))".to_string());
+    let last_log = recording.outcome.logs.len() - 1;
+    let program_id = Pubkey::new_from_array(crate::ID);
+    recording.outcome.logs[last_log] = format!("Program {program_id} failed");
+
+    let differences = recording.replay().diff(&recording.outcome);
+    assert_eq!(
+        differences,
+        vec![
+            Difference::Error {
+                recorded: Some("InstructionError(1, Custom(9))".to_string()),
+                replayed: None,
+            },
+            Difference::Log {
+                line: last_log,
+                recorded: Some(format!("Program {program_id} failed")),
+                replayed: Some(format!("Program {program_id} success")),
+            },
+            Difference::Account {
+                address: user_mint_acc,
+                field: "token amount",
+                recorded: (received - 1).to_string(),
+                replayed: received.to_string(),
+            },
+        ]
+    );
+}
+
+fn surfpool_recording() -> RecordedTransaction {
+    RecordedTransaction::from_surfpool(
+        &format!("{FIXTURES_DIR}/failed_arbitrage_transaction.json"),
+        &format!("{FIXTURES_DIR}/failed_arbitrage_pre_state.json"),
+        Some(&format!("{FIXTURES_DIR}/failed_arbitrage_post_state.json")),
+    )
+}
+
+#[test]
+fn it_loads_a_transaction_exported_by_surfpool() {
+    let recording = surfpool_recording();
+    let user = Pubkey::from_str_const(&recording.payer);
+    let base_mint_acc = associated_token_address(&user, &WSOL_MINT);
+    let user_mint_acc = associated_token_address(&user, &TEST_MINT);
+
+    // The v0 message resolves its lookup table accounts to the original swap. A
+    // message keeps one set of flags per account, so the user signs twice.
+    let mut swap = pump_amm_swap_ix(&FIXTURE_POOL, &user, &base_mint_acc, &user_mint_acc);
+    swap.accounts[2].is_signer = true;
+    let ixs: Vec<Instruction> = recording
+        .instructions
+        .iter()
+        .map(RecordedInstruction::to_instruction)
+        .collect();
+    assert_eq!(ixs, [set_compute_unit_limit(10_000), swap]);
+    assert_eq!(recording.slot, Some(372_345_678));
+    assert_eq!(
+        recording.outcome.err.as_deref(),
+        Some("InstructionError(1, Custom(18))")
+    );
+    assert_eq!(recording.outcome.logs.len(), 5);
+    assert!(recording
+        .pre_state
+        .contains_key(&FIXTURE_POOL.pool.to_string()));
+    assert!(recording.pre_state.contains_key(&user.to_string()));
+    // Of the post snapshot only the writable accounts of the transaction remain
+    assert_eq!(recording.outcome.post_state.len(), 2);
+    assert!(!recording
+        .outcome
+        .post_state
+        .contains_key(&FIXTURE_POOL.pool.to_string()));
+    assert!(recording
+        .outcome
+        .post_state
+        .contains_key(&base_mint_acc.to_string()));
+}
+
+#[test]
+fn it_reproduces_a_failed_arbitrage_exported_by_surfpool() {
+    // The program fails the compute budget check before any CPI
+    let recording = surfpool_recording();
+    recording.replay().assert_matches(&recording.outcome);
+}
@@ -0,0 +1,275 @@
+use crate::dex::{quote_buy, quote_buy_within};
+use crate::events::{ArbitrageEvent, SwapEvent, SwapProgramEvent};
+use crate::instruction::layout::{COMMON_ACCOUNTS, SIGNER_PDA_ACCOUNTS};
+
+use super::dlmm::*;
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::*;
+
+/// Leg accounts of a `DexSwap` instruction, without the common and the PDA accounts.
+fn leg_accounts(ix: Instruction) -> Vec<AccountMeta> {
+    let end = ix.accounts.len() - SIGNER_PDA_ACCOUNTS.len();
+    ix.accounts[COMMON_ACCOUNTS.len()..end].to_vec()
+}
+
+/// `RoundTrip` buying with the `buy` leg and selling with the `sell` leg.
+pub fn round_trip_ix(
+    user: &Pubkey,
+    base_mint_acc: &Pubkey,
+    user_mint_acc: &Pubkey,
+    buy: Instruction,
+    sell: Instruction,
+    ix_data: RoundTripIxData,
+) -> Instruction {
+    let mut accounts = common_accounts(user, base_mint_acc, user_mint_acc);
+    accounts.extend(leg_accounts(buy));
+    accounts.extend(leg_accounts(sell));
+    accounts.extend([
+        AccountMeta::new(operator_limits_address(user), false),
+        AccountMeta::new(pnl_ledger_address(user), false),
+    ]);
+    Instruction {
+        program_id: Pubkey::new_from_array(crate::ID),
+        accounts,
+        data: round_trip_data(ix_data),
+    }
+}
+
+/// Both mocks deployed, a mock DLMM pair and a mock Pump AMM pool of `TEST_MINT`/WSOL.
+pub fn setup_mock_round_trip() -> (LiteSVM, Keypair, DlmmPairKeys, PumpAmmPoolKeys) {
+    let (mut svm, user) = setup_mock(&DLMM_PROGRAM_ID, DLMM_MOCK_PATH);
+    svm.add_program_from_file(PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH)
+        .unwrap_or_else(|_| panic!("{PUMP_AMM_MOCK_PATH} not found, run `cargo build-sbf` first"));
+    let pair = set_mock_dlmm_pair(&mut svm);
+    let pool = set_mock_pump_pool(&mut svm);
+    (svm, user, pair, pool)
+}
+
+#[test]
+fn it_sells_exactly_what_the_dlmm_leg_bought_on_pump_amm() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    svm.warp_to_slot(42);
+    // TEST costs half as much WSOL on the DLMM pair as on the Pump AMM pool
+    set_token_account(&mut svm, &pair.lb_pair, &WSOL_MINT, WSOL_RESERVE / 2);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    // TEST held before the round trip is not sold
+    let held = 5_000;
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, held);
+
+    let amount_in = 1_000_000;
+    let ix = round_trip_ix(
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+        dlmm_swap_ix(&pair, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+        pump_amm_swap_ix(&pool, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+        RoundTripIxData {
+            min_profit_threshold: 100_000,
+            ..round_trip_ix_data(SupportDex::DLMM, SupportDex::PumpAmm, amount_in)
+        },
+    );
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("round trip should have succeeded");
+
+    let (bought, _) = dlmm_mock::quote_swap(WSOL_RESERVE / 2, TOKEN_RESERVE, amount_in).unwrap();
+    let (_, received) = pump_amm_mock::quote_sell(TOKEN_RESERVE, WSOL_RESERVE, bought).unwrap();
+    let profit = received - amount_in;
+    assert_eq!(token_balance(&svm, &user_mint_acc), held);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL + profit);
+    assert_eq!(
+        token_balance(&svm, &pool.pool_base_token_acc),
+        TOKEN_RESERVE + bought
+    );
+
+    assert_eq!(
+        SwapProgramEvent::from_logs(&crate::ID, &res.logs),
+        vec![
+            SwapProgramEvent::Swap(SwapEvent {
+                dex: SupportDex::DLMM as u8,
+                pool: pair.lb_pair.to_bytes(),
+                amount_in,
+                amount_out: bought,
+                slot: 42,
+            }),
+            SwapProgramEvent::Swap(SwapEvent {
+                dex: SupportDex::PumpAmm as u8,
+                pool: pool.pool.to_bytes(),
+                amount_in: bought,
+                amount_out: received,
+                slot: 42,
+            }),
+            SwapProgramEvent::Arbitrage(ArbitrageEvent {
+                dex: SupportDex::DLMM as u8,
+                legs: 2,
+                amount_in,
+                amount_out: received,
+                profit: profit as i64,
+                slot: 42,
+            }),
+        ]
+    );
+}
+
+#[test]
+fn it_buys_on_pump_amm_at_most_the_amount_in() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    svm.warp_to_slot(42);
+    // TEST costs half as much WSOL on the Pump AMM pool as on the DLMM pair
+    let quote_reserve = WSOL_RESERVE / 2;
+    set_token_account(&mut svm, &pool.pool, &WSOL_MINT, quote_reserve);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // Spreading the rounding over the total fee buys more than 1_000_000 pays for
+    let amount_in = 1_000_000;
+    let fee_bps = [
+        pump_amm_mock::LP_FEE_BPS,
+        pump_amm_mock::PROTOCOL_FEE_BPS,
+        pump_amm_mock::COIN_CREATOR_FEE_BPS,
+    ];
+    let overestimate = quote_buy(TOKEN_RESERVE, quote_reserve, amount_in, 30).unwrap();
+    let (_, cost) =
+        pump_amm_mock::quote_buy(TOKEN_RESERVE, quote_reserve, overestimate.amount_out).unwrap();
+    assert!(cost > amount_in);
+    let bought = quote_buy_within(TOKEN_RESERVE, quote_reserve, amount_in, &fee_bps)
+        .unwrap()
+        .amount_out;
+    let (_, spent) = pump_amm_mock::quote_buy(TOKEN_RESERVE, quote_reserve, bought).unwrap();
+    assert!(spent <= amount_in);
+
+    let ix = round_trip_ix(
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+        pump_amm_swap_ix(&pool, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+        dlmm_swap_ix(&pair, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+        round_trip_ix_data(SupportDex::PumpAmm, SupportDex::DLMM, amount_in),
+    );
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("a pump amm buy within amount_in should have succeeded");
+
+    let (received, _) = dlmm_mock::quote_swap(TOKEN_RESERVE, WSOL_RESERVE, bought).unwrap();
+    let profit = received - spent;
+    assert_eq!(token_balance(&svm, &user_mint_acc), 0);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL + profit);
+    assert_eq!(
+        SwapProgramEvent::from_logs(&crate::ID, &res.logs),
+        vec![
+            SwapProgramEvent::Swap(SwapEvent {
+                dex: SupportDex::PumpAmm as u8,
+                pool: pool.pool.to_bytes(),
+                amount_in: spent,
+                amount_out: bought,
+                slot: 42,
+            }),
+            SwapProgramEvent::Swap(SwapEvent {
+                dex: SupportDex::DLMM as u8,
+                pool: pair.lb_pair.to_bytes(),
+                amount_in: bought,
+                amount_out: received,
+                slot: 42,
+            }),
+            SwapProgramEvent::Arbitrage(ArbitrageEvent {
+                dex: SupportDex::PumpAmm as u8,
+                legs: 2,
+                amount_in: spent,
+                amount_out: received,
+                profit: profit as i64,
+                slot: 42,
+            }),
+        ]
+    );
+}
+
+#[test]
+fn it_reverts_a_round_trip_below_the_profit_threshold() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // Same price on both pools, the fees of the two legs make a loss
+    let ix = round_trip_ix(
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+        dlmm_swap_ix(&pair, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+        pump_amm_swap_ix(&pool, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+        round_trip_ix_data(SupportDex::DLMM, SupportDex::PumpAmm, 1_000_000),
+    );
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("a losing round trip should have failed");
+
+    // LossProgramError::ProfitBelowThreshold
+    assert_custom_error(&res.err, 13);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+    assert_eq!(token_balance(&svm, &user_mint_acc), 0);
+}
+
+#[test]
+fn it_reverts_a_profitable_round_trip_below_a_threshold_past_i64() {
+    let (mut svm, user, pair, pool) = setup_mock_round_trip();
+    set_token_account(&mut svm, &pair.lb_pair, &WSOL_MINT, WSOL_RESERVE / 2);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, USER_WSOL);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // 2^63 wrapped to a negative i64 and let every round trip through
+    let ix = round_trip_ix(
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+        dlmm_swap_ix(&pair, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+        pump_amm_swap_ix(&pool, &user.pubkey(), &base_mint_acc, &user_mint_acc),
+        RoundTripIxData {
+            min_profit_threshold: 1 << 63,
+            ..round_trip_ix_data(SupportDex::DLMM, SupportDex::PumpAmm, 1_000_000)
+        },
+    );
+    let res = send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect_err("no round trip makes 2^63 lamports");
+
+    // LossProgramError::ProfitBelowThreshold
+    assert_custom_error(&res.err, 13);
+    assert_eq!(token_balance(&svm, &base_mint_acc), USER_WSOL);
+}
+
+#[test]
+fn it_round_trips_the_fixture_pools() {
+    let (mut svm, user) = setup("pump_amm_pool.json", &PUMP_PROGRAM_ID);
+    SurfpoolSnapshotLoader::load(&format!("{FIXTURES_DIR}/dlmm_pair.json"), &mut svm);
+    SurfpoolSnapshotLoader::load_program(&DLMM_PROGRAM_ID, &mut svm);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    // Buys through the on-chain Pump AMM quote, the snapshots decide the profit
+    let ix = round_trip_ix(
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+        pump_amm_swap_ix(
+            &FIXTURE_POOL,
+            &user.pubkey(),
+            &base_mint_acc,
+            &user_mint_acc,
+        ),
+        dlmm_swap_ix(
+            &FIXTURE_PAIR,
+            &user.pubkey(),
+            &base_mint_acc,
+            &user_mint_acc,
+        ),
+        round_trip_ix_data(SupportDex::PumpAmm, SupportDex::DLMM, 1_000_000),
+    );
+    match send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user) {
+        Ok(_) => {
+            assert_eq!(token_balance(&svm, &user_mint_acc), 0);
+            assert!(token_balance(&svm, &base_mint_acc) >= 1_000_000_000);
+        }
+        Err(res) => {
+            // Both legs ran, only the profit check failed
+            let sell = format!("Program {DLMM_PROGRAM_ID} invoke [2]");
+            assert!(res.meta.logs.contains(&sell));
+            assert_custom_error(&res.err, 13);
+        }
+    }
+}
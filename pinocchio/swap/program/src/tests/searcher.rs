@@ -0,0 +1,237 @@
+use solana_hash::Hash;
+use solana_keypair::Signer;
+use solana_message::AddressLookupTableAccount;
+use solana_transaction::Transaction;
+
+use super::dlmm::*;
+use super::pump_amm::*;
+use super::round_trip::round_trip_ix;
+use super::*;
+use crate::instruction::layout::COMMON_ACCOUNTS;
+use crate::searcher::lookup_table::{
+    compile_v0, extend_instructions, static_accounts, EXTEND_CHUNK_LEN, MAX_ADDRESSES,
+};
+use crate::searcher::route::{search, MAX_BIN_TO_PROCESS};
+use crate::searcher::rpc::is_local_url;
+use crate::searcher::store::AccountStore;
+use crate::searcher::venue::Venue;
+
+/// Largest serialized transaction a leader accepts.
+const PACKET_DATA_SIZE: usize = 1232;
+
+/// The Pump AMM pool and the DLMM pair of the fixtures, both trading `TEST_MINT`.
+fn fixture_venues() -> (Venue, Venue) {
+    let mut store = AccountStore::new(None);
+    for fixture in ["pump_amm_pool", "dlmm_pair"] {
+        store
+            .load_snapshot(&format!("{FIXTURES_DIR}/{fixture}.json"))
+            .unwrap();
+    }
+    let mut venues = Venue::discover(&store)
+        .into_iter()
+        .map(|address| Venue::load(&mut store, address).unwrap());
+    let (first, second) = (venues.next().unwrap(), venues.next().unwrap());
+    assert!(venues.next().is_none());
+    match first.dex() {
+        SupportDex::PumpAmm => (first, second),
+        _ => (second, first),
+    }
+}
+
+#[test]
+fn it_collects_the_static_accounts_of_every_venue_once() {
+    let (pump_amm, dlmm) = fixture_venues();
+    let venues = [pump_amm, dlmm];
+    let keys = static_accounts(&venues, &WSOL_MINT);
+
+    assert_eq!(
+        keys[..4],
+        [
+            WSOL_MINT,
+            TOKEN_PROGRAM_ID,
+            SYSTEM_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID
+        ]
+    );
+    for (index, key) in keys.iter().enumerate() {
+        assert!(!keys[..index].contains(key), "{key} is listed twice");
+    }
+    assert!(keys.contains(&TEST_MINT));
+    for venue in &venues {
+        assert!(keys.contains(&venue.address()));
+    }
+    // The bin arrays follow the active bin and stay out of the table
+    let dlmm_accounts = venues[1].leg_accounts(&WSOL_MINT, true);
+    for bin_array in &dlmm_accounts[6..9] {
+        assert!(!keys.contains(&bin_array.pubkey));
+    }
+    assert!(keys.len() < MAX_ADDRESSES);
+}
+
+#[test]
+fn it_extends_a_lookup_table_in_legacy_sized_chunks() {
+    let authority = Pubkey::new_unique();
+    let table = Pubkey::new_unique();
+    let present: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    let mut keys: Vec<Pubkey> = (0..2 * EXTEND_CHUNK_LEN + 5)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    keys.extend_from_slice(&present);
+    keys.push(keys[0]);
+
+    let extend_ixs = extend_instructions(table, &authority, &present, &keys).unwrap();
+    let chunk_lens: Vec<usize> = extend_ixs.iter().map(|(_, chunk)| chunk.len()).collect();
+    assert_eq!(chunk_lens, [EXTEND_CHUNK_LEN, EXTEND_CHUNK_LEN, 5]);
+    let appended: Vec<Pubkey> = extend_ixs
+        .iter()
+        .flat_map(|(_, chunk)| chunk.iter().copied())
+        .collect();
+    assert_eq!(appended, keys[..2 * EXTEND_CHUNK_LEN + 5]);
+
+    // Each extension goes out as its own signed legacy transaction
+    for (extend_ix, _) in &extend_ixs {
+        let mut transaction =
+            Transaction::new_with_payer(std::slice::from_ref(extend_ix), Some(&authority));
+        transaction.signatures = vec![Default::default(); 1];
+        let len = bincode::serialize(&transaction).unwrap().len();
+        assert!(len <= PACKET_DATA_SIZE, "extension of {len} bytes");
+    }
+
+    assert!(extend_instructions(table, &authority, &keys, &keys)
+        .unwrap()
+        .is_empty());
+    let full: Vec<Pubkey> = (0..MAX_ADDRESSES).map(|_| Pubkey::new_unique()).collect();
+    assert!(extend_instructions(table, &authority, &full, &present).is_err());
+}
+
+#[test]
+fn it_compiles_a_round_trip_under_the_packet_size() {
+    let (pump_amm, dlmm) = fixture_venues();
+    let venues = [pump_amm, dlmm];
+    let payer = Keypair::new();
+    let routes = search(&venues, &WSOL_MINT, &[1_000_000], 0);
+    let instructions = [
+        set_compute_unit_limit(1_400_000),
+        routes[0].round_trip_instruction(&payer.pubkey(), &WSOL_MINT, 1),
+    ];
+
+    let legacy = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::default(),
+    );
+    let legacy_len = bincode::serialize(&legacy).unwrap().len();
+
+    let table = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: static_accounts(&venues, &WSOL_MINT),
+    };
+    let transaction = compile_v0(&payer, &instructions, &[table], Hash::default()).unwrap();
+    let len = bincode::serialize(&transaction).unwrap().len();
+    assert!(len <= PACKET_DATA_SIZE, "v0 round trip of {len} bytes");
+    assert!(len < legacy_len / 2);
+}
+
+#[test]
+fn it_submits_the_whole_cycle_with_the_quoted_amount() {
+    let (pump_amm, dlmm) = fixture_venues();
+    let venues = [pump_amm, dlmm];
+    let user = Pubkey::new_unique();
+    let base_mint_acc = associated_token_address(&user, &WSOL_MINT);
+    let user_mint_acc = associated_token_address(&user, &TEST_MINT);
+    let pump_amm_leg = pump_amm_swap_ix(&FIXTURE_POOL, &user, &base_mint_acc, &user_mint_acc);
+    let dlmm_leg = dlmm_swap_ix(&FIXTURE_PAIR, &user, &base_mint_acc, &user_mint_acc);
+
+    // The sell leg of a DLMM pair passes the loaded bin arrays, which lie on the buy side
+    let leg = |ix: &Instruction| ix.accounts[COMMON_ACCOUNTS.len()..ix.accounts.len() - 2].to_vec();
+    assert_eq!(venues[1].leg_accounts(&WSOL_MINT, false), leg(&dlmm_leg));
+    assert_eq!(
+        venues[0].leg_accounts(&WSOL_MINT, false),
+        leg(&pump_amm_leg)
+    );
+
+    // The fixture pair only has liquidity to sell WSOL for the mint
+    let routes = search(&venues, &WSOL_MINT, &[1_000_000], 0);
+    assert!(!routes.is_empty());
+    for route in &routes {
+        let (buy, sell) = match route.buy.dex() {
+            SupportDex::PumpAmm => (pump_amm_leg.clone(), dlmm_leg.clone()),
+            _ => (dlmm_leg.clone(), pump_amm_leg.clone()),
+        };
+        let expected = round_trip_ix(
+            &user,
+            &base_mint_acc,
+            &user_mint_acc,
+            buy,
+            sell,
+            RoundTripIxData {
+                min_profit_threshold: 7,
+                max_bin_to_process: MAX_BIN_TO_PROCESS,
+                ..round_trip_ix_data(route.buy.dex(), route.sell.dex(), route.buy_quote.amount_in)
+            },
+        );
+        let amount_in = route.buy_quote.amount_in;
+        assert_eq!(amount_in, 1_000_000);
+        assert_eq!(route.round_trip_instruction(&user, &WSOL_MINT, 7), expected);
+    }
+}
+
+#[test]
+fn it_submits_only_a_route_above_the_min_profit() {
+    let (pump_amm, dlmm) = fixture_venues();
+    let venues = [pump_amm, dlmm];
+    let routes = search(&venues, &WSOL_MINT, &[1_000_000], 0);
+    for route in &routes {
+        let gained = route
+            .sell_quote
+            .amount_out
+            .saturating_sub(route.buy_quote.amount_in);
+        assert_eq!(route.meets_min_profit(gained), route.profit() >= 0);
+        assert!(!route.meets_min_profit(gained + 1));
+        assert!(!route.meets_min_profit(u64::MAX));
+    }
+}
+
+#[test]
+fn it_never_pairs_two_venues_of_the_same_dex() {
+    let (pump_amm, dlmm) = fixture_venues();
+    // A second DLMM pair on the same mint, which alone must yield no route
+    let Venue::Dlmm(mut other) = fixture_venues().1 else {
+        unreachable!();
+    };
+    other.address = Pubkey::new_unique();
+    let other = Venue::Dlmm(other);
+    assert_eq!(dlmm.mint(&WSOL_MINT), other.mint(&WSOL_MINT));
+    let dlmm_pairs = [dlmm, other];
+    assert!(search(&dlmm_pairs, &WSOL_MINT, &[1_000_000], 0).is_empty());
+
+    let [dlmm, other] = dlmm_pairs;
+    let venues = [pump_amm, dlmm, other];
+    let routes = search(&venues, &WSOL_MINT, &[1_000_000], 0);
+    assert!(!routes.is_empty());
+    for route in &routes {
+        assert_ne!(route.buy.dex(), route.sell.dex());
+    }
+}
+
+#[test]
+fn it_only_sends_to_a_local_rpc() {
+    for url in [
+        "http://127.0.0.1:8899",
+        "http://localhost:8899/",
+        "http://[::1]:8899",
+        "http://127.0.0.2",
+    ] {
+        assert!(is_local_url(url), "{url}");
+    }
+    for url in [
+        "http://api.mainnet-beta.solana.com",
+        "https://127.0.0.1:8899",
+        "http://localhost.example.com:8899",
+        "http://127.0.0.1@example.com",
+        "http://10.0.0.1:8899",
+    ] {
+        assert!(!is_local_url(url), "{url}");
+    }
+}
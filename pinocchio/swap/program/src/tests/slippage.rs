@@ -0,0 +1,92 @@
+use solana_keypair::Signer;
+
+use super::dlmm::{dlmm_swap_ix, AMOUNT_IN, DLMM_PROGRAM_ID, FIXTURE_PAIR};
+use super::pump_amm::{pump_amm_swap_ix, BASE_AMOUNT_OUT, FIXTURE_POOL, PUMP_PROGRAM_ID};
+use super::*;
+use crate::error::LossProgramError;
+use crate::slippage::{max_amount_in, min_amount_out, SlippageLimit, MAX_SLIPPAGE_BPS};
+
+const SLIPPAGE_BPS: u16 = 50;
+
+#[test]
+fn it_derives_limits_from_the_quote() {
+    assert_eq!(max_amount_in(1_000_000, 50), 1_005_000);
+    assert_eq!(min_amount_out(1_000_000, 50), 995_000);
+    // the slippage part rounds down, so the limit never exceeds the tolerance
+    assert_eq!(max_amount_in(199, 50), 199);
+    assert_eq!(min_amount_out(199, 50), 199);
+    assert_eq!(max_amount_in(u64::MAX, MAX_SLIPPAGE_BPS), u64::MAX);
+    assert_eq!(min_amount_out(1_000, MAX_SLIPPAGE_BPS), 0);
+}
+
+#[test]
+fn it_fails_with_slippage_exceeded_beyond_the_limit() {
+    let max_in = SlippageLimit::max_amount_in(1_000_000, SLIPPAGE_BPS);
+    assert_eq!(
+        max_in,
+        SlippageLimit::MaxAmountIn {
+            quoted: 1_000_000,
+            limit: 1_005_000
+        }
+    );
+    assert_eq!(max_in.check(1_005_000, 0), Ok(()));
+    assert_eq!(
+        max_in.check(1_005_001, u64::MAX),
+        Err(LossProgramError::SlippageExceeded.into())
+    );
+
+    let min_out = SlippageLimit::min_amount_out(1_000_000, SLIPPAGE_BPS);
+    assert_eq!(min_out.check(u64::MAX, 995_000), Ok(()));
+    assert_eq!(
+        min_out.check(0, 994_999),
+        Err(LossProgramError::SlippageExceeded.into())
+    );
+}
+
+#[test]
+fn it_buys_on_pump_amm_within_the_quoted_limit() {
+    let (mut svm, user) = setup("pump_amm_pool.json", &PUMP_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = pump_amm_swap_ix(
+        &FIXTURE_POOL,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.data = dex_swap_data(ArbitrageIxData {
+        slippage_bps: SLIPPAGE_BPS,
+        ..arbitrage_ix_data(SupportDex::PumpAmm)
+    });
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("pump amm swap with slippage should have succeeded");
+
+    assert_eq!(token_balance(&svm, &user_mint_acc), BASE_AMOUNT_OUT);
+}
+
+#[test]
+fn it_swaps_on_dlmm_within_the_quoted_limit() {
+    let (mut svm, user) = setup("dlmm_pair.json", &DLMM_PROGRAM_ID);
+    let base_mint_acc = set_token_account(&mut svm, &user.pubkey(), &WSOL_MINT, 1_000_000_000);
+    let user_mint_acc = set_token_account(&mut svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = dlmm_swap_ix(
+        &FIXTURE_PAIR,
+        &user.pubkey(),
+        &base_mint_acc,
+        &user_mint_acc,
+    );
+    ix.data = dex_swap_data(ArbitrageIxData {
+        slippage_bps: SLIPPAGE_BPS,
+        ..arbitrage_ix_data(SupportDex::DLMM)
+    });
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user)
+        .expect("dlmm swap with slippage should have succeeded");
+
+    assert_eq!(
+        token_balance(&svm, &base_mint_acc),
+        1_000_000_000 - AMOUNT_IN
+    );
+    assert!(token_balance(&svm, &user_mint_acc) > 0);
+}
@@ -0,0 +1,54 @@
+use crate::error::LossProgramError;
+
+use super::mock_cpi::*;
+use super::pump_amm::*;
+use super::*;
+
+const WRAP_LAMPORTS: u64 = 1_000_000;
+/// WSOL the mock pool takes for `BASE_AMOUNT_OUT`, fees included
+const QUOTE_AMOUNT_IN: u64 = 505;
+const SIGNATURE_FEE: u64 = 5_000;
+
+fn wrapping_swap_ix(svm: &mut LiteSVM, user: &Keypair) -> (Instruction, Pubkey, Pubkey) {
+    let keys = set_mock_pump_pool(svm);
+    // No WSOL account yet, the program creates it
+    let base_mint_acc = associated_token_address(&user.pubkey(), &WSOL_MINT);
+    let user_mint_acc = set_token_account(svm, &user.pubkey(), &TEST_MINT, 0);
+
+    let mut ix = pump_amm_swap_ix(&keys, &user.pubkey(), &base_mint_acc, &user_mint_acc);
+    ix.data = dex_swap_data(ArbitrageIxData {
+        wrap_lamports: WRAP_LAMPORTS,
+        ..arbitrage_ix_data(SupportDex::PumpAmm)
+    });
+    (ix, base_mint_acc, user_mint_acc)
+}
+
+#[test]
+fn it_wraps_and_unwraps_sol_around_the_legs() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let (ix, base_mint_acc, user_mint_acc) = wrapping_swap_ix(&mut svm, &user);
+    let lamports_before = svm.get_balance(&user.pubkey()).unwrap();
+
+    send_tx(&mut svm, &[set_compute_unit_limit(400_000), ix], &user).unwrap();
+
+    assert_eq!(token_balance(&svm, &user_mint_acc), BASE_AMOUNT_OUT);
+    // The WSOL account is closed, its rent and the unspent SOL came back
+    assert!(svm
+        .get_account(&base_mint_acc)
+        .is_none_or(|account| account.lamports == 0));
+    assert_eq!(
+        svm.get_balance(&user.pubkey()).unwrap(),
+        lamports_before - QUOTE_AMOUNT_IN - SIGNATURE_FEE
+    );
+}
+
+#[test]
+fn it_only_wraps_into_the_native_mint() {
+    let (mut svm, user) = setup_mock(&PUMP_PROGRAM_ID, PUMP_AMM_MOCK_PATH);
+    let (mut ix, _, _) = wrapping_swap_ix(&mut svm, &user);
+    ix.accounts[1] = AccountMeta::new_readonly(TEST_MINT, false);
+
+    let res = send_tx(&mut svm, &[ix], &user).expect_err("TEST is not native SOL");
+
+    assert_custom_error(&res.err, LossProgramError::BaseMintNotNative as u32);
+}
@@ -0,0 +1,84 @@
+//! SOL 自动包装
+//!
+//! With `ArbitrageIxData::wrap_lamports` set, the signer pays in native SOL:
+//! the WSOL ATA is created if needed, funded and synced before the legs, then
+//! closed afterwards so the remaining WSOL and the rent return to the signer.
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+use pinocchio_token::instructions::{CloseAccount, SyncNative};
+
+use crate::ata;
+use crate::error::LossProgramError;
+
+/// Native SOL 的 mint
+pub const NATIVE_MINT: Pubkey =
+    pinocchio_pubkey::pubkey!("So11111111111111111111111111111111111111112");
+
+/// 包装 SOL 所需的账户
+pub struct WrappedSol<'a> {
+    signer: &'a AccountInfo,
+    native_mint: &'a AccountInfo,
+    wsol_account: &'a AccountInfo,
+    token_program: &'a AccountInfo,
+    system_program: &'a AccountInfo,
+}
+
+impl<'a> WrappedSol<'a> {
+    /// `accounts` are the leading instruction accounts shared by every leg
+    pub fn load(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [signer, native_mint, _, wsol_account, token_program, system_program, associated_token_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if native_mint.key() != &NATIVE_MINT {
+            return Err(LossProgramError::BaseMintNotNative.into());
+        }
+        if token_program.key() != &pinocchio_token::ID
+            || system_program.key() != &pinocchio_system::ID
+            || associated_token_program.key() != &pinocchio_associated_token_account::ID
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Ok(Self {
+            signer,
+            native_mint,
+            wsol_account,
+            token_program,
+            system_program,
+        })
+    }
+
+    /// 创建 WSOL ATA (如不存在), 转入 `lamports` 并同步余额
+    pub fn wrap(&self, lamports: u64) -> ProgramResult {
+        ata::create_if_missing(
+            self.wsol_account,
+            self.signer,
+            self.native_mint,
+            self.system_program,
+            self.token_program,
+        )?;
+        Transfer {
+            from: self.signer,
+            to: self.wsol_account,
+            lamports,
+        }
+        .invoke()?;
+        SyncNative {
+            native_token: self.wsol_account,
+        }
+        .invoke()
+    }
+
+    /// 关闭 WSOL ATA, 剩余 WSOL 与租金以 SOL 退回 signer
+    pub fn unwrap(&self) -> ProgramResult {
+        CloseAccount {
+            account: self.wsol_account,
+            destination: self.signer,
+            authority: self.signer,
+        }
+        .invoke()
+    }
+}